@@ -0,0 +1,613 @@
+//! First order equations built backwards from a chosen solution: separable,
+//! homogeneous, linear, Bernoulli and exact ones with small integer parameters.
+//! The equation, the answer and the primitives of the worked solution are `Expr`s in
+//! `x` and `y` (and `z` after a substitution), printed as they are checked by
+//! `verify::verify_answer`.
+
+use rand::prelude::*;
+use variant_generation::expr::{
+    Expr, Q, arctan, cos, exp, ln_abs, num, rational, sin, sum, tg, var,
+};
+
+pub struct Equation {
+    /// Family and parameters, for the fingerprint.
+    pub choice: String,
+    /// Typst markup.
+    pub problem: String,
+    pub solution: String,
+    /// The printed equation is `y' = rhs(x, y)`.
+    pub rhs: Expr,
+    pub answer: Answer,
+    /// The primitives found in the worked solution.
+    pub primitives: Vec<Primitive>,
+    /// `(x, y)` near the solutions.
+    pub sample_points: Vec<(f64, f64)>,
+    /// `y' = rhs(x, y)` of the wrong options, the answer must not solve them.
+    pub distractors: Vec<Expr>,
+}
+
+/// The answer `lhs(x, y) = rhs(x, constant)`, where `rhs` is affine in the constant.
+pub struct Answer {
+    pub lhs: Expr,
+    pub rhs: Expr,
+    pub constant: &'static str,
+}
+
+/// `primitive' = integrand` in the variable `var`.
+pub struct Primitive {
+    pub var: &'static str,
+    pub integrand: Expr,
+    pub primitive: Expr,
+}
+
+impl Answer {
+    fn as_typst(&self) -> String {
+        format!("{} = {}", self.lhs, self.rhs)
+    }
+}
+
+const COEFFICIENTS: [i64; 6] = [-3, -2, -1, 1, 2, 3];
+
+fn x() -> Expr {
+    var("x")
+}
+
+fn y() -> Expr {
+    var("y")
+}
+
+/// `k t`.
+fn times(k: i64, t: Expr) -> Expr {
+    num(k) * t
+}
+
+/// A random option other than `excluded`.
+fn choose_other(options: &[i64], excluded: i64, rng: &mut impl Rng) -> i64 {
+    let options = options
+        .iter()
+        .filter(|&&o| o != excluded)
+        .collect::<Vec<_>>();
+    **options.choose(rng).unwrap()
+}
+
+fn problem(equation: &str) -> String {
+    format!("= Решите следующее дифференциальное уравнение:\n$\n{equation}\n$\n")
+}
+
+/// `y' = k u(x)/v(y)`, solved as `V(y) = k U(x) + c` with `V' = v`, `U' = u`.
+pub fn separable(rng: &mut impl Rng) -> Equation {
+    let k = *COEFFICIENTS.choose(rng).unwrap();
+    let a = *[2, 3].choose(rng).unwrap();
+    let b = *[1, 2].choose(rng).unwrap();
+    let (kind_x, kind_y) = (rng.random_range(0..5), rng.random_range(0..6));
+
+    let ax = times(a, x());
+    let (u, big_u) = match kind_x {
+        0 => (
+            x().pow(a as i32),
+            rational(Q::new(1, a + 1)) * x().pow(a as i32 + 1),
+        ),
+        1 => (exp(ax.clone()), rational(Q::new(1, a)) * exp(ax)),
+        2 => (cos(ax.clone()), rational(Q::new(1, a)) * sin(ax)),
+        3 => (sin(ax.clone()), rational(Q::new(-1, a)) * cos(ax)),
+        _ => (
+            x() * exp(x().pow(2)),
+            rational(Q::new(1, 2)) * exp(x().pow(2)),
+        ),
+    };
+    let by = times(b, y());
+    let (v, big_v) = match kind_y {
+        0 => (
+            y().pow(b as i32),
+            rational(Q::new(1, b + 1)) * y().pow(b as i32 + 1),
+        ),
+        1 => (exp(by.clone()), rational(Q::new(1, b)) * exp(by)),
+        2 => (cos(y()), sin(y())),
+        3 => (num(1) / y(), ln_abs(y())),
+        4 => (num(1) / (num(1) + y().pow(2)), arctan(y())),
+        _ => (num(1) / cos(y()).pow(2), tg(y())),
+    };
+
+    let rhs = times(k, u.clone()) / v.clone();
+    let equation = format!("y' = {rhs}");
+    let answer = Answer {
+        lhs: big_v.clone(),
+        rhs: times(k, big_u.clone()) + var("c"),
+        constant: "c",
+    };
+    let solution = format!(
+        "= Решите ${equation}$\n\n\
+         Разделим переменные: ${v} dif y = {} dif x$.\n\n\
+         Проинтегрировав, получим ${}$.\n",
+        times(k, u.clone()),
+        answer.as_typst(),
+    );
+
+    Equation {
+        choice: format!("separable {kind_x} {kind_y} a={a} b={b} k={k}"),
+        problem: problem(&equation),
+        solution,
+        rhs,
+        answer,
+        primitives: vec![
+            Primitive {
+                var: "x",
+                integrand: u,
+                primitive: big_u,
+            },
+            Primitive {
+                var: "y",
+                integrand: v,
+                primitive: big_v,
+            },
+        ],
+        sample_points: grid(&[0.3, 0.6, 0.9], &[0.4, 0.8, 1.2]),
+        distractors: vec![],
+    }
+}
+
+/// `y' = y/x + k/v(y/x)`: the substitution `y = x z` gives `x z' = k/v(z)`, solved
+/// as `V(z) = k ln abs(x) + c` with `V' = v`.
+pub fn homogeneous(rng: &mut impl Rng) -> Equation {
+    let k = *COEFFICIENTS.choose(rng).unwrap();
+    let b = *[1, 2].choose(rng).unwrap();
+    let kind = rng.random_range(0..5);
+
+    // `v(t)` and `V(t)`, for `t = z` and `t = y/x`.
+    let v = |t: Expr| match kind {
+        0 => cos(t),
+        1 => num(1) / cos(t).pow(2),
+        2 => exp(times(b, t)),
+        3 => t,
+        _ => num(1) / (num(1) + t.pow(2)),
+    };
+    let big_v = |t: Expr| match kind {
+        0 => sin(t),
+        1 => tg(t),
+        2 => rational(Q::new(1, b)) * exp(times(b, t)),
+        3 => rational(Q::new(1, 2)) * t.pow(2),
+        _ => arctan(t),
+    };
+    let z = var("z");
+    let ratio = y() / x();
+
+    let rhs = ratio.clone() + num(k) / v(ratio.clone());
+    let equation = format!("y' = {rhs}");
+    let log = times(k, ln_abs(x()));
+    let answer = Answer {
+        lhs: big_v(ratio),
+        rhs: log.clone() + var("c"),
+        constant: "c",
+    };
+    let solution = format!(
+        "= Решите ${equation}$\n\n\
+         Уравнение однородное, сделаем замену $y = x z$, $y' = z + x z'$: $x z' = {}$.\n\n\
+         Разделим переменные: ${} dif z = {} dif x$.\n\n\
+         Проинтегрировав, получим ${} = {}$, то есть ${}$.\n",
+        num(k) / v(z.clone()),
+        v(z.clone()),
+        num(k) / x(),
+        big_v(z.clone()),
+        answer.rhs,
+        answer.as_typst(),
+    );
+
+    let sample_points = [0.5, 1., 1.5]
+        .into_iter()
+        .flat_map(|x| [0.3, 0.7, 1.1].map(|z| (x, x * z)))
+        .collect();
+    Equation {
+        choice: format!("homogeneous {kind} b={b} k={k}"),
+        problem: problem(&equation),
+        solution,
+        rhs,
+        answer,
+        primitives: vec![
+            Primitive {
+                var: "z",
+                integrand: v(z.clone()),
+                primitive: big_v(z),
+            },
+            Primitive {
+                var: "x",
+                integrand: num(k) / x(),
+                primitive: log,
+            },
+        ],
+        sample_points,
+        distractors: vec![],
+    }
+}
+
+/// `lead var' + coeff var = right`, solved by the variation of the constant: the
+/// homogeneous equation has the solution `c y0`, the ansatz `c(x) y0` gives `c'(x)`
+/// with the primitive `c`.
+struct Linear {
+    var: &'static str,
+    derivative: &'static str,
+    lead: Expr,
+    coeff: Expr,
+    right: Expr,
+    y0: Expr,
+    c: Expr,
+}
+
+impl Linear {
+    fn lhs(&self) -> Expr {
+        self.lead.clone() * var(self.derivative) + self.coeff.clone() * var(self.var)
+    }
+
+    fn equation(&self) -> String {
+        format!("{} = {}", self.lhs(), self.right)
+    }
+
+    /// `var' = rhs(x, var)`.
+    fn rhs(&self) -> Expr {
+        (self.right.clone() - self.coeff.clone() * var(self.var)) / self.lead.clone()
+    }
+
+    fn c_derivative(&self) -> Expr {
+        self.right.clone() / (self.lead.clone() * self.y0.clone())
+    }
+
+    /// `c_1 y0 + c y0`.
+    fn general(&self) -> Expr {
+        var("c_1") * self.y0.clone() + self.c.clone() * self.y0.clone()
+    }
+
+    fn answer(&self) -> Answer {
+        Answer {
+            lhs: var(self.var),
+            rhs: self.general(),
+            constant: "c_1",
+        }
+    }
+
+    fn steps(&self) -> String {
+        format!(
+            "Решение однородного уравнения ${} = 0$: ${} = {}$.\n\n\
+             Ищем решение в виде ${} = {}$: ${} = {}$, откуда $c'(x) = {}$, \
+             $c(x) = {} + c_1$.\n\n",
+            self.lhs(),
+            self.var,
+            var("c") * self.y0.clone(),
+            self.var,
+            var("c(x)") * self.y0.clone(),
+            var("c'(x)") * self.lead.clone() * self.y0.clone(),
+            self.right,
+            self.c_derivative(),
+            self.c,
+        )
+    }
+
+    fn primitive(&self) -> Primitive {
+        Primitive {
+            var: "x",
+            integrand: self.c_derivative(),
+            primitive: self.c.clone(),
+        }
+    }
+}
+
+/// `x var' + a var = q x^m` with the homogeneous solution `c x^(-a)` and
+/// `c(x) = q/(m + a) x^(m + a)`.
+fn power_linear(var: &'static str, derivative: &'static str, a: i64, q: i64, m: i64) -> Linear {
+    let a32 = a as i32;
+    let m32 = m as i32;
+    Linear {
+        var,
+        derivative,
+        lead: x(),
+        coeff: num(a),
+        right: num(q) * x().pow(m32),
+        y0: x().pow(-a32),
+        c: rational(Q::new(q, m + a)) * x().pow(m32 + a32),
+    }
+}
+
+/// `y' + p(x) y = q(x)`: `p = a/x` (written as `x y' + a y = q`), `p = a` or
+/// `p = ±tg x`, with a particular solution `b x^m`, `b e^(m x)` or `b x cos^(±1) x`.
+pub fn linear(rng: &mut impl Rng) -> Equation {
+    let a = *COEFFICIENTS.choose(rng).unwrap();
+    let b = *COEFFICIENTS.choose(rng).unwrap();
+    let kind = rng.random_range(0..3);
+
+    let (choice, linear) = match kind {
+        0 => {
+            let m = choose_other(&[1, 2, 3], -a, rng);
+            (
+                format!("linear x a={a} b={b} m={m}"),
+                power_linear("y", "y'", a, b * (m + a), m),
+            )
+        }
+        1 => {
+            let m = choose_other(&[-2, -1, 1, 2, 3], -a, rng);
+            (
+                format!("linear const a={a} b={b} m={m}"),
+                Linear {
+                    var: "y",
+                    derivative: "y'",
+                    lead: num(1),
+                    coeff: num(a),
+                    right: num(b * (m + a)) * exp(times(m, x())),
+                    y0: exp(times(-a, x())),
+                    c: num(b) * exp(times(m + a, x())),
+                },
+            )
+        }
+        _ if a > 0 => (
+            format!("linear tg b={b}"),
+            Linear {
+                var: "y",
+                derivative: "y'",
+                lead: num(1),
+                coeff: tg(x()),
+                right: num(b) * cos(x()),
+                y0: cos(x()),
+                c: times(b, x()),
+            },
+        ),
+        _ => (
+            format!("linear -tg b={b}"),
+            Linear {
+                var: "y",
+                derivative: "y'",
+                lead: num(1),
+                coeff: -tg(x()),
+                right: num(b) / cos(x()),
+                y0: num(1) / cos(x()),
+                c: times(b, x()),
+            },
+        ),
+    };
+
+    let equation = linear.equation();
+    let answer = linear.answer();
+    let solution = format!(
+        "= Решите ${equation}$\n\n\
+         Уравнение линейное, решим его методом вариации постоянной.\n\n\
+         {}Ответ: ${}$.\n",
+        linear.steps(),
+        answer.as_typst(),
+    );
+    Equation {
+        choice,
+        problem: problem(&equation),
+        solution,
+        rhs: linear.rhs(),
+        answer,
+        primitives: vec![linear.primitive()],
+        sample_points: grid(&[0.3, 0.7, 1.1], &[-1., 0.5, 2.]),
+        distractors: vec![],
+    }
+}
+
+/// `x y' + a y = b x^m y^n` with `n = 2, 3, -1`: the substitution `z = y^(1 - n)`
+/// gives the linear `x z' + (1 - n) a z = (1 - n) b x^m`.
+pub fn bernoulli(rng: &mut impl Rng) -> Equation {
+    let a = *[-2, -1, 1, 2].choose(rng).unwrap();
+    let b = *COEFFICIENTS.choose(rng).unwrap();
+    let n = *[2, 3, -1].choose(rng).unwrap();
+    let nu = 1 - n;
+    let m = choose_other(&[1, 2, 3], -nu * a, rng);
+
+    let right = num(b) * x().pow(m as i32) * y().pow(n as i32);
+    let linear = power_linear("z", "z'", nu * a, nu * b, m);
+    let z = y().pow(nu as i32);
+    let factor = num(nu) * y().pow(-n as i32);
+
+    let equation = format!("{} = {right}", x() * var("y'") + times(a, y()));
+    let answer = Answer {
+        lhs: z.clone(),
+        rhs: linear.general(),
+        constant: "c_1",
+    };
+    let solution = format!(
+        "= Решите ${equation}$\n\n\
+         Уравнение Бернулли. Умножим его на ${factor}$ и сделаем замену $z = {z}$, \
+         $z' = {factor} y'$: получим линейное уравнение ${}$.\n\n\
+         {}Ответ: ${}$.\n",
+        linear.equation(),
+        linear.steps(),
+        answer.as_typst(),
+    );
+
+    Equation {
+        choice: format!("bernoulli a={a} b={b} n={n} m={m}"),
+        problem: problem(&equation),
+        solution,
+        rhs: (right - times(a, y())) / x(),
+        answer,
+        primitives: vec![linear.primitive()],
+        sample_points: grid(&[0.5, 1., 1.5], &[0.5, 1.2]),
+        distractors: vec![],
+    }
+}
+
+/// Factor of a summand of the potential of an exact equation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Atom {
+    Power(i64),
+    Exp(i64),
+    Sin,
+    Cos,
+}
+
+impl Atom {
+    fn expr(self, t: Expr) -> Expr {
+        match self {
+            Atom::Power(n) => t.pow(n as i32),
+            Atom::Exp(k) => exp(times(k, t)),
+            Atom::Sin => sin(t),
+            Atom::Cos => cos(t),
+        }
+    }
+
+    /// `(c, atom)` for the derivative `c atom`.
+    fn derivative(self) -> (i64, Atom) {
+        match self {
+            Atom::Power(0) => (0, Atom::Power(0)),
+            Atom::Power(n) => (n, Atom::Power(n - 1)),
+            Atom::Exp(k) => (k, Atom::Exp(k)),
+            Atom::Sin => (1, Atom::Cos),
+            Atom::Cos => (-1, Atom::Sin),
+        }
+    }
+
+    fn random_transcendental(rng: &mut impl Rng) -> Self {
+        match rng.random_range(0..3) {
+            0 => Atom::Exp(*[-2, -1, 1, 2].choose(rng).unwrap()),
+            1 => Atom::Sin,
+            _ => Atom::Cos,
+        }
+    }
+}
+
+/// `coeff x y` for atoms `x` of `x` and `y` of `y`.
+#[derive(Debug, Clone, Copy)]
+struct Summand {
+    coeff: i64,
+    x: Atom,
+    y: Atom,
+}
+
+impl Summand {
+    fn expr(self) -> Expr {
+        num(self.coeff) * self.x.expr(x()) * self.y.expr(y())
+    }
+
+    /// `∂/∂x`.
+    fn dx(self) -> Expr {
+        let (c, dx) = self.x.derivative();
+        num(self.coeff * c) * dx.expr(x()) * self.y.expr(y())
+    }
+
+    fn dy(self) -> Expr {
+        let (c, dy) = self.y.derivative();
+        num(self.coeff * c) * self.x.expr(x()) * dy.expr(y())
+    }
+}
+
+/// `M dif x + N dif y = 0` with `M = ∂Φ/∂x`, `N = ∂Φ/∂y` for a potential `Φ` of a
+/// polynomial, a mixed transcendental and a single-variable summand, among two wrong
+/// options with the sign of a mixed summand changed in `M` or in `N`.
+pub fn exact(rng: &mut impl Rng) -> Equation {
+    let mut coeff = || *COEFFICIENTS.choose(rng).unwrap();
+    let coeffs = [coeff(), coeff(), coeff()];
+
+    let polynomial = Summand {
+        coeff: coeffs[0],
+        x: Atom::Power(rng.random_range(1..=3)),
+        y: Atom::Power(rng.random_range(1..=2)),
+    };
+    let transcendental = Atom::random_transcendental(rng);
+    let monomial = Atom::Power(rng.random_range(1..=2));
+    let mixed = if rng.random_bool(0.5) {
+        Summand {
+            coeff: coeffs[1],
+            x: monomial,
+            y: transcendental,
+        }
+    } else {
+        Summand {
+            coeff: coeffs[1],
+            x: transcendental,
+            y: monomial,
+        }
+    };
+    let single = if rng.random_bool(0.7) {
+        Atom::random_transcendental(rng)
+    } else {
+        Atom::Power(3)
+    };
+    let single = if rng.random_bool(0.5) {
+        Summand {
+            coeff: coeffs[2],
+            x: single,
+            y: Atom::Power(0),
+        }
+    } else {
+        Summand {
+            coeff: coeffs[2],
+            x: Atom::Power(0),
+            y: single,
+        }
+    };
+    let summands = [polynomial, mixed, single];
+
+    // Signs of the summands in `M` and `N`, the first option is the exact one.
+    let mut flips = vec![(0, false), (0, true), (1, false), (1, true)];
+    flips.shuffle(rng);
+    let side = |signs: [i64; 3], part: fn(Summand) -> Expr| {
+        sum(summands.iter().zip(signs).map(|(s, t)| num(t) * part(*s)))
+    };
+    let options = std::iter::once(None)
+        .chain(flips.into_iter().take(2).map(Some))
+        .map(|flip| {
+            let mut m_signs = [1; 3];
+            let mut n_signs = [1; 3];
+            match flip {
+                Some((i, false)) => m_signs[i] = -1,
+                Some((i, true)) => n_signs[i] = -1,
+                None => {}
+            }
+            (side(m_signs, Summand::dx), side(n_signs, Summand::dy))
+        })
+        .collect::<Vec<_>>();
+    let printed = options
+        .iter()
+        .map(|(m, n)| {
+            if rng.random_bool(0.5) {
+                format!("({m}) dif x + ({n}) dif y = 0")
+            } else {
+                format!("({m}) dif x = ({}) dif y", -n.clone())
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut order = [0, 1, 2];
+    order.shuffle(rng);
+    let letters = ["a", "b", "c"];
+    let letter = letters[order.iter().position(|&i| i == 0).unwrap()];
+    let listed = order
+        .iter()
+        .map(|&i| format!("== ${}$", printed[i]))
+        .collect::<Vec<_>>()
+        .join(",\n\n");
+
+    let potential = sum(summands.iter().map(|s| s.expr()));
+    let answer = Answer {
+        lhs: potential.clone(),
+        rhs: var("c"),
+        constant: "c",
+    };
+    let problem = format!(
+        "= #[\n  Среди следующих дифференциальных уравнений укажите уравнения в полных\n  \
+         дифференциалах и решите их.\n]\n\n{listed}.\n"
+    );
+    let (m, n) = &options[0];
+    let solution = format!(
+        "= Уравнением в полных дифференциалах является только уравнение ({letter})\n\n\
+         Действительно, $dif ({potential}) = ({m}) dif x + ({n}) dif y$.\n\n\
+         Его решение: ${}$\n",
+        answer.as_typst(),
+    );
+
+    let slope = |(m, n): &(Expr, Expr)| -(m.clone() / n.clone());
+    Equation {
+        choice: format!("exact {potential}"),
+        problem,
+        solution,
+        rhs: slope(&options[0]),
+        answer,
+        primitives: vec![],
+        sample_points: grid(&[0.3, 0.8, 1.3], &[0.4, 0.9, 1.4]),
+        distractors: options[1..].iter().map(slope).collect(),
+    }
+}
+
+fn grid(xs: &[f64], ys: &[f64]) -> Vec<(f64, f64)> {
+    xs.iter()
+        .flat_map(|&x| ys.iter().map(move |&y| (x, y)))
+        .collect()
+}
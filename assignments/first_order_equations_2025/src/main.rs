@@ -2,6 +2,9 @@
 // mod linear_equation_old;
 //
 //
+mod families;
+mod verify;
+
 use std::io::{self, Read};
 
 use base64::prelude::*;
@@ -15,9 +18,18 @@ use typst::{
 };
 use typst_as_lib::typst_kit_options::TypstKitFontOptions;
 use typst_render::render;
-use variant_generation::{VariantGeneratorInput, VariantGeneratorOutput};
+use variant_generation::{
+    VariantGeneratorInput, VariantGeneratorOutput, fingerprint, least_overlapping,
+};
 
 use indoc::indoc;
+use verify::verify_answer;
+
+/// Random variants compared with the issued ones.
+const CANDIDATES: usize = 100;
+
+/// Rejected tasks are logged and generated again, up to this many times.
+const MAX_ATTEMPTS: usize = 100;
 
 #[derive(Debug, Clone, IntoValue, IntoDict)]
 struct Content {
@@ -41,6 +53,14 @@ struct Task {
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     pretty_env_logger::init();
+
+    let args = std::env::args().collect::<Vec<_>>();
+    if let [_, flag, count] = args.as_slice()
+        && flag == "--self-test"
+    {
+        return self_test(count.parse()?);
+    }
+
     let mut rng = rand::rng();
 
     let mut buffer = String::new();
@@ -50,209 +70,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         VariantGeneratorInput {
             variant_number: 666,
             generator: "test_assignment".to_string(),
+            parameters: serde_json::Value::Null,
+            issued_fingerprints: vec![],
         }
     });
 
-    let task1_variants = vec![
-        Task {
-            problem: indoc!(
-                "
-                 = Решите следующее дифференциальное уравнение:
-                 $
-                 (x^2 - 1) y' - 1 = y^2
-                 $
-                 "
-            )
-            .to_string(),
-            solution: indoc!(
-                "
-                = Решите $(x^2 - 1) y' - 1 = y^2$
-
-                Разделим переменные: $ (dif y) / (1 + y^2) = (dif x) / (x^2 - 1) $.
-
-                Проинтегрировав, получим $arctan y = 1/2 ln abs((x-1)/(x+1)) + c$.
-
-                Можно выразить $y = tan (1/2 ln abs((x-1)/(x+1)) + c)$.
-            "
-            )
-            .to_string(),
-        },
-        Task {
-            problem: indoc!(
-                "
-                         = Решите следующее дифференциальное уравнение:
-                         $
-                         6 x y' = ln(x)/y^2
-                         $
-                         "
-            )
-            .to_string(),
-            solution: indoc!(
-                "
-                = Решите $3 x y' = 2 ln(x)/y^2$
-
-                Разделим переменные: $3 y^2 dif y = 2 (ln x)/x dif x$
-
-                Проинтегрировав, получим $y^3 = ln^2 x + c$.
-
-                Можно выразить $y = root(3, ln^2 x + c)$.
-                "
-            )
-            .to_string(),
-        },
-        Task {
-            problem: indoc!(
-                "
-                         = Решите следующее дифференциальное уравнение:
-                         $
-                         y'/sin(x) = x/cos(y)
-                         $
-                         "
-            )
-            .to_string(),
-            solution: indoc!(
-                "
-                    = Решите $y'/sin(x) = x/cos(y)$
-
-                    Разделим переменные: $cos(y) dif y = sin(x) dif x$
-
-                    Проинтегрировав, получим $sin(y) = cos(x) + c$.
-                    "
-            )
-            .to_string(),
-        },
-        Task {
-            problem: indoc!(
-                "
-                         = Решите следующее дифференциальное уравнение:
-                         $
-                         y' = x y^(-1) e^(x - y)
-                         $
-                         "
-            )
-            .to_string(),
-            solution: indoc!(
-                "
-                    = Решите $y' = x y^(-1) e^(x - y)$
-
-                    Разделим переменные: $y e^y dif y = x e^x dif x$
-
-                    Проинтегрировав по частям, получим $(y - 1)e^y = (x - 1)e^x + c$.
-                    "
-            )
-            .to_string(),
-        },
-    ];
-
-    let task2_variants = vec![
-        Task {
-            problem: indoc!(
-                "
-                = #[
-                  Среди следующих дифференциальных уравнений укажите уравнения в полных 
-                  дифференциалах и решите их. 
-                ]
-
-                == $(y e^x + 2 x ln y) dif x - (x^2/y + e^x + cos(y)) dif y = 0$, 
-
-                == $(y e^x - 2 x ln y) dif x = (x^2/y - e^x - cos(y)) dif y$,  // y e^x - x^2 ln (y) + sin(y)
-
-                == $(y e^x - 2 x ln y) dif x + (x^2/y - e^x + cos(y)) dif y = 0$,
-                         "
-            )
-            .to_string(),
-            solution: indoc!(
-                "
-                = Уравнением в полных дифференциалах является только уравнение (b)
-
-                Его решение: $y e^x - x^2 ln (y) + sin(y) = c$
-                "
-            )
-            .to_string(),
-        },
-        Task {
-            problem: indoc!(
-                "
-                = #[
-                  Среди следующих дифференциальных уравнений укажите уравнения в полных 
-                  дифференциалах и решите их. 
-                ]
-
-
-                == $(e^(x - y) - sin(x)/y + 1) dif x + (cos(x)/y^2 - e^(x - y) - 1) dif y = 0$, 
-
-                == $(e^(x - y) - sin(x)/y - 1) dif x + (cos(x)/y^2 + e^(x - y) - 1) dif y = 0$, 
-
-                == $(e^(x - y) - sin(x)/y + 1) dif x = (cos(x)/y^2 + e^(x - y) + 1) dif y$, // cos(x)/y + e^(x - y) + x - y
-                         "
-            )
-            .to_string(),
-            solution: indoc!(
-                "
-                = Уравнением в полных дифференциалах является только уравнение (c)
-
-                Его решение: $cos(x)/y + e^(x - y) + x - y = c$
-                "
-            )
-            .to_string(),
-        },
-        Task {
-            problem: indoc!(
-                "
-                = #[
-                  Среди следующих дифференциальных уравнений укажите уравнения в полных 
-                  дифференциалах и решите их. 
-                ]
-
-
-                == $(2 x y^2 - (2 x + 1) e^(2 x) + 2 y e^(2 x) ) dif x = ( e^(2 x) + 2 x^2 y) dif y$,
-
-                // $x^2 y^2 + x e^(2 x) + y e^(2 x)$
-                == $(2 x y^2 + (2 x + 1) e^(2 x) + 2 y e^(2 x) ) dif x + (e^(2 x) + 2 x^2 y) dif y = 0$, 
-
-                == $(2 x y^2 - (2 x + 1) e^(2 x) + 2 y e^(2 x) ) dif x + (e^(2 x) - 2 x^2 y) dif y = 0$.
-                         "
-            )
-            .to_string(),
-            solution: indoc!(
-                "
-                = Уравнением в полных дифференциалах является только уравнение (b)
-
-                Его решение: $x^2 y^2 + x e^(2 x) + y e^(2 x) = c$
-                "
-            )
-            .to_string(),
-        },
-        Task {
-            problem: indoc!(
-                "
-                = #[
-                  Среди следующих дифференциальных уравнений укажите уравнения в полных 
-                  дифференциалах и решите их. 
-                ]
-
-
-                  == $(1/(x e^y) + x^2 cos(y)) dif x + (ln(x)/e^y + 2 y^2 + x^3/3 sin(y)) dif y = 0$,
-
-                  == $(1/(x e^y) + x^2 cos(y)) dif x = (ln(x)/e^y + 2 y + x^3 sin(y)) dif y$,
-
-                  // ln(x) e^(-y) + 1/3 x^3 cos(y) - (y+1)^2
-                  == $(1/(x e^y) + x^2 cos(y)) dif x = (ln(x)/e^y + 2 y + x^3/3 sin(y) + 2) dif y$,
-                         "
-            )
-            .to_string(),
-            solution: indoc!(
-                "
-                = Уравнением в полных дифференциалах является только уравнение (c)
-
-                Его решение: $ln(x) e^(-y) + 1/3 x^3 cos(y) - (y+1)^2 = c$
-                "
-            )
-            .to_string(),
-        },
-    ];
-
-    let task3_variants = vec![
+    // Reduction to a homogeneous equation by a shift.
+    let reduction_variants = [
         Task {
             problem: indoc!(
                 "
@@ -338,114 +162,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             .to_string(),
         },
     ];
-    let task4_variants = vec![
-        Task {
-            problem: indoc!(
-                "
-                = Какие из следующих уравнений являются однородными?  Найдите решения тех, которые являются однородными. 
-
-                #set math.lr(size: 100%)
-                == $y' - (cos^2(y))/(cos^2(x)) = y/x$; #h(1fr)
-
-                == $y' - cos^2(y/x) = y/x$; #h(1fr) //tg(y/x) = ln(x) + c
-
-                == $y'/x - cos^2(y/x) = y/x$; #h(1fr) 
-
-                "
-            )
-            .to_string(),
-            solution: indoc!(
-                "
-                = Однородное уравнение только (b). Его решение: $tg(y/x) = ln(x) + c$
-                "
-            )
-            .to_string(),
-        },
-        Task {
-            problem: indoc!(
-                "
-                = Какие из следующих уравнений являются однородными?  Найдите решения тех, которые являются однородными. 
-
-                #set math.lr(size: 100%)
-                == $y'/x = 2 e^(-y/(2x)) + y/x$; #h(1fr)
-                == $y' = 2 e^(-y/(2x)) + y/x$; #h(1fr) // e^(y/(2 x)) = ln(x) + c
-                == $y' = (2 e^(-y))/e^(2x) + y/x$; #h(1fr) 
-
-                "
-            )
-            .to_string(),
-            solution: indoc!(
-                "
-                = Однородное уравнение только (b). Его решение: $e^(y/(2 x)) = ln(x) + c$
-                "
-            )
-            .to_string(),
-        },
-        Task {
-            problem: indoc!(
-                "
-                = Какие из следующих уравнений являются однородными?  Найдите решения тех, которые являются однородными. 
-
-                #set math.lr(size: 100%)
-                == $(y' - y)cos(y/x) = x$; #h(1fr) 
-                == $(y' - y)/x^2 cos(y/x) = 1/y$; #h(1fr) 
-
-                == $y' = ( y cos(y/x) + x)/(x cos(y/x))$. #h(1fr)// sin(y / x) = ln(x) + c
-
-                "
-            )
-            .to_string(),
-            solution: indoc!(
-                "
-                = Однородное уравнение только (c). Его решение: $sin(y / x) = ln(x) + c$
-                "
-            )
-            .to_string(),
-        },
-
-        Task {
-            problem: indoc!(
-                "
-                = Какие из следующих уравнений являются однородными?  Найдите решения тех, которые являются однородными. 
-
-                == $(y' - y)sin(y/x) = x$; #h(1fr) 
-                == $y' = ( y sin(y/x) + x)/(x sin(y/x))$. #h(1fr)// cos(y / x) = ln(x) + c
-
-                == $(y' - y)/x^2 sin(y/x) = 1/y$; #h(1fr) 
-
-                "
-            )
-            .to_string(),
-            solution: indoc!(
-                "
-                = Однородное уравнение только (b). Его решение: $cos(y / x) = ln(x) + c$
-                "
-            )
-            .to_string(),
-        },
-
-    ];
-    let task5_variants = vec![
-        Task {
-            problem: indoc!("
-                 = Решите следующие дифференциальные уравнения:
-                 == $x y' =  y$.
-                 == $2 x y' + sqrt(x) = 2 y$; 
-                 == $6 sqrt(x) y y' + 1/y = (2 y^2)/sqrt(x)$; 
-             ").to_string(),
-
-            solution: indoc!(
-                "
-                = Решения:
-                == Пункт (a) является уравнением с разделяющимися переменными, а также линейным однородным уравнением. Его решение: $y = c x$.
-                == Пункт (b) является неоднородным линейным уравнением. Приводится к виду $y' = y/x + 1/(2 sqrt(x))$. Его решение: $y = c x - sqrt(x)$.
-                == Пункт (c) является уравнением линейным Бернулли, приводящееся к предыдущему уравнению заменой $z = y^3$. Тогда $z = c x - sqrt(x)$ и $y = root(3, c x - sqrt(x))$.
-                "
-            )
-            .to_string(),
-        }
-
-    ];
 
     let problem_code = String::from(include_str!("problem.typ"));
     let solution_code = String::from(include_str!("solution.typ"));
@@ -470,13 +186,35 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         )
         .build();
 
-    let tasks = vec![
-        task1_variants.choose(&mut rng).unwrap().clone(),
-        task2_variants.choose(&mut rng).unwrap().clone(),
-        task3_variants.choose(&mut rng).unwrap().clone(),
-        task4_variants.choose(&mut rng).unwrap().clone(),
-        task5_variants.choose(&mut rng).unwrap().clone(),
-    ];
+    // Random variants, the one least like the variants issued to the group is used.
+    let mut candidates = vec![];
+    for _ in 0..CANDIDATES {
+        let equations = [
+            verified(&mut rng, families::separable, verify_answer)?,
+            verified(&mut rng, families::exact, verify_answer)?,
+            verified(&mut rng, families::homogeneous, verify_answer)?,
+            verified(&mut rng, families::linear, verify_answer)?,
+            verified(&mut rng, families::bernoulli, verify_answer)?,
+        ];
+        let reduction = rng.random_range(0..reduction_variants.len());
+
+        let mut choices = equations
+            .iter()
+            .map(|eq| eq.choice.clone())
+            .collect::<Vec<_>>();
+        let mut tasks = equations
+            .into_iter()
+            .map(|eq| Task {
+                problem: eq.problem,
+                solution: eq.solution,
+            })
+            .collect::<Vec<_>>();
+        choices.insert(2, format!("reduction {reduction}"));
+        tasks.insert(2, reduction_variants[reduction].clone());
+        candidates.push((fingerprint(&choices), tasks));
+    }
+    let (variant_fingerprint, tasks) =
+        least_overlapping(candidates, &input.issued_fingerprints).expect("no candidates");
 
     let content = Content {
         variant: input.variant_number.to_string(),
@@ -518,6 +256,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         problem_images: problem_output,
         solution_code,
         solution_images: solution_output,
+        fingerprint: Some(variant_fingerprint),
     };
 
     println!("{}", serde_json::to_string(&output)?);
@@ -525,6 +264,50 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Generates until the answer passes verification.
+fn verified<R: Rng, T>(
+    rng: &mut R,
+    generate: impl Fn(&mut R) -> T,
+    verify: impl Fn(&T) -> Result<(), String>,
+) -> Result<T, Box<dyn std::error::Error>> {
+    for _ in 0..MAX_ATTEMPTS {
+        let task = generate(rng);
+        match verify(&task) {
+            Ok(()) => return Ok(task),
+            Err(err) => log::error!("Rejected a generated task: {err}"),
+        }
+    }
+    Err(format!("no valid task in {MAX_ATTEMPTS} attempts").into())
+}
+
+/// `--self-test N`: generates the tasks for the seeds `0..N` and verifies every
+/// answer, without rendering anything.
+fn self_test(count: u64) -> Result<(), Box<dyn std::error::Error>> {
+    let mut failures = 0;
+    for seed in 0..count {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let results = [
+            ("separable", verify_answer(&families::separable(&mut rng))),
+            ("exact", verify_answer(&families::exact(&mut rng))),
+            ("homogeneous", verify_answer(&families::homogeneous(&mut rng))),
+            ("linear", verify_answer(&families::linear(&mut rng))),
+            ("bernoulli", verify_answer(&families::bernoulli(&mut rng))),
+        ];
+        for (task, result) in results {
+            if let Err(err) = result {
+                failures += 1;
+                eprintln!("seed {seed}, {task}: {err}");
+            }
+        }
+    }
+
+    println!("{count} seeds, {failures} failed answers");
+    if failures > 0 {
+        return Err(format!("{failures} answers failed verification").into());
+    }
+    Ok(())
+}
+
 fn render_page_to_png(page: &Page, scale: f32) -> Vec<u8> {
     let make_buf = |page, scale| {
         let pixmap = render(page, scale);
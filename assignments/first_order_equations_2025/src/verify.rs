@@ -0,0 +1,131 @@
+//! Checks that the answers solve the printed equations before a variant is emitted.
+//!
+//! The answer `L(x, y) = R(x, c)` is affine in the constant, so it is the level set
+//! `Φ(x, y) = c` of `Φ = (L - R|c=0)/(R|c=1 - R|c=0)`. Its slope `-Φ_x/Φ_y` is
+//! taken by central differences at the sample points and compared with the
+//! right-hand side `y' = f(x, y)`; the primitives of the worked solution are
+//! differentiated the same way.
+
+use variant_generation::expr::Expr;
+
+use crate::families::{Answer, Equation};
+
+/// Step of the central differences.
+const STEP: f64 = 1e-4;
+
+/// Relative to the magnitude of the slopes.
+const TOLERANCE: f64 = 1e-5;
+
+/// A wrong option must differ from the answer at least by this much somewhere,
+/// relative to the slopes alone, as they may be small.
+const DISTINCT: f64 = 1e-3;
+
+/// `Φ_y` below this means a vertical tangent, the point is skipped.
+const VERTICAL: f64 = 1e-3;
+
+fn mismatch(slope: f64, rhs: f64) -> f64 {
+    (slope - rhs).abs() / (1. + slope.abs() + rhs.abs())
+}
+
+/// The variables at a point, `z = y/x` for the homogeneous substitution.
+fn point(x: f64, y: f64) -> Vec<(&'static str, f64)> {
+    vec![("x", x), ("y", y), ("z", y / x)]
+}
+
+/// `∂e/∂var` at the point.
+fn derivative(e: &Expr, var: &str, at: &[(&'static str, f64)]) -> f64 {
+    let shifted = |h: f64| {
+        let at = at
+            .iter()
+            .map(|&(name, value)| (name, if name == var { value + h } else { value }))
+            .collect::<Vec<_>>();
+        e.value(&at)
+    };
+    (shifted(STEP) - shifted(-STEP)) / (2. * STEP)
+}
+
+/// The right-hand side of the answer for the given value of the constant.
+fn answer_rhs(answer: &Answer, x: f64, y: f64, c: f64) -> f64 {
+    answer
+        .rhs
+        .value(&[("x", x), ("y", y), (answer.constant, c)])
+}
+
+/// `Φ(x, y)`.
+fn phi(answer: &Answer, x: f64, y: f64) -> f64 {
+    let r0 = answer_rhs(answer, x, y, 0.);
+    let r1 = answer_rhs(answer, x, y, 1.);
+    (answer.lhs.value(&point(x, y)) - r0) / (r1 - r0)
+}
+
+/// The slope of the answer through `(x, y)`, `None` where the tangent is vertical.
+fn slope(answer: &Answer, x: f64, y: f64) -> Option<f64> {
+    let h = STEP;
+    let phi_x = (phi(answer, x + h, y) - phi(answer, x - h, y)) / (2. * h);
+    let phi_y = (phi(answer, x, y + h) - phi(answer, x, y - h)) / (2. * h);
+    (phi_y.abs() >= VERTICAL).then(|| -phi_x / phi_y)
+}
+
+/// The answer is affine in the constant, its primitives are right, it solves the
+/// equation at every sample point (and at least one of them is checked), and solves
+/// none of the wrong options.
+pub fn verify_answer(eq: &Equation) -> Result<(), String> {
+    for &(x, y) in &eq.sample_points {
+        let r = |c| answer_rhs(&eq.answer, x, y, c);
+        let affine = mismatch(r(2.) - r(1.), r(1.) - r(0.));
+        if affine.is_nan() || affine > TOLERANCE {
+            return Err(format!(
+                "{}: the answer is not affine in {} at ({x}, {y})",
+                eq.choice, eq.answer.constant
+            ));
+        }
+
+        for p in &eq.primitives {
+            let at = point(x, y);
+            let derivative = derivative(&p.primitive, p.var, &at);
+            let integrand = p.integrand.value(&at);
+            let off = mismatch(derivative, integrand);
+            if off.is_nan() || off > TOLERANCE {
+                return Err(format!(
+                    "{}: ({})' = {derivative} instead of {integrand} at ({x}, {y})",
+                    eq.choice, p.primitive
+                ));
+            }
+        }
+    }
+
+    let points = eq
+        .sample_points
+        .iter()
+        .filter_map(|&(x, y)| slope(&eq.answer, x, y).map(|slope| (x, y, slope)))
+        .collect::<Vec<_>>();
+    if points.is_empty() {
+        return Err(format!("{}: no sample point to check", eq.choice));
+    }
+
+    for &(x, y, slope) in &points {
+        let rhs = eq.rhs.value(&point(x, y));
+        let mismatch = mismatch(slope, rhs);
+        if mismatch.is_nan() || mismatch > TOLERANCE {
+            return Err(format!(
+                "{}: the answer has the slope {slope} instead of {rhs} at ({x}, {y})",
+                eq.choice
+            ));
+        }
+    }
+
+    for (i, distractor) in eq.distractors.iter().enumerate() {
+        if points.iter().all(|&(x, y, slope)| {
+            let rhs = distractor.value(&point(x, y));
+            (slope - rhs).abs() <= DISTINCT * (slope.abs() + rhs.abs())
+        }) {
+            return Err(format!(
+                "{}: the answer also solves the wrong option {}",
+                eq.choice,
+                i + 1
+            ));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,214 @@
+//! Euler equations `sum a_k x^k y^(k) = f(x)`, `x > 0`. The substitution `x = e^t`
+//! turns `x^k y^(k)` into `θ(θ-1)…(θ-k+1) y` for `θ = d/dt`, so the equation is
+//! built from a `LinEq` in `t` and printed back in `x`.
+
+use polynomial_ring::{Polynomial, polynomial};
+use rand::prelude::*;
+
+use crate::{
+    LinEq, Poly, Q, QPoly, Root, Zero, fraction, linear_combination_typst,
+    linear_combination_typst_rev, q,
+};
+
+pub struct Euler {
+    /// The equation in `t`.
+    pub eq: LinEq,
+    /// `a_k` of `x^k y^(k)`.
+    pub coeffs: Vec<Q>,
+}
+
+/// Coefficients of `θ(θ-1)…(θ-k+1)`.
+fn falling_factorial(k: usize) -> Vec<Q> {
+    let mut p = vec![q(1)];
+    for i in 0..k {
+        let mut next = vec![Q::zero(); p.len() + 1];
+        for (j, c) in p.iter().enumerate() {
+            next[j + 1] += c;
+            next[j] -= c * q(i as i64);
+        }
+        p = next;
+    }
+    p
+}
+
+/// `x^(re) ln^p x cos(im ln x)` and the like, for `e^(re t) t^p cos(im t)`.
+fn term_in_x(re: &Q, p: usize, im: &Q, trig: &str) -> String {
+    let power = match fraction(re) {
+        (0, _) => String::new(),
+        (1, 1) => "x".to_string(),
+        (n, 1) => format!("x^({n})"),
+        (n, d) => format!("x^({n}/{d})"),
+    };
+    let log = match p {
+        0 => String::new(),
+        1 => "ln x".to_string(),
+        p => format!("ln^{p} x"),
+    };
+    let trig = match fraction(im) {
+        (0, _) => String::new(),
+        (1, 1) => format!("{trig}(ln x)"),
+        (n, 1) => format!("{trig}({n} ln x)"),
+        (n, d) => format!("{trig}({n}/{d} ln x)"),
+    };
+
+    [power, log, trig]
+        .into_iter()
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The quasi-polynomial in `t` as a function of `x = e^t`.
+pub fn qpoly_in_x_as_typst(f: &QPoly) -> String {
+    let mut coeffs = vec![];
+    let mut vars = vec![];
+    for (c, p) in f.pcos.coeffs().iter().zip(0..) {
+        coeffs.push(*c);
+        vars.push(term_in_x(&f.re, p, &f.im, "cos"));
+    }
+    if !f.im.is_zero() {
+        for (c, p) in f.psin.coeffs().iter().zip(0..) {
+            coeffs.push(*c);
+            vars.push(term_in_x(&f.re, p, &f.im, "sin"));
+        }
+    }
+
+    let res = linear_combination_typst(&coeffs, &vars);
+    if res.is_empty() { "0".to_string() } else { res }
+}
+
+impl Euler {
+    /// The Euler equation whose substitution gives `eq`: `a_k` with
+    /// `sum a_k θ(θ-1)…(θ-k+1) = P(θ)` for the characteristic polynomial `P`.
+    pub fn from_eq(eq: LinEq) -> Self {
+        let n = eq.order();
+        let mut rest = eq.coeffs.clone();
+        let mut coeffs = vec![Q::zero(); n + 1];
+        for k in (0..=n).rev() {
+            coeffs[k] = rest[k];
+            for (j, c) in falling_factorial(k).iter().enumerate() {
+                rest[j] -= coeffs[k] * c;
+            }
+        }
+        Self { eq, coeffs }
+    }
+
+    pub fn eq_as_typst(&self) -> String {
+        let vars = (0..self.coeffs.len())
+            .map(|k| match k {
+                0 => "y".to_string(),
+                1 => "x y'".to_string(),
+                k @ 2..=4 => format!("x^{k} y{}", "'".repeat(k)),
+                k => format!("x^{k} y^(({k}))"),
+            })
+            .collect::<Vec<_>>();
+
+        let mut res = linear_combination_typst_rev(&self.coeffs, &vars);
+        res.push('=');
+        if self.eq.f.is_empty() {
+            res.push('0');
+        } else {
+            for (i, f) in self.eq.f.iter().enumerate() {
+                let f_str = qpoly_in_x_as_typst(f);
+                if i != 0 && !f_str.starts_with("-") {
+                    res.push('+');
+                }
+                res.push_str(&f_str);
+            }
+        }
+        res
+    }
+
+    pub fn solution_as_typst(&self) -> String {
+        let mut res = String::new();
+
+        res.push_str("y=");
+        for (i, yy) in self.eq.y0_basis.iter().enumerate() {
+            if i != 0 {
+                res.push('+');
+            }
+            res.push_str(&format!("c_({}) {}", i + 1, qpoly_in_x_as_typst(yy)));
+        }
+
+        for yy in self.eq.y_part.iter() {
+            let yy_str = qpoly_in_x_as_typst(yy);
+
+            if !yy_str.starts_with("-") {
+                res.push('+');
+            }
+            res.push_str(&yy_str);
+        }
+        res
+    }
+
+    /// The worked solution, Typst markup.
+    pub fn steps_as_typst(&self) -> String {
+        format!(
+            "Замена $x=e^t$ ($x>0$) приводит к уравнению с постоянными коэффициентами \
+             (штрих — производная по $t$):\n\
+             $ {} $\n\n\
+             Характеристическое уравнение: ${}$, корни: ${}$\n\n\
+             $ {} $\n\n\
+             Возвращаясь к $x$ ($t=ln x$):\n\
+             $ {} $",
+            self.eq.eq_as_typst_in("t"),
+            self.eq.char_eq_as_typst(),
+            self.eq.char_roots_as_typst(),
+            self.eq.solution_as_typst_in("t"),
+            self.solution_as_typst(),
+        )
+    }
+}
+
+/// An Euler equation of order 2 or 3 with non-zero characteristic roots: distinct
+/// real ones, a double one or a complex pair; the right-hand side is a power of `x`
+/// or `ln x`, non-resonant.
+pub fn generate_euler(rng: &mut impl Rng) -> Euler {
+    let roots = match rng.random_range(0..4) {
+        0 => distinct_roots(2, rng),
+        1 => distinct_roots(3, rng),
+        2 => {
+            let root = q(*[-2, -1, 1, 2].choose(rng).unwrap());
+            vec![Root::Real(root), Root::Real(root)]
+        }
+        _ => {
+            let re = q(*[0, 1].choose(rng).unwrap());
+            let im = q(*[1, 2].choose(rng).unwrap());
+            vec![Root::Complex { re, im }]
+        }
+    };
+    let eq = LinEq::from_roots(roots);
+
+    let c = q(*[-2, -1, 1, 2].choose(rng).unwrap());
+    let powers = [-2, -1, 1, 2, 3]
+        .into_iter()
+        .map(q)
+        .filter(|k| {
+            let value: Q = eq.coeffs.iter().rev().fold(Q::zero(), |acc, a| acc * k + a);
+            !value.is_zero()
+        })
+        .collect::<Vec<_>>();
+    let y_part = match powers.choose(rng) {
+        Some(&k) if rng.random_bool(0.7) => QPoly {
+            re: k,
+            im: Q::zero(),
+            pcos: polynomial![c],
+            psin: Poly::zero(),
+        },
+        _ => QPoly {
+            re: Q::zero(),
+            im: Q::zero(),
+            pcos: polynomial![Q::zero(), c],
+            psin: Poly::zero(),
+        },
+    };
+
+    Euler::from_eq(eq.with_y_part(y_part))
+}
+
+/// Distinct non-zero real roots from -3 to 3.
+fn distinct_roots(count: usize, rng: &mut impl Rng) -> Vec<Root> {
+    let mut roots = vec![-3, -2, -1, 1, 2, 3];
+    roots.shuffle(rng);
+    roots.into_iter().take(count).map(|r| Root::Real(q(r))).collect()
+}
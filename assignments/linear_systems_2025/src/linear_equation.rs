@@ -1,34 +1,70 @@
 pub use num_traits::Zero;
 pub use polynomial_ring::Polynomial;
-pub type Poly = polynomial_ring::Polynomial<f64>;
 
+/// All coefficients are exact, so the answers never depend on rounding.
+pub type Q = num::rational::Rational64;
+pub type Poly = polynomial_ring::Polynomial<Q>;
+
+use nalgebra::DMatrix;
 use num_traits::One;
 use polynomial_ring::polynomial;
 
+use crate::linear_system::try_inverse;
+
+pub fn q(n: i64) -> Q {
+    Q::from_integer(n)
+}
+
+/// Numerator and denominator, the denominator is positive.
+pub fn fraction(x: &Q) -> (i64, i64) {
+    (*x.numer(), *x.denom())
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct LinEq {
-    pub coeffs: Vec<f64>,
+    pub coeffs: Vec<Q>,
     pub roots: Vec<(Root, usize)>,
     pub f: Vec<QPoly>,
     pub y0_basis: Vec<QPoly>,
     pub y_part: Vec<QPoly>,
+    /// Set for Cauchy problems.
+    pub ivp: Option<Ivp>,
+}
+
+/// Cauchy problem: the values `y(0), y'(0), …` and the constants `c_i` of the
+/// general solution that satisfy them.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Ivp {
+    pub initial_values: Vec<Q>,
+    pub constants: Vec<Q>,
 }
 
 #[derive(Clone, PartialEq, Debug)]
 pub enum Root {
-    Real(f64),
-    Complex { re: f64, im: f64 },
+    Real(Q),
+    Complex { re: Q, im: Q },
 }
 
 #[derive(Clone, PartialEq, Debug)]
 pub struct QPoly {
-    pub re: f64,
-    pub im: f64,
+    pub re: Q,
+    pub im: Q,
     pub pcos: Poly,
     pub psin: Poly,
 }
 
 impl QPoly {
+    /// `y(0), y'(0), …, y^(n-1)(0)`.
+    pub fn values_at_zero(&self, n: usize) -> Vec<Q> {
+        let mut y = self.clone();
+        let mut values = vec![];
+        for _ in 0..n {
+            values.push(y.pcos.coeffs().first().cloned().unwrap_or_else(Q::zero));
+            y = y.derivative();
+        }
+        values
+    }
+
     pub fn derivative(self) -> Self {
         let Self { re, im, pcos, psin } = self;
 
@@ -48,9 +84,9 @@ impl LinEq {
 
         for root in roots.iter().cloned() {
             match root {
-                Root::Real(lambda) => char_polynomial = char_polynomial * polynomial![-lambda, 1.],
+                Root::Real(lambda) => char_polynomial *= polynomial![-lambda, Q::one()],
                 Root::Complex { re, im } => {
-                    char_polynomial = char_polynomial * polynomial![re * re + im * im, -2. * re, 1.]
+                    char_polynomial *= polynomial![re * re + im * im, q(-2) * re, Q::one()]
                 }
             }
         }
@@ -68,15 +104,15 @@ impl LinEq {
 
         for (root, m) in root_multiplicities.iter() {
             for i in 0..*m {
-                let mut coefs = vec![0.; i as usize + 1];
-                coefs[i as usize] = 1.;
+                let mut coefs = vec![Q::zero(); i + 1];
+                coefs[i] = Q::one();
                 let p = Poly::new(coefs);
 
                 match *root {
                     Root::Real(lambda) => {
                         y0_basis.push(QPoly {
                             re: lambda,
-                            im: 0.,
+                            im: Q::zero(),
                             pcos: p,
                             psin: Poly::zero(),
                         });
@@ -105,6 +141,7 @@ impl LinEq {
             f: vec![],
             y0_basis,
             y_part: vec![],
+            ivp: None,
         }
     }
 
@@ -117,8 +154,8 @@ impl LinEq {
             psin: Poly::zero(),
         };
         for c in self.coeffs.iter().cloned() {
-            f.pcos = f.pcos + y_diff.pcos.clone() * c;
-            f.psin = f.psin + y_diff.psin.clone() * c;
+            f.pcos += y_diff.pcos.clone() * c;
+            f.psin += y_diff.psin.clone() * c;
 
             y_diff = y_diff.derivative();
         }
@@ -129,14 +166,83 @@ impl LinEq {
         }
         self
     }
+
+    pub fn order(&self) -> usize {
+        self.coeffs.len() - 1
+    }
+
+    /// `y(0), y'(0), …` of the particular solution.
+    fn y_part_at_zero(&self) -> Vec<Q> {
+        let n = self.order();
+        self.y_part.iter().fold(vec![Q::zero(); n], |sum, y| {
+            sum.iter().zip(y.values_at_zero(n)).map(|(a, b)| a + b).collect()
+        })
+    }
+
+    /// The Cauchy problem with the given `y(0), y'(0), …`, `None` if their number is
+    /// not the order.
+    pub fn with_initial_values(mut self, initial_values: Vec<Q>) -> Option<Self> {
+        let n = self.order();
+        if initial_values.len() != n {
+            return None;
+        }
+
+        let basis_values = self
+            .y0_basis
+            .iter()
+            .map(|y| y.values_at_zero(n))
+            .collect::<Vec<_>>();
+        let wronskian = DMatrix::from_fn(n, n, |k, i| basis_values[i][k]);
+        let inverse = try_inverse(&wronskian)?;
+
+        let rhs = initial_values
+            .iter()
+            .zip(self.y_part_at_zero())
+            .map(|(value, part)| value - part)
+            .collect::<Vec<_>>();
+        let constants = (0..n)
+            .map(|i| (0..n).map(|k| inverse[(i, k)] * rhs[k]).sum::<Q>())
+            .collect();
+
+        self.ivp = Some(Ivp {
+            initial_values,
+            constants,
+        });
+        Some(self)
+    }
+
+    /// The Cauchy problem solved by the general solution with the given constants.
+    pub fn with_constants(mut self, constants: Vec<Q>) -> Self {
+        let n = self.order();
+        let mut initial_values = self.y_part_at_zero();
+        for (c, y) in constants.iter().zip(&self.y0_basis) {
+            for (value, basis_value) in initial_values.iter_mut().zip(y.values_at_zero(n)) {
+                *value += c * basis_value;
+            }
+        }
+
+        self.ivp = Some(Ivp {
+            initial_values,
+            constants,
+        });
+        self
+    }
+}
+
+fn q_as_typst(x: &Q) -> String {
+    match fraction(x) {
+        (num, 1) => format!("{num}"),
+        (num, denom) if num < 0 => format!("-{}/{denom}", num.abs()),
+        (num, denom) => format!("{num}/{denom}"),
+    }
 }
 
-pub fn linear_combination_typst_rev(coeffs: &[f64], vars: &[String]) -> String {
+pub fn linear_combination_typst_rev(coeffs: &[Q], vars: &[String]) -> String {
     let mut result_str = String::new();
     let mut count_nonzero = 0;
 
-    for (var, coeff) in (vars.iter()).zip(coeffs.iter().cloned()).rev() {
-        let (num, denom) = rationalize_cf(coeff, 0.001);
+    for (var, coeff) in (vars.iter()).zip(coeffs.iter()).rev() {
+        let (num, denom) = fraction(coeff);
         if num != 0 {
             count_nonzero += 1;
             match (num, denom) {
@@ -162,12 +268,12 @@ pub fn linear_combination_typst_rev(coeffs: &[f64], vars: &[String]) -> String {
     result_str
 }
 
-pub fn linear_combination_typst(coeffs: &[f64], vars: &[String]) -> String {
+pub fn linear_combination_typst(coeffs: &[Q], vars: &[String]) -> String {
     let mut result_str = String::new();
     let mut count_nonzero = 0;
 
-    for (var, coeff) in (vars.iter()).zip(coeffs.iter().cloned()) {
-        let (num, denom) = rationalize_cf(coeff, 0.001);
+    for (var, coeff) in (vars.iter()).zip(coeffs.iter()) {
+        let (num, denom) = fraction(coeff);
         if num != 0 {
             count_nonzero += 1;
             match (num, denom) {
@@ -197,8 +303,8 @@ pub fn qpoly_as_typst(f: &QPoly, var: &str) -> String {
     let mut res = String::new();
 
     let QPoly { re, im, pcos, psin } = f;
-    let re = rationalize_cf(*re, 0.001);
-    let im = rationalize_cf(*im, 0.001);
+    let re = fraction(re);
+    let im = fraction(im);
 
     let exp_str = match re {
         (0, _) => "",
@@ -209,7 +315,7 @@ pub fn qpoly_as_typst(f: &QPoly, var: &str) -> String {
         (n, d) => &format!("e^({}/{} {var})", n.abs(), d),
     };
 
-    let polynomial_str = |coeffs: &[f64]| {
+    let polynomial_str = |coeffs: &[Q]| {
         let mut result_str = String::new();
 
         result_str.push_str(&linear_combination_typst_rev(
@@ -224,8 +330,8 @@ pub fn qpoly_as_typst(f: &QPoly, var: &str) -> String {
         ));
 
         let mut count_nonzero = 0;
-        for c in coeffs.iter().cloned() {
-            if rationalize_cf(c, 0.001).0 != 0 {
+        for c in coeffs.iter() {
+            if !c.is_zero() {
                 count_nonzero += 1;
             }
         }
@@ -316,6 +422,11 @@ impl LinEq {
         lhs
     }
     pub fn eq_as_typst(&self) -> String {
+        self.eq_as_typst_in("x")
+    }
+
+    /// The equation with the right-hand side in the variable `var`.
+    pub fn eq_as_typst_in(&self, var: &str) -> String {
         // let deg = self.coeffs.len() - 1;
         // let powers = (0..self.coeffs.len()).rev();
         // let coeffs = self.coeffs.iter().rev().cloned();
@@ -344,7 +455,7 @@ impl LinEq {
             rhs.push_str("0");
         } else {
             for (i, f) in self.f.iter().enumerate() {
-                let f_str = qpoly_as_typst(f, "x");
+                let f_str = qpoly_as_typst(f, var);
 
                 if i != 0 && !f_str.starts_with("-") {
                     rhs.push_str("+");
@@ -378,11 +489,16 @@ impl LinEq {
     }
 
     pub fn solution_as_typst(&self) -> String {
+        self.solution_as_typst_in("x")
+    }
+
+    /// The general solution in the variable `var`.
+    pub fn solution_as_typst_in(&self, var: &str) -> String {
         let mut res = String::new();
 
         res.push_str("y=");
         for (i, yy) in self.y0_basis.iter().enumerate() {
-            let yy_str = qpoly_as_typst(yy, "x");
+            let yy_str = qpoly_as_typst(yy, var);
 
             if i != 0 {
                 res.push_str("+");
@@ -394,7 +510,7 @@ impl LinEq {
         }
 
         for (_i, yy) in self.y_part.iter().enumerate() {
-            let yy_str = qpoly_as_typst(yy, "x");
+            let yy_str = qpoly_as_typst(yy, var);
 
             if !yy_str.starts_with("-") {
                 res.push_str("+");
@@ -422,6 +538,74 @@ impl LinEq {
         res
     }
 
+    /// `y(0)=1, y'(0)=-2`, empty unless this is a Cauchy problem.
+    pub fn initial_conditions_as_typst(&self) -> String {
+        let Some(ivp) = &self.ivp else {
+            return String::new();
+        };
+        ivp.initial_values
+            .iter()
+            .enumerate()
+            .map(|(k, value)| {
+                let y = match k {
+                    0..=4 => format!("y{}", "'".repeat(k)),
+                    k => format!("y^(({k}))"),
+                };
+                format!("{y}(0)={}", q_as_typst(value))
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// `c_1=2, c_2=-1/2`, empty unless this is a Cauchy problem.
+    pub fn constants_as_typst(&self) -> String {
+        let Some(ivp) = &self.ivp else {
+            return String::new();
+        };
+        ivp.constants
+            .iter()
+            .enumerate()
+            .map(|(i, c)| format!("c_({})={}", i + 1, q_as_typst(c)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// The general solution with the constants of the Cauchy problem, empty unless
+    /// this is one.
+    pub fn specific_solution_as_typst(&self) -> String {
+        let Some(ivp) = &self.ivp else {
+            return String::new();
+        };
+
+        let mut terms = vec![];
+        for (c, y) in ivp.constants.iter().zip(&self.y0_basis) {
+            if !c.is_zero() {
+                terms.push(QPoly {
+                    re: y.re,
+                    im: y.im,
+                    pcos: y.pcos.clone() * *c,
+                    psin: y.psin.clone() * *c,
+                });
+            }
+        }
+        terms.extend(self.y_part.iter().cloned());
+
+        let mut res = String::new();
+        res.push_str("y=");
+        for (i, yy) in terms.iter().enumerate() {
+            let yy_str = qpoly_as_typst(yy, "x");
+
+            if i != 0 && !yy_str.starts_with("-") {
+                res.push('+');
+            }
+            res.push_str(&yy_str);
+        }
+        if terms.is_empty() {
+            res.push('0');
+        }
+        res
+    }
+
     pub fn char_roots_as_typst(&self) -> String {
         let mut res = String::new();
         let mut i = 1;
@@ -447,9 +631,10 @@ impl LinEq {
                         res.push_str(&format!(",{i}"));
                         i += 1;
                     }
-                    match re {
-                        0. => res.push_str(&format!(")= plus.minus {im} i")),
-                        re => res.push_str(&format!(")={re} plus.minus {im} i")),
+                    if re.is_zero() {
+                        res.push_str(&format!(")= plus.minus {im} i"))
+                    } else {
+                        res.push_str(&format!(")={re} plus.minus {im} i"))
                     }
                 }
             }
@@ -459,36 +644,3 @@ impl LinEq {
         res
     }
 }
-
-/// Rational approximation of x using continued fractions.
-/// Returns (numerator, denominator) such that |x - n/d| <= delta.
-pub fn rationalize_cf(x: f64, delta: f64) -> (i64, i64) {
-    let mut a = x.floor() as i64;
-    let mut h1 = 1i64;
-    let mut k1 = 0i64;
-    let mut h = a;
-    let mut k = 1i64;
-    let mut x_rem = x - a as f64;
-
-    while x_rem.abs() > delta {
-        if x_rem == 0.0 {
-            break;
-        }
-        let r = 1.0 / x_rem;
-        a = r.floor() as i64;
-        let h_next = a * h + h1;
-        let k_next = a * k + k1;
-
-        h1 = h;
-        k1 = k;
-        h = h_next;
-        k = k_next;
-
-        x_rem = r - a as f64;
-    }
-
-    assert!(((h as f64 / k as f64) - x).abs() < delta);
-
-    let gcd = gcd::euclid_u64(h.abs() as u64, k.abs() as u64) as i64;
-    (h / gcd, k / gcd)
-}
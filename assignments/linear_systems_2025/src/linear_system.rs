@@ -6,20 +6,20 @@ use num::One;
 use num::Zero;
 pub use polynomial_ring::Polynomial;
 pub use polynomial_ring::polynomial;
+pub use pretty_matrix_inverse::try_inverse;
 
 use crate::linear_combination_typst_rev;
-use crate::linear_equation::linear_combination_typst;
+use crate::linear_equation::{fraction, linear_combination_typst};
 use crate::qpoly_as_typst;
-use crate::rationalize_cf;
-use crate::{Poly, QPoly, Root};
+use crate::{Poly, Q, QPoly, Root, q};
 
 #[derive(Clone, PartialEq, Debug)]
 pub struct LinSys {
-    pub a_matrix: DMatrix<f64>,
-    pub c_matrix: DMatrix<f64>,
+    pub a_matrix: DMatrix<Q>,
+    pub c_matrix: DMatrix<Q>,
     pub j_blocks: Vec<(Root, usize)>,
     pub roots: Vec<(Root, usize)>,
-    pub char_coeffs: Vec<f64>,
+    pub char_coeffs: Vec<Q>,
     pub f: Vec<VQPoly>,
     pub y0_basis: Vec<VQPoly>,
     pub y_part: Vec<VQPoly>,
@@ -27,8 +27,8 @@ pub struct LinSys {
 
 #[derive(Clone, PartialEq, Debug)]
 pub struct VQPoly {
-    pub re: f64,
-    pub im: f64,
+    pub re: Q,
+    pub im: Q,
     pub pcos: DVector<Poly>,
     pub psin: DVector<Poly>,
 }
@@ -49,6 +49,49 @@ impl VQPoly {
     }
 }
 
+/// Real Jordan matrix of the blocks: a real root with ones above the diagonal, a
+/// complex pair as `(re, im; -im, re)` cells with identities above them.
+pub fn jordan_matrix(blocks: &[(Root, usize)]) -> DMatrix<Q> {
+    let n = blocks
+        .iter()
+        .map(|(root, size)| match root {
+            Root::Real(_) => *size,
+            Root::Complex { .. } => 2 * size,
+        })
+        .sum();
+
+    let mut j_mat = DMatrix::zeros(n, n);
+    let mut i = 0;
+    for (root, size) in blocks {
+        match *root {
+            Root::Real(r) => {
+                for k in 0..*size {
+                    j_mat[(i + k, i + k)] = r;
+                    if k > 0 {
+                        j_mat[(i + k - 1, i + k)] = Q::one();
+                    }
+                }
+                i += size;
+            }
+            Root::Complex { re, im } => {
+                for k in 0..*size {
+                    let d = i + 2 * k;
+                    j_mat[(d, d)] = re;
+                    j_mat[(d, d + 1)] = im;
+                    j_mat[(d + 1, d)] = -im;
+                    j_mat[(d + 1, d + 1)] = re;
+                    if k > 0 {
+                        j_mat[(d - 2, d)] = Q::one();
+                        j_mat[(d - 1, d + 1)] = Q::one();
+                    }
+                }
+                i += 2 * size;
+            }
+        }
+    }
+    j_mat
+}
+
 #[must_use]
 pub fn integral<R: Sized>(p: &Polynomial<R>) -> Polynomial<R>
 where
@@ -73,7 +116,7 @@ pub fn vqpoly_as_typst(f: &VQPoly) -> String {
     let VQPoly { re, im, pcos, psin } = f;
 
     {
-        let re = rationalize_cf(*re, 0.001);
+        let re = fraction(re);
         let exp_str = match re {
             (0, _) => "",
             (1, 1) => "e^(t)",
@@ -89,7 +132,7 @@ pub fn vqpoly_as_typst(f: &VQPoly) -> String {
     for (pcos, psin) in pcos.iter().zip(psin.iter()) {
         res.push_str(&qpoly_as_typst(
             &QPoly {
-                re: 0.,
+                re: Q::zero(),
                 im: *im,
                 pcos: pcos.clone(),
                 psin: psin.clone(),
@@ -108,8 +151,8 @@ pub fn vqpoly_as_typst(f: &VQPoly) -> String {
 
 impl LinSys {
     pub fn new(
-        a_matrix: DMatrix<f64>,
-        c_matrix: DMatrix<f64>,
+        a_matrix: DMatrix<Q>,
+        c_matrix: DMatrix<Q>,
         j_blocks: Vec<(Root, usize)>,
     ) -> Self {
         let mut y0_basis = vec![];
@@ -133,14 +176,14 @@ impl LinSys {
             match *root {
                 Root::Real(r) => {
                     for _ in 0..*size {
-                        char_polynomial = char_polynomial * polynomial![-r, 1.];
+                        char_polynomial *= polynomial![-r, Q::one()];
                     }
 
                     let v = c_matrix.column(i).clone().map(|c| polynomial![c]);
 
                     y0_basis.push(VQPoly {
                         re: r,
-                        im: 0.,
+                        im: Q::zero(),
                         pcos: v.clone(),
                         psin: v.map(|_| polynomial![]),
                     });
@@ -151,7 +194,7 @@ impl LinSys {
 
                         y0_basis.push(VQPoly {
                             re: r,
-                            im: 0.,
+                            im: Q::zero(),
                             pcos: h.clone(),
                             psin: h.map(|_| polynomial![]),
                         })
@@ -161,56 +204,54 @@ impl LinSys {
                 }
                 Root::Complex { re, im } => {
                     for _ in 0..*size {
-                        char_polynomial =
-                            char_polynomial * polynomial![re * re + im * im, -2. * re, 1.];
+                        char_polynomial *= polynomial![re * re + im * im, q(-2) * re, Q::one()];
                     }
 
                     let v1 = c_matrix.column(i).clone().map(|c| polynomial![c]);
                     let v2 = c_matrix.column(i + 1).clone().map(|c| polynomial![c]);
 
-                    // true eighenvector V is - ?
-                    // V e (cos + i sin) + ~V e (cos - i sin)
-                    //
-                    // goes to (2 Re V cos - 2 Im V sin)
-                    // v1 = 2 Re V, v2 = -2 Im V
+                    // the block of J is (re, im; -im, re), so z' = J z is solved by
+                    // e^(re t) (cos, -sin) and e^(re t) (sin, cos), and y = C z by
+                    // e^(re t) (v1 cos - v2 sin) and e^(re t) (v2 cos + v1 sin)
 
                     y0_basis.push(VQPoly {
                         re,
                         im,
                         pcos: v1.clone(),
-                        psin: v2.clone(),
+                        psin: -v2.clone(),
                     });
                     y0_basis.push(VQPoly {
                         re,
                         im,
                         pcos: v2.clone(),
-                        psin: -v1.clone(),
+                        psin: v1.clone(),
                     });
 
+                    // the next pairs of the chain add their columns to the integrals
+                    // of the previous pair, as the real chains do
                     for j in 1..*size {
-                        let h1 = y0_basis[i + 2 * j - 2].pcos.map(|p| integral(&p))
-                            + c_matrix
-                                .column(i + 2 * j - 1)
-                                .clone()
-                                .map(|c| polynomial![c]);
-                        let h2 = y0_basis[i + 2 * j - 2].psin.map(|p| integral(&p))
-                            + c_matrix.column(i + 2 * j).clone().map(|c| polynomial![c]);
+                        let v1 = c_matrix.column(i + 2 * j).clone().map(|c| polynomial![c]);
+                        let v2 = c_matrix
+                            .column(i + 2 * j + 1)
+                            .clone()
+                            .map(|c| polynomial![c]);
+                        let prev1 = y0_basis[i + 2 * j - 2].clone();
+                        let prev2 = y0_basis[i + 2 * j - 1].clone();
 
                         y0_basis.push(VQPoly {
                             re,
                             im,
-                            pcos: h1.clone(),
-                            psin: h2.clone(),
+                            pcos: prev1.pcos.map(|p| integral(&p)) + v1.clone(),
+                            psin: prev1.psin.map(|p| integral(&p)) - v2.clone(),
                         });
                         y0_basis.push(VQPoly {
                             re,
                             im,
-                            pcos: h2.clone(),
-                            psin: -h1.clone(),
+                            pcos: prev2.pcos.map(|p| integral(&p)) + v2,
+                            psin: prev2.psin.map(|p| integral(&p)) + v1,
                         });
                     }
 
-                    i += size;
                     i += 2 * size;
                 }
             }
@@ -334,7 +375,7 @@ impl LinSys {
         for i in 0..self.a_matrix.nrows() {
             for j in 0..self.a_matrix.ncols() {
                 let elem = self.a_matrix[(i, j)];
-                match rationalize_cf(elem, 0.001) {
+                match fraction(&elem) {
                     (0, _) => res.push_str("0"),
                     (num, 1) => res.push_str(&format!("{num}")),
                     (num, denom) => res.push_str(&format!("{num}/{denom}")),
@@ -447,9 +488,10 @@ impl LinSys {
                         res.push_str(&format!(",{i}"));
                         i += 1;
                     }
-                    match re {
-                        0. => res.push_str(&format!(")= plus.minus {im} i")),
-                        re => res.push_str(&format!(")={re} plus.minus {im} i")),
+                    if re.is_zero() {
+                        res.push_str(&format!(")= plus.minus {im} i"))
+                    } else {
+                        res.push_str(&format!(")={re} plus.minus {im} i"))
                     }
                 }
             }
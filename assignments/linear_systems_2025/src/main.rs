@@ -2,10 +2,16 @@
 // mod linear_equation_old;
 //
 //
+pub mod euler;
 pub mod linear_equation;
 pub mod linear_system;
+pub mod variation;
+pub mod verify;
 
-use std::io::{self, Read};
+use std::{
+    io::{self, Read},
+    panic::AssertUnwindSafe,
+};
 
 use base64::prelude::*;
 use derive_typst_intoval::{IntoDict, IntoValue};
@@ -13,19 +19,37 @@ use image::{ImageBuffer, Rgba};
 use linear_equation::*;
 use linear_system::*;
 
-use nalgebra::{Matrix2, Matrix3, vector};
+use num_traits::One;
 use polynomial_ring::polynomial;
+use pretty_matrix_inverse::{Constraints, Product};
 use rand::prelude::*;
+use serde::Deserialize;
 use typst::{
     foundations::{Dict, IntoValue},
     layout::{Page, PagedDocument},
 };
 use typst_as_lib::typst_kit_options::TypstKitFontOptions;
 use typst_render::render;
-use variant_generation::{VariantGeneratorInput, VariantGeneratorOutput};
+use variant_generation::{
+    VariantGeneratorInput, VariantGeneratorOutput, fingerprint, least_overlapping,
+};
+use euler::generate_euler;
+use variation::generate_variation;
+use verify::{verify_equation, verify_euler, verify_system, verify_variation};
+
+/// Random variants compared with the issued ones.
+const CANDIDATES: usize = 20;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     pretty_env_logger::init();
+
+    let args = std::env::args().collect::<Vec<_>>();
+    if let [_, flag, count] = args.as_slice()
+        && flag == "--self-test"
+    {
+        return self_test(count.parse()?);
+    }
+
     let mut rng = rand::rng();
 
     let mut buffer = String::new();
@@ -35,544 +59,766 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         VariantGeneratorInput {
             variant_number: 666,
             generator: "test_assignment".to_string(),
+            parameters: serde_json::Value::Null,
+            issued_fingerprints: vec![],
         }
     });
 
-    // Task 1:
-    // - distinct real roots
-    // - e^x + x^2 or x e^x
-    let eq1 = {
-        let root_options: [f64; _] = [-3., -2., -1., 1., 2., 3.];
-        let roots = loop {
-            let (a, b) = (
-                root_options.choose(&mut rng).unwrap(),
-                root_options.choose(&mut rng).unwrap(),
-            );
-            if a.abs() != b.abs() {
-                break (*a, *b);
-            }
-        };
-        let mut eq = LinEq::from_roots(vec![Root::Real(roots.0), Root::Real(roots.1)]);
+    let parameters: Parameters = match &input.parameters {
+        serde_json::Value::Null => Parameters::default(),
+        parameters => serde_json::from_value(parameters.clone())?,
+    };
+    let (equation_levels, cauchy_levels, system_levels) = parameters.levels()?;
+    let variation_count = parameters.variation;
+    let euler_count = parameters.euler;
 
-        let k = loop {
-            let k = *root_options.choose(&mut rng).unwrap();
-            if k != roots.0 && k != roots.1 {
-                break k;
-            }
-        };
-        let coeff_options = [-5., -3., -2., 2., 3., 5., 7.];
-        let coeff1 = *coeff_options.choose(&mut rng).unwrap();
-        let coeff2 = *coeff_options.choose(&mut rng).unwrap();
-        let coeff3 = *coeff_options.choose(&mut rng).unwrap();
+    let problem_code = String::from(include_str!("problem.typ"));
+    let solution_code = String::from(include_str!("solution.typ"));
 
-        if rand::random_bool(0.5) {
-            let y1 = QPoly {
-                re: k,
-                im: 0.,
-                pcos: polynomial![coeff1],
-                psin: Poly::zero(),
-            };
+    let problem_engine = typst_as_lib::TypstEngine::builder()
+        .main_file(problem_code.clone())
+        .search_fonts_with(
+            TypstKitFontOptions::default()
+                .include_system_fonts(false)
+                // This line is not necessary, because thats the default.
+                .include_embedded_fonts(true),
+        )
+        .build();
 
-            let y2 = QPoly {
-                re: 0.,
-                im: 0.,
-                pcos: polynomial![coeff2, 0., coeff3],
-                psin: Poly::zero(),
-            };
-            eq = eq.with_y_part(y1).with_y_part(y2);
-        } else {
-            let y1 = QPoly {
-                re: k,
-                im: 0.,
-                pcos: polynomial![0., 0., coeff3],
-                psin: Poly::zero(),
-            };
+    let solution_engine = typst_as_lib::TypstEngine::builder()
+        .main_file(solution_code.clone())
+        .search_fonts_with(
+            TypstKitFontOptions::default()
+                .include_system_fonts(false)
+                // This line is not necessary, because thats the default.
+                .include_embedded_fonts(true),
+        )
+        .build();
 
-            eq = eq.with_y_part(y1);
+    // Random variants, the one least like the variants issued to the group is used.
+    // The tasks are told apart by their characteristic roots.
+    let mut candidates = vec![];
+    for _ in 0..CANDIDATES {
+        let mut tasks = vec![];
+        let mut choices = vec![];
+        for &level in &equation_levels {
+            let eq = verified(&mut rng, |rng| Ok(generate_equation(level, rng)), verify_equation)?;
+            choices.push(eq.char_roots_as_typst());
+            tasks.push(Task {
+                problem: "Для следующих однородного и неоднородного уравнений, найдите общее решение"
+                    .to_string(),
+                equation_homo: eq.eq_homo_as_typst(),
+                equation: eq.eq_as_typst(),
+                char_equation: eq.char_eq_as_typst(),
+                solution_homo: eq.solution_homo_as_typst(),
+                solution: eq.solution_as_typst(),
+                char_roots: eq.char_roots_as_typst(),
+                show_char_roots: false,
+                initial_conditions: String::new(),
+                constants: String::new(),
+                specific_solution: String::new(),
+                steps: String::new(),
+            });
         }
-        eq
+        for &level in &cauchy_levels {
+            let eq = verified(&mut rng, |rng| Ok(generate_ivp(level, rng)), verify_equation)?;
+            choices.push(eq.char_roots_as_typst());
+            tasks.push(Task {
+                problem: "Решите задачу Коши".to_string(),
+                equation_homo: eq.eq_homo_as_typst(),
+                equation: eq.eq_as_typst(),
+                char_equation: eq.char_eq_as_typst(),
+                solution_homo: eq.solution_homo_as_typst(),
+                solution: eq.solution_as_typst(),
+                char_roots: eq.char_roots_as_typst(),
+                show_char_roots: false,
+                initial_conditions: eq.initial_conditions_as_typst(),
+                constants: eq.constants_as_typst(),
+                specific_solution: eq.specific_solution_as_typst(),
+                steps: String::new(),
+            });
+        }
+        for _ in 0..variation_count {
+            let task = verified(&mut rng, |rng| Ok(generate_variation(rng)), verify_variation)?;
+            choices.push(task.family.to_string());
+            tasks.push(Task {
+                problem: "Решите уравнение методом вариации постоянных".to_string(),
+                equation_homo: task.eq.eq_homo_as_typst(),
+                equation: task.eq_as_typst(),
+                char_equation: task.eq.char_eq_as_typst(),
+                solution_homo: task.eq.solution_homo_as_typst(),
+                solution: task.solution_as_typst(),
+                char_roots: task.eq.char_roots_as_typst(),
+                show_char_roots: false,
+                initial_conditions: String::new(),
+                constants: String::new(),
+                specific_solution: String::new(),
+                steps: task.steps_as_typst(),
+            });
+        }
+        for _ in 0..euler_count {
+            let task = verified(&mut rng, |rng| Ok(generate_euler(rng)), verify_euler)?;
+            choices.push(format!("euler {}", task.eq.char_roots_as_typst()));
+            tasks.push(Task {
+                problem: "Решите уравнение Эйлера".to_string(),
+                equation_homo: task.eq.eq_homo_as_typst(),
+                equation: task.eq_as_typst(),
+                char_equation: task.eq.char_eq_as_typst(),
+                solution_homo: task.eq.solution_homo_as_typst(),
+                solution: task.solution_as_typst(),
+                char_roots: task.eq.char_roots_as_typst(),
+                show_char_roots: false,
+                initial_conditions: String::new(),
+                constants: String::new(),
+                specific_solution: String::new(),
+                steps: task.steps_as_typst(),
+            });
+        }
+        for &level in &system_levels {
+            let sys = verified(&mut rng, |rng| generate_system(level, rng), verify_system)?;
+            choices.push(sys.char_roots_as_typst());
+            tasks.push(Task {
+                problem: "Для следующих однородной и неоднородной системы уравнений уравнений, найдите общее решение"
+                    .to_string(),
+                equation_homo: sys.eq_homo_as_typst(),
+                equation: sys.eq_as_typst(),
+                char_equation: sys.char_eq_as_typst(),
+                solution_homo: sys.solution_homo_as_typst(),
+                solution: sys.solution_as_typst(),
+                char_roots: sys.char_roots_as_typst(),
+                show_char_roots: sys.a_matrix.nrows() >= 3,
+                initial_conditions: String::new(),
+                constants: String::new(),
+                specific_solution: String::new(),
+                steps: String::new(),
+            });
+        }
+        candidates.push((fingerprint(&choices), tasks));
+    }
+    let (variant_fingerprint, tasks) =
+        least_overlapping(candidates, &input.issued_fingerprints).expect("no candidates");
+
+    let content = Content {
+            variant: input.variant_number.to_string(),
+            tasks: tasks.clone(),
+        };
+
+    // log::debug!("{content:?}");
+
+    let problem_doc: PagedDocument = problem_engine
+        .compile_with_input(content)
+        .output
+        .expect("typst::compile() returned an error!");
+
+    let solution_doc: PagedDocument = solution_engine
+        .compile_with_input(Content {
+            variant: input.variant_number.to_string(),
+            tasks: tasks.clone(),
+        })
+        .output
+        .expect("typst::compile() returned an error!");
+
+    let problem_output = problem_doc
+        .pages
+        .iter()
+        .map(|page| render_page_to_png(page, 300. / 72.))
+        .map(|img| BASE64_STANDARD.encode(img))
+        .collect::<Vec<_>>();
+    let solution_output = solution_doc
+        .pages
+        .iter()
+        .map(|page| render_page_to_png(page, 300. / 72.))
+        .map(|img| BASE64_STANDARD.encode(img))
+        .collect::<Vec<_>>();
+
+    let output = VariantGeneratorOutput {
+        variant_number: input.variant_number,
+        generator: input.generator,
+        problem_code,
+        problem_images: problem_output,
+        solution_code,
+        solution_images: solution_output,
+        fingerprint: Some(variant_fingerprint),
     };
 
-    let eq2 = {
-        if rand::random_bool(0.5) {
-            // let root_options: [f64; _] = [-3., -2., -1., 1., 2., 3.];
-            let root = *[-3., -2., -1., 1., 2., 3.].choose(&mut rng).unwrap();
-            let mut eq = LinEq::from_roots(vec![Root::Real(root), Root::Real(root)]);
+    println!("{}", serde_json::to_string(&output)?);
 
-            let coeff1 = *[-5., -3., -2., 2., 3., 5., 7.].choose(&mut rng).unwrap();
-            let coeff2 = *[-5., -3., -2., 2., 3., 5., 7.].choose(&mut rng).unwrap();
+    Ok(())
+}
 
-            let y1 = QPoly {
-                re: root,
-                im: 0.,
-                pcos: polynomial![0., 0., coeff1],
-                psin: Poly::zero(),
-            };
+/// `VariantGeneratorInput::parameters`: `{"level": 2}` sets the level of every task,
+/// `{"equations": [1, 1, 2], "systems": [2]}` lists the levels of the tasks, one task
+/// per entry. By default there is one equation and one system of every level.
+/// `{"cauchy": [1, 2]}` adds Cauchy problems for the equations of the levels, there
+/// are none by default. The systems also have levels 4 (3d) and 5 (4d).
+/// `{"variation": 2}` adds that many equations for the variation of constants,
+/// `{"euler": 1}` that many Euler equations.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Parameters {
+    level: Option<u8>,
+    equations: Option<Vec<u8>>,
+    cauchy: Vec<u8>,
+    variation: usize,
+    euler: usize,
+    systems: Option<Vec<u8>>,
+}
 
-            let y2 = QPoly {
-                re: -root,
-                im: 0.,
-                pcos: polynomial![coeff2],
-                psin: Poly::zero(),
-            };
-            eq = eq.with_y_part(y1).with_y_part(y2);
-            eq
-        } else {
-            let im = *[2., 3., 4.].choose(&mut rng).unwrap();
-            let mut eq = LinEq::from_roots(vec![Root::Complex { re: 0., im }]);
-
-            let re = *[-3., -2., -1., 1., 2., 3.].choose(&mut rng).unwrap();
-            let coeff1 = *[-5., -3., -2., 2., 3., 5., 7.].choose(&mut rng).unwrap();
-            let coeff2 = *[-5., -3., -2., 2., 3., 5., 7.].choose(&mut rng).unwrap();
-
-            if rand::random_bool(0.5) {
-                let y1 = QPoly {
-                    re: 0.,
-                    im: im,
-                    pcos: polynomial![coeff1],
-                    psin: Poly::zero(),
-                };
-                let y2 = QPoly {
-                    re: re,
-                    im: im,
-                    pcos: Poly::zero(),
-                    psin: polynomial![coeff2],
-                };
-                eq = eq.with_y_part(y1).with_y_part(y2);
-                eq.y_part.shuffle(&mut rng);
-            } else {
-                let y1 = QPoly {
-                    re: 0.,
-                    im: im,
-                    pcos: Poly::zero(),
-                    psin: polynomial![coeff1],
-                };
-                let y2 = QPoly {
-                    re: re,
-                    im: im,
-                    pcos: polynomial![coeff2],
-                    psin: Poly::zero(),
-                };
-                eq = eq.with_y_part(y1).with_y_part(y2);
-                eq.y_part.shuffle(&mut rng);
-            }
-            eq
+/// Levels of the equations, of the Cauchy problems and of the systems.
+type Levels = (Vec<u8>, Vec<u8>, Vec<u8>);
+
+impl Parameters {
+    fn levels(&self) -> Result<Levels, String> {
+        let default = self.level.map_or(vec![1, 2, 3], |level| vec![level; 3]);
+        let equations = self.equations.clone().unwrap_or_else(|| default.clone());
+        let systems = self.systems.clone().unwrap_or_else(|| default.clone());
+
+        if let Some(level) = equations
+            .iter()
+            .chain(&self.cauchy)
+            .find(|level| !(1..=3).contains(*level))
+        {
+            return Err(format!(
+                "unknown level {level} of an equation, the levels are 1, 2 and 3"
+            ));
         }
-    };
+        if let Some(level) = systems.iter().find(|level| !(1..=5).contains(*level)) {
+            return Err(format!("unknown level {level} of a system, the levels are 1 to 5"));
+        }
+        Ok((equations, self.cauchy.clone(), systems))
+    }
+}
 
-    // Bi quadratic complex
-    let eq3 = {
-        // let root_options: [f64; _] = [-3., -2., -1., 1., 2., 3.];
-        let im = *[2., 3., 4.].choose(&mut rng).unwrap();
+/// The levels of `plan.typ`:
+/// 1. distinct real roots; an exponent plus a polynomial, or an exponent times x;
+/// 2. a double real root or imaginary roots; a resonant term plus a misleading one;
+/// 3. imaginary roots of multiplicity 2; a resonant term.
+fn generate_equation(level: u8, rng: &mut impl Rng) -> LinEq {
+    match level {
+        1 => generate_eq1(rng),
+        2 => generate_eq2(rng),
+        _ => generate_eq3(rng),
+    }
+}
 
-        let mut eq = LinEq::from_roots(vec![
-            Root::Complex { re: 0., im },
-            Root::Complex { re: 0., im },
-        ]);
+/// The levels of `plan.typ`:
+/// 1. 2d, distinct real roots; an exponent plus a polynomial, or an exponent times t;
+/// 2. 2d, one Jordan cell or complex roots; a resonant term plus a misleading one;
+/// 3. 3d, Jordan cells of sizes 2 and 1; a resonant term.
+///
+/// and the larger systems:
+/// 4. 3d, distinct real roots, or a real root and a complex pair; an exponent;
+/// 5. 4d, distinct real roots, a Jordan cell of size 2 and a complex pair, or two
+///    real roots and a complex pair; an exponent.
+fn generate_system(level: u8, rng: &mut impl Rng) -> Result<LinSys, String> {
+    match level {
+        1 => generate_sys1(rng),
+        2 => generate_sys2(rng),
+        3 => generate_sys3(rng),
+        4 => generate_sys4(rng),
+        _ => generate_sys5(rng),
+    }
+}
 
-        let coeff1 = *[-5., -3., -2., 2., 3., 5., 7.].choose(&mut rng).unwrap();
+/// A Cauchy problem for an equation of the level: small integer initial values for
+/// which the constants come out as small integers or simple fractions, failing that,
+/// the initial values of small integer constants.
+fn generate_ivp(level: u8, rng: &mut impl Rng) -> LinEq {
+    let eq = generate_equation(level, rng);
+    let order = eq.order();
+
+    let is_simple = |c: &Q| c.numer().abs() <= 10 && *c.denom() <= 6;
+    for _ in 0..MAX_ATTEMPTS {
+        let initial_values = (0..order).map(|_| q(rng.random_range(-3..=3))).collect();
+        if let Some(problem) = eq.clone().with_initial_values(initial_values)
+            && problem
+                .ivp
+                .as_ref()
+                .is_some_and(|ivp| ivp.constants.iter().all(is_simple))
+        {
+            return problem;
+        }
+    }
 
-        let y1 = QPoly {
-            re: 0.,
-            im: im,
-            pcos: polynomial![0., 0., coeff1],
-            psin: Poly::zero(),
-        };
+    let constants = (0..order)
+        .map(|_| q(*[-2, -1, 1, 2].choose(rng).unwrap()))
+        .collect();
+    eq.with_constants(constants)
+}
 
-        eq = eq.with_y_part(y1);
-        eq
+// Task 1:
+// - distinct real roots
+// - e^x + x^2 or x e^x
+fn generate_eq1(rng: &mut impl Rng) -> LinEq {
+    let root_options: [i64; _] = [-3, -2, -1, 1, 2, 3];
+    let roots = loop {
+        let (a, b) = (
+            root_options.choose(rng).unwrap(),
+            root_options.choose(rng).unwrap(),
+        );
+        if a.abs() != b.abs() {
+            break (*a, *b);
+        }
     };
+    let mut eq = LinEq::from_roots(vec![Root::Real(q(roots.0)), Root::Real(q(roots.1))]);
 
-    let sys1 = {
-        let root_options: [f64; _] = [-3., -2., -1., 1., 2., 3.];
-        let roots = loop {
-            let (a, b) = (
-                root_options.choose(&mut rng).unwrap(),
-                root_options.choose(&mut rng).unwrap(),
-            );
-            if a.abs() != b.abs() {
-                break (*a, *b);
-            }
+    let k = loop {
+        let k = *root_options.choose(rng).unwrap();
+        if k != roots.0 && k != roots.1 {
+            break k;
+        }
+    };
+    let coeff_options = [-5, -3, -2, 2, 3, 5, 7];
+    let coeff1 = q(*coeff_options.choose(rng).unwrap());
+    let coeff2 = q(*coeff_options.choose(rng).unwrap());
+    let coeff3 = q(*coeff_options.choose(rng).unwrap());
+
+    if rng.random_bool(0.5) {
+        let y1 = QPoly {
+            re: q(k),
+            im: Q::zero(),
+            pcos: polynomial![coeff1],
+            psin: Poly::zero(),
         };
 
-        let j_mat = Matrix2::from_diagonal(&vector![roots.0, roots.1]);
-        let j_blocks = vec![(Root::Real(roots.0), 1), (Root::Real(roots.1), 1)];
+        let y2 = QPoly {
+            re: Q::zero(),
+            im: Q::zero(),
+            pcos: polynomial![coeff2, Q::zero(), coeff3],
+            psin: Poly::zero(),
+        };
+        eq = eq.with_y_part(y1).with_y_part(y2);
+    } else {
+        let y1 = QPoly {
+            re: q(k),
+            im: Q::zero(),
+            pcos: polynomial![Q::zero(), Q::zero(), coeff3],
+            psin: Poly::zero(),
+        };
 
-        let c_mat = 'c: loop {
-            let data: [f64; 4] =
-                std::array::from_fn(|_| *[-2., -1., 1., 2.].choose(&mut rng).unwrap());
+        eq = eq.with_y_part(y1);
+    }
+    eq
+}
 
-            let c_mat = Matrix2::from_row_slice(&data);
+fn generate_eq2(rng: &mut impl Rng) -> LinEq {
+    if rng.random_bool(0.5) {
+        let root = q(*[-3, -2, -1, 1, 2, 3].choose(rng).unwrap());
+        let mut eq = LinEq::from_roots(vec![Root::Real(root), Root::Real(root)]);
 
-            if let Some(inv) = c_mat.try_inverse() {
-                for &el in inv.as_slice().iter() {
-                    if el.floor() != el {
-                        continue 'c;
-                    }
-                }
+        let coeff1 = q(*[-5, -3, -2, 2, 3, 5, 7].choose(rng).unwrap());
+        let coeff2 = q(*[-5, -3, -2, 2, 3, 5, 7].choose(rng).unwrap());
 
-                break c_mat;
-            }
+        let y1 = QPoly {
+            re: root,
+            im: Q::zero(),
+            pcos: polynomial![Q::zero(), Q::zero(), coeff1],
+            psin: Poly::zero(),
         };
 
-        let a_mat = c_mat * j_mat * c_mat.try_inverse().unwrap();
-
-        let mut sys = LinSys::new(
-            a_mat.resize(a_mat.nrows(), a_mat.ncols(), 0.),
-            c_mat.resize(c_mat.nrows(), c_mat.ncols(), 0.),
-            j_blocks,
-        );
-
-        let k = loop {
-            let k = *root_options.choose(&mut rng).unwrap();
-            if k != roots.0 && k != roots.1 {
-                break k;
-            }
+        let y2 = QPoly {
+            re: -root,
+            im: Q::zero(),
+            pcos: polynomial![coeff2],
+            psin: Poly::zero(),
         };
-        let coeff_options = [-5., -3., -2., 2., 3., 5., 7.];
-        let coeff1 = *coeff_options.choose(&mut rng).unwrap();
-        let coeff2 = *coeff_options.choose(&mut rng).unwrap();
-        let coeff3 = *coeff_options.choose(&mut rng).unwrap();
-
-        if rand::random_bool(0.5) {
-            let mut p1 = vec![polynomial![coeff1], Poly::zero()];
-            p1.shuffle(&mut rng);
-
-            let y1 = VQPoly {
-                re: k,
-                im: 0.,
-                pcos: DVector::from_vec(p1),
-                psin: DVector::from_vec(vec![Poly::zero(), Poly::zero()]),
-            };
+        eq = eq.with_y_part(y1).with_y_part(y2);
+        eq
+    } else {
+        let im = q(*[2, 3, 4].choose(rng).unwrap());
+        let mut eq = LinEq::from_roots(vec![Root::Complex { re: Q::zero(), im }]);
 
-            let mut p2 = vec![polynomial![coeff2], polynomial![0., 0., coeff3]];
-            p2.shuffle(&mut rng);
+        let re = q(*[-3, -2, -1, 1, 2, 3].choose(rng).unwrap());
+        let coeff1 = q(*[-5, -3, -2, 2, 3, 5, 7].choose(rng).unwrap());
+        let coeff2 = q(*[-5, -3, -2, 2, 3, 5, 7].choose(rng).unwrap());
 
-            let y2 = VQPoly {
-                re: 0.,
-                im: 0.,
-                pcos: DVector::from_vec(p2),
-                psin: DVector::from_vec(vec![Poly::zero(), Poly::zero()]),
+        if rng.random_bool(0.5) {
+            let y1 = QPoly {
+                re: Q::zero(),
+                im: im,
+                pcos: polynomial![coeff1],
+                psin: Poly::zero(),
+            };
+            let y2 = QPoly {
+                re: re,
+                im: im,
+                pcos: Poly::zero(),
+                psin: polynomial![coeff2],
             };
-            sys = sys.with_y_part(y1).with_y_part(y2);
+            eq = eq.with_y_part(y1).with_y_part(y2);
+            eq.y_part.shuffle(rng);
         } else {
-            let mut p1 = vec![polynomial![coeff1, 0., coeff3], polynomial![0., coeff2, 0.]];
-            p1.shuffle(&mut rng);
-            let y1 = VQPoly {
-                re: k,
-                im: 0.,
-                pcos: DVector::from_vec(p1),
-                psin: DVector::from_vec(vec![polynomial![], polynomial![]]),
+            let y1 = QPoly {
+                re: Q::zero(),
+                im: im,
+                pcos: Poly::zero(),
+                psin: polynomial![coeff1],
             };
-
-            sys = sys.with_y_part(y1);
+            let y2 = QPoly {
+                re: re,
+                im: im,
+                pcos: polynomial![coeff2],
+                psin: Poly::zero(),
+            };
+            eq = eq.with_y_part(y1).with_y_part(y2);
+            eq.y_part.shuffle(rng);
         }
-        sys
+        eq
+    }
+}
+
+// Bi quadratic complex
+fn generate_eq3(rng: &mut impl Rng) -> LinEq {
+    let im = q(*[2, 3, 4].choose(rng).unwrap());
+
+    let mut eq = LinEq::from_roots(vec![
+        Root::Complex { re: Q::zero(), im },
+        Root::Complex { re: Q::zero(), im },
+    ]);
+
+    let coeff1 = q(*[-5, -3, -2, 2, 3, 5, 7].choose(rng).unwrap());
+
+    let y1 = QPoly {
+        re: Q::zero(),
+        im: im,
+        pcos: polynomial![Q::zero(), Q::zero(), coeff1],
+        psin: Poly::zero(),
     };
 
-    let sys2 = {
-        if rand::random_bool(0.5) {
-            let root_options: [f64; _] = [-3., -2., -1., 1., 2., 3.];
-            let root = *root_options.choose(&mut rng).unwrap();
+    eq = eq.with_y_part(y1);
+    eq
+}
 
-            let j_mat = Matrix2::from_row_slice(&[root, 1., 0., root]);
-            let j_blocks = vec![(Root::Real(root), 2)];
+fn generate_sys1(rng: &mut impl Rng) -> Result<LinSys, String> {
+    let root_options: [i64; _] = [-3, -2, -1, 1, 2, 3];
+    let roots = loop {
+        let (a, b) = (
+            root_options.choose(rng).unwrap(),
+            root_options.choose(rng).unwrap(),
+        );
+        if a.abs() != b.abs() {
+            break (*a, *b);
+        }
+    };
 
-            let c_mat = 'c: loop {
-                let data: [f64; 4] =
-                    std::array::from_fn(|_| *[-2., -1., 1., 2.].choose(&mut rng).unwrap());
+    let j_mat = DMatrix::from_diagonal(&DVector::from_vec(vec![q(roots.0), q(roots.1)]));
+    let j_blocks = vec![(Root::Real(q(roots.0)), 1), (Root::Real(q(roots.1)), 1)];
 
-                let c_mat = Matrix2::from_row_slice(&data);
+    let (c_mat, c_inv) = random_c_matrix(&j_mat, rng)?;
+    let a_mat = &c_mat * j_mat * c_inv;
 
-                if let Some(inv) = c_mat.try_inverse() {
-                    for &el in inv.as_slice().iter() {
-                        if el.floor() != el {
-                            continue 'c;
-                        }
-                    }
+    let mut sys = LinSys::new(a_mat, c_mat, j_blocks);
 
-                    break c_mat;
-                }
-            };
+    let k = loop {
+        let k = *root_options.choose(rng).unwrap();
+        if k != roots.0 && k != roots.1 {
+            break k;
+        }
+    };
+    let coeff_options = [-5, -3, -2, 2, 3, 5, 7];
+    let coeff1 = q(*coeff_options.choose(rng).unwrap());
+    let coeff2 = q(*coeff_options.choose(rng).unwrap());
+    let coeff3 = q(*coeff_options.choose(rng).unwrap());
 
-            let a_mat = c_mat * j_mat * c_mat.try_inverse().unwrap();
+    if rng.random_bool(0.5) {
+        let mut p1 = vec![polynomial![coeff1], Poly::zero()];
+        p1.shuffle(rng);
 
-            let sys = LinSys::new(
-                a_mat.resize(a_mat.nrows(), a_mat.ncols(), 0.),
-                c_mat.resize(c_mat.nrows(), c_mat.ncols(), 0.),
-                j_blocks,
-            );
+        let y1 = VQPoly {
+            re: q(k),
+            im: Q::zero(),
+            pcos: DVector::from_vec(p1),
+            psin: DVector::from_vec(vec![Poly::zero(), Poly::zero()]),
+        };
 
-            let coeff_options = [-5., -3., -2., 2., 3., 5., 7.];
-            let coeff1 = *coeff_options.choose(&mut rng).unwrap();
-            let coeff2 = *coeff_options.choose(&mut rng).unwrap();
+        let mut p2 = vec![polynomial![coeff2], polynomial![Q::zero(), Q::zero(), coeff3]];
+        p2.shuffle(rng);
 
-            let mut p1 = vec![polynomial![coeff1], Poly::zero()];
-            let mut p2 = vec![Poly::zero(), polynomial![coeff2]];
-            if rand::random_bool(0.5) {
-                std::mem::swap(&mut p1, &mut p2);
-            }
+        let y2 = VQPoly {
+            re: Q::zero(),
+            im: Q::zero(),
+            pcos: DVector::from_vec(p2),
+            psin: DVector::from_vec(vec![Poly::zero(), Poly::zero()]),
+        };
+        sys = sys.with_y_part(y1).with_y_part(y2);
+    } else {
+        let mut p1 = vec![
+            polynomial![coeff1, Q::zero(), coeff3],
+            polynomial![Q::zero(), coeff2],
+        ];
+        p1.shuffle(rng);
+        let y1 = VQPoly {
+            re: q(k),
+            im: Q::zero(),
+            pcos: DVector::from_vec(p1),
+            psin: DVector::from_vec(vec![polynomial![], polynomial![]]),
+        };
 
-            let y1 = VQPoly {
-                re: root,
-                im: 0.,
-                pcos: DVector::from_vec(p1),
-                psin: DVector::from_vec(vec![Poly::zero(), Poly::zero()]),
-            };
+        sys = sys.with_y_part(y1);
+    }
+    Ok(sys)
+}
 
-            let y2 = VQPoly {
-                re: -root,
-                im: 0.,
-                pcos: DVector::from_vec(p2),
-                psin: DVector::from_vec(vec![Poly::zero(), Poly::zero()]),
-            };
+fn generate_sys2(rng: &mut impl Rng) -> Result<LinSys, String> {
+    if rng.random_bool(0.5) {
+        let root_options = [-3, -2, -1, 1, 2, 3];
+        let root = q(*root_options.choose(rng).unwrap());
 
-            sys.with_y_part(y1).with_y_part(y2)
-        } else {
-            let root_options: [f64; _] = [-3., -2., -1., 1., 2., 3.];
-            let re = *root_options.choose(&mut rng).unwrap();
-            let im = *[1., 2., 3.].choose(&mut rng).unwrap();
+        let j_mat = DMatrix::from_row_slice(2, 2, &[root, Q::one(), Q::zero(), root]);
+        let j_blocks = vec![(Root::Real(root), 2)];
 
-            let j_mat = Matrix2::from_row_slice(&[re, im, -im, re]);
-            let j_blocks = vec![(Root::Complex { re, im }, 1)];
+        let (c_mat, c_inv) = random_c_matrix(&j_mat, rng)?;
+        let a_mat = &c_mat * j_mat * c_inv;
 
-            let c_mat = 'c: loop {
-                let data: [f64; 4] =
-                    std::array::from_fn(|_| *[-2., -1., 1., 2.].choose(&mut rng).unwrap());
+        let sys = LinSys::new(a_mat, c_mat, j_blocks);
 
-                let c_mat = Matrix2::from_row_slice(&data);
+        let coeff_options = [-5, -3, -2, 2, 3, 5, 7];
+        let coeff1 = q(*coeff_options.choose(rng).unwrap());
+        let coeff2 = q(*coeff_options.choose(rng).unwrap());
 
-                if let Some(inv) = c_mat.try_inverse() {
-                    for &el in inv.as_slice().iter() {
-                        if el.floor() != el {
-                            continue 'c;
-                        }
-                    }
+        let mut p1 = vec![polynomial![coeff1], Poly::zero()];
+        let mut p2 = vec![Poly::zero(), polynomial![coeff2]];
+        if rng.random_bool(0.5) {
+            std::mem::swap(&mut p1, &mut p2);
+        }
 
-                    break c_mat;
-                }
-            };
+        let y1 = VQPoly {
+            re: root,
+            im: Q::zero(),
+            pcos: DVector::from_vec(p1),
+            psin: DVector::from_vec(vec![Poly::zero(), Poly::zero()]),
+        };
 
-            let a_mat = c_mat * j_mat * c_mat.try_inverse().unwrap();
+        let y2 = VQPoly {
+            re: -root,
+            im: Q::zero(),
+            pcos: DVector::from_vec(p2),
+            psin: DVector::from_vec(vec![Poly::zero(), Poly::zero()]),
+        };
 
-            let sys = LinSys::new(
-                a_mat.resize(a_mat.nrows(), a_mat.ncols(), 0.),
-                c_mat.resize(c_mat.nrows(), c_mat.ncols(), 0.),
-                j_blocks,
-            );
+        Ok(sys.with_y_part(y1).with_y_part(y2))
+    } else {
+        let root_options = [-3, -2, -1, 1, 2, 3];
+        let re = q(*root_options.choose(rng).unwrap());
+        let im = q(*[1, 2, 3].choose(rng).unwrap());
 
-            let coeff_options = [-5., -3., -2., 2., 3., 5., 7.];
-            let coeff1 = *coeff_options.choose(&mut rng).unwrap();
-            let coeff2 = *coeff_options.choose(&mut rng).unwrap();
+        let j_mat = DMatrix::from_row_slice(2, 2, &[re, im, -im, re]);
+        let j_blocks = vec![(Root::Complex { re, im }, 1)];
 
-            let mut p1 = vec![polynomial![coeff1], Poly::zero()];
-            let mut p2 = vec![Poly::zero(), polynomial![coeff2]];
-            if rand::random_bool(0.5) {
-                std::mem::swap(&mut p1, &mut p2);
-            }
+        let (c_mat, c_inv) = random_c_matrix(&j_mat, rng)?;
+        let a_mat = &c_mat * j_mat * c_inv;
 
-            let y1 = VQPoly {
-                re: re,
-                im: 0.,
-                pcos: DVector::from_vec(p1),
-                psin: DVector::from_vec(vec![Poly::zero(), Poly::zero()]),
-            };
+        let sys = LinSys::new(a_mat, c_mat, j_blocks);
 
-            let y2 = VQPoly {
-                re: re,
-                im: im,
-                pcos: DVector::from_vec(p2),
-                psin: DVector::from_vec(vec![Poly::zero(), Poly::zero()]),
-            };
+        let coeff_options = [-5, -3, -2, 2, 3, 5, 7];
+        let coeff1 = q(*coeff_options.choose(rng).unwrap());
+        let coeff2 = q(*coeff_options.choose(rng).unwrap());
 
-            sys.with_y_part(y1).with_y_part(y2)
+        let mut p1 = vec![polynomial![coeff1], Poly::zero()];
+        let mut p2 = vec![Poly::zero(), polynomial![coeff2]];
+        if rng.random_bool(0.5) {
+            std::mem::swap(&mut p1, &mut p2);
         }
-    };
 
-    let sys3 = {
-        let root_options: [f64; _] = [-3., -2., -1., 1., 2., 3.];
-        let root = *root_options.choose(&mut rng).unwrap();
-
-        let j_mat = Matrix3::from_row_slice(&[
-            root, 1., 0., //
-            0., root, 0., //
-            0., 0., root, //
-        ]);
-        let j_blocks = vec![(Root::Real(root), 2), (Root::Real(root), 1)];
-
-        let c_mat = 'try_c: loop {
-            let data: [f64; 9] =
-                std::array::from_fn(|_| *[-2., -1., 1., 2.].choose(&mut rng).unwrap());
-
-            let c_mat = Matrix3::from_row_slice(&data);
-
-            if c_mat.abs().sum() >= 7.
-                && let Some(inv) = c_mat.try_inverse()
-            {
-                for &el in inv.as_slice().iter() {
-                    if el.floor() != el {
-                        continue 'try_c;
-                    }
-                }
-
-                break c_mat;
-            }
+        let y1 = VQPoly {
+            re: re,
+            im: Q::zero(),
+            pcos: DVector::from_vec(p1),
+            psin: DVector::from_vec(vec![Poly::zero(), Poly::zero()]),
         };
 
-        let a_mat = c_mat * j_mat * c_mat.try_inverse().unwrap();
+        let y2 = VQPoly {
+            re: re,
+            im: im,
+            pcos: DVector::from_vec(p2),
+            psin: DVector::from_vec(vec![Poly::zero(), Poly::zero()]),
+        };
 
-        let sys = LinSys::new(
-            a_mat.resize(a_mat.nrows(), a_mat.ncols(), 0.),
-            c_mat.resize(c_mat.nrows(), c_mat.ncols(), 0.),
-            j_blocks,
-        );
+        Ok(sys.with_y_part(y1).with_y_part(y2))
+    }
+}
 
-        let coeff_options = [2., 3., 5., 7.];
-        let coeff1 = *coeff_options.choose(&mut rng).unwrap();
+fn generate_sys3(rng: &mut impl Rng) -> Result<LinSys, String> {
+    let root_options = [-3, -2, -1, 1, 2, 3];
+    let root = q(*root_options.choose(rng).unwrap());
+
+    let j_mat = DMatrix::from_row_slice(
+        3,
+        3,
+        &[
+            root, Q::one(), Q::zero(), //
+            Q::zero(), root, Q::zero(), //
+            Q::zero(), Q::zero(), root, //
+        ],
+    );
+    let j_blocks = vec![(Root::Real(root), 2), (Root::Real(root), 1)];
 
-        let mut p1 = vec![polynomial![coeff1], Poly::zero(), Poly::zero()];
-        p1.shuffle(&mut rng);
+    let (c_mat, c_inv) = random_c_matrix(&j_mat, rng)?;
+    let a_mat = &c_mat * j_mat * c_inv;
 
-        let y1 = VQPoly {
-            re: root,
-            im: 0.,
-            pcos: DVector::from_vec(p1),
-            psin: DVector::from_vec(vec![Poly::zero(), Poly::zero(), Poly::zero()]),
-        };
+    let sys = LinSys::new(a_mat, c_mat, j_blocks);
+
+    let coeff_options = [2, 3, 5, 7];
+    let coeff1 = q(*coeff_options.choose(rng).unwrap());
+
+    let mut p1 = vec![polynomial![coeff1], Poly::zero(), Poly::zero()];
+    p1.shuffle(rng);
 
-        sys.with_y_part(y1)
+    let y1 = VQPoly {
+        re: root,
+        im: Q::zero(),
+        pcos: DVector::from_vec(p1),
+        psin: DVector::from_vec(vec![Poly::zero(), Poly::zero(), Poly::zero()]),
     };
 
-    let problem_code = String::from(include_str!("problem.typ"));
-    let solution_code = String::from(include_str!("solution.typ"));
+    Ok(sys.with_y_part(y1))
+}
 
-    let problem_engine = typst_as_lib::TypstEngine::builder()
-        .main_file(problem_code.clone())
-        .search_fonts_with(
-            TypstKitFontOptions::default()
-                .include_system_fonts(false)
-                // This line is not necessary, because thats the default.
-                .include_embedded_fonts(true),
-        )
-        .build();
+fn generate_sys4(rng: &mut impl Rng) -> Result<LinSys, String> {
+    let j_blocks = if rng.random_bool(0.5) {
+        distinct_real_roots(3, rng)
+            .into_iter()
+            .map(|root| (Root::Real(root), 1))
+            .collect()
+    } else {
+        vec![
+            (Root::Real(distinct_real_roots(1, rng)[0]), 1),
+            random_complex_pair(rng),
+        ]
+    };
+    system_with_exponent(j_blocks, rng)
+}
 
-    let solution_engine = typst_as_lib::TypstEngine::builder()
-        .main_file(solution_code.clone())
-        .search_fonts_with(
-            TypstKitFontOptions::default()
-                .include_system_fonts(false)
-                // This line is not necessary, because thats the default.
-                .include_embedded_fonts(true),
-        )
-        .build();
+fn generate_sys5(rng: &mut impl Rng) -> Result<LinSys, String> {
+    let j_blocks = match rng.random_range(0..3) {
+        0 => distinct_real_roots(4, rng)
+            .into_iter()
+            .map(|root| (Root::Real(root), 1))
+            .collect(),
+        1 => vec![
+            (Root::Real(distinct_real_roots(1, rng)[0]), 2),
+            random_complex_pair(rng),
+        ],
+        _ => {
+            let roots = distinct_real_roots(2, rng);
+            vec![
+                (Root::Real(roots[0]), 1),
+                (Root::Real(roots[1]), 1),
+                random_complex_pair(rng),
+            ]
+        }
+    };
+    system_with_exponent(j_blocks, rng)
+}
 
-    let tasks = vec![
-        Task {
-            problem: "Для следующих однородного и неоднородного уравнений, найдите общее решение"
-                .to_string(),
-            equation_homo: eq1.eq_homo_as_typst(),
-            equation: eq1.eq_as_typst(),
-            char_equation: eq1.char_eq_as_typst(),
-            solution_homo: eq1.solution_homo_as_typst(),
-            solution: eq1.solution_as_typst(),
-            char_roots: eq1.char_roots_as_typst()
-        },
-        Task {
-            problem: "Для следующих однородного и неоднородного уравнений, найдите общее решение"
-                .to_string(),
-            equation_homo: eq2.eq_homo_as_typst(),
-            char_equation: eq2.char_eq_as_typst(),
-            solution_homo: eq2.solution_homo_as_typst(),
-            equation: eq2.eq_as_typst(),
-            solution: eq2.solution_as_typst(),
-            char_roots: eq2.char_roots_as_typst()
-        },
-        Task {
-            problem: "Для следующих однородного и неоднородного уравнений, найдите общее решение"
-                .to_string(),
-            equation_homo: eq3.eq_homo_as_typst(),
-            char_equation: eq3.char_eq_as_typst(),
-            solution_homo: eq3.solution_homo_as_typst(),
-            equation: eq3.eq_as_typst(),
-            solution: eq3.solution_as_typst(),
-            char_roots: eq3.char_roots_as_typst()
-        },
-        Task {
-            problem: "Для следующих однородной и неоднородной системы уравнений уравнений, найдите общее решение"
-                .to_string(),
-            equation_homo: sys1.eq_homo_as_typst(),
-            equation: sys1.eq_as_typst(),
-            char_equation: sys1.char_eq_as_typst(),
-            solution_homo: sys1.solution_homo_as_typst(),
-            solution: sys1.solution_as_typst(),
-            char_roots: sys1.char_roots_as_typst()
-        },
-        Task {
-            problem: "Для следующих однородной и неоднородной системы уравнений уравнений, найдите общее решение"
-                .to_string(),
-            equation_homo: sys2.eq_homo_as_typst(),
-            char_equation: sys2.char_eq_as_typst(),
-            solution_homo: sys2.solution_homo_as_typst(),
-            equation: sys2.eq_as_typst(),
-            solution: sys2.solution_as_typst(),
-            char_roots: sys2.char_roots_as_typst()
-        },
-        Task {
-            problem: "Для следующих однородной и неоднородной системы уравнений уравнений, найдите общее решение"
-                .to_string(),
-            equation_homo: sys3.eq_homo_as_typst(),
-            char_equation: sys3.char_eq_as_typst(),
-            solution_homo: sys3.solution_homo_as_typst(),
-            equation: sys3.eq_as_typst(),
-            solution: sys3.solution_as_typst(),
-            char_roots: sys3.char_roots_as_typst()
-        },
-    ];
+/// Non-zero real roots from -4 to 4 with distinct absolute values.
+fn distinct_real_roots(count: usize, rng: &mut impl Rng) -> Vec<Q> {
+    let mut magnitudes = vec![1, 2, 3, 4];
+    magnitudes.shuffle(rng);
+    magnitudes
+        .into_iter()
+        .take(count)
+        .map(|m| q(if rng.random_bool(0.5) { m } else { -m }))
+        .collect()
+}
 
-    let content = Content {
-            variant: input.variant_number.to_string(),
-            tasks: tasks.clone(),
-        };
+fn random_complex_pair(rng: &mut impl Rng) -> (Root, usize) {
+    let re = q(*[-2, -1, 0, 1, 2].choose(rng).unwrap());
+    let im = q(*[1, 2, 3].choose(rng).unwrap());
+    (Root::Complex { re, im }, 1)
+}
 
-    // log::debug!("{content:?}");
+/// `y' = A y + f` with `A = C J C^-1` for the Jordan blocks and a random integer `C`
+/// with an integer inverse; `f` is a non-resonant exponent in one of the equations.
+fn system_with_exponent(
+    j_blocks: Vec<(Root, usize)>,
+    rng: &mut impl Rng,
+) -> Result<LinSys, String> {
+    let j_mat = jordan_matrix(&j_blocks);
+    let n = j_mat.nrows();
 
-    let problem_doc: PagedDocument = problem_engine
-        .compile_with_input(content)
-        .output
-        .expect("typst::compile() returned an error!");
+    let (c_mat, c_inv) = random_c_matrix(&j_mat, rng)?;
+    let a_mat = &c_mat * j_mat * c_inv;
 
-    let solution_doc: PagedDocument = solution_engine
-        .compile_with_input(Content {
-            variant: input.variant_number.to_string(),
-            tasks: tasks.clone(),
-        })
-        .output
-        .expect("typst::compile() returned an error!");
+    let sys = LinSys::new(a_mat, c_mat, j_blocks);
 
-    let problem_output = problem_doc
-        .pages
-        .iter()
-        .map(|page| render_page_to_png(page, 300. / 72.))
-        .map(|img| BASE64_STANDARD.encode(img))
-        .collect::<Vec<_>>();
-    let solution_output = solution_doc
-        .pages
-        .iter()
-        .map(|page| render_page_to_png(page, 300. / 72.))
-        .map(|img| BASE64_STANDARD.encode(img))
-        .collect::<Vec<_>>();
+    let k = loop {
+        let k = q(*[-3, -2, -1, 1, 2, 3].choose(rng).unwrap());
+        if !sys.roots.iter().any(|(root, _)| *root == Root::Real(k)) {
+            break k;
+        }
+    };
+    let coeff = q(*[-5, -3, -2, 2, 3, 5, 7].choose(rng).unwrap());
 
-    let output = VariantGeneratorOutput {
-        variant_number: input.variant_number,
-        generator: input.generator,
-        problem_code,
-        problem_images: problem_output,
-        solution_code,
-        solution_images: solution_output,
+    let mut p = vec![Poly::zero(); n];
+    p[0] = polynomial![coeff];
+    p.shuffle(rng);
+
+    let y = VQPoly {
+        re: k,
+        im: Q::zero(),
+        pcos: DVector::from_vec(p),
+        psin: DVector::from_vec(vec![Poly::zero(); n]),
     };
+    Ok(sys.with_y_part(y))
+}
 
-    println!("{}", serde_json::to_string(&output)?);
+/// Rejected tasks are logged and generated again, up to this many times.
+const MAX_ATTEMPTS: usize = 100;
+
+/// Generates until the answer passes verification. The exact arithmetic panics on
+/// overflow, even in release builds, such tasks are rejected as well.
+fn verified<R: Rng, T>(
+    rng: &mut R,
+    generate: impl Fn(&mut R) -> Result<T, String>,
+    verify: impl Fn(&T) -> Result<(), String>,
+) -> Result<T, Box<dyn std::error::Error>> {
+    for _ in 0..MAX_ATTEMPTS {
+        let task = std::panic::catch_unwind(AssertUnwindSafe(|| {
+            generate(rng).and_then(|task| verify(&task).map(|()| task))
+        }))
+        .unwrap_or_else(|_| Err("arithmetic overflow".to_string()));
+        match task {
+            Ok(task) => return Ok(task),
+            Err(err) => log::error!("Rejected a generated task: {err}"),
+        }
+    }
+    Err(format!("no valid task in {MAX_ATTEMPTS} attempts").into())
+}
+
+/// `--self-test N`: generates the tasks for the seeds `0..N` and verifies every
+/// answer, without rendering anything.
+fn self_test(count: u64) -> Result<(), Box<dyn std::error::Error>> {
+    let mut failures = 0;
+    for seed in 0..count {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let results = [
+            ("eq1", verify_equation(&generate_eq1(&mut rng))),
+            ("eq2", verify_equation(&generate_eq2(&mut rng))),
+            ("eq3", verify_equation(&generate_eq3(&mut rng))),
+            ("ivp1", verify_equation(&generate_ivp(1, &mut rng))),
+            ("ivp2", verify_equation(&generate_ivp(2, &mut rng))),
+            ("ivp3", verify_equation(&generate_ivp(3, &mut rng))),
+            ("variation", verify_variation(&generate_variation(&mut rng))),
+            ("euler", verify_euler(&generate_euler(&mut rng))),
+            ("sys1", generate_sys1(&mut rng).and_then(|sys| verify_system(&sys))),
+            ("sys2", generate_sys2(&mut rng).and_then(|sys| verify_system(&sys))),
+            ("sys3", generate_sys3(&mut rng).and_then(|sys| verify_system(&sys))),
+            ("sys4", generate_sys4(&mut rng).and_then(|sys| verify_system(&sys))),
+            ("sys5", generate_sys5(&mut rng).and_then(|sys| verify_system(&sys))),
+        ];
+        for (task, result) in results {
+            if let Err(err) = result {
+                failures += 1;
+                eprintln!("seed {seed}, {task}: {err}");
+            }
+        }
+    }
 
+    println!("{count} seeds, {failures} failed answers");
+    if failures > 0 {
+        return Err(format!("{failures} answers failed verification").into());
+    }
     Ok(())
 }
 
@@ -597,8 +843,38 @@ struct Task {
     equation: String,
     char_equation: String,
     char_roots: String,
+    /// The roots are given in the problem, for systems with a cubic or higher
+    /// characteristic polynomial.
+    show_char_roots: bool,
     solution_homo: String,
     solution: String,
+    /// Empty unless the task is a Cauchy problem.
+    initial_conditions: String,
+    constants: String,
+    specific_solution: String,
+    /// Worked solution in Typst markup, for the variation of constants and the Euler
+    /// equations, otherwise empty.
+    steps: String,
+}
+
+/// Random matrix `C` with entries in {-2, -1, 1, 2} whose inverse (returned too) has
+/// small integer entries, so that `C J C^-1` for the integer Jordan matrix `J` has
+/// small integer entries as well.
+fn random_c_matrix(
+    j_mat: &DMatrix<Q>,
+    rng: &mut impl Rng,
+) -> Result<(DMatrix<Q>, DMatrix<Q>), String> {
+    let jordan = j_mat.map(|x| x.to_integer());
+    let unimodular = Constraints::new(j_mat.nrows())
+        .with_max_inverse_entry(Some(3), false)
+        .with_product(Product {
+            jordan,
+            max_entry: Some(20),
+            nonzero_entries: false,
+        })
+        .sample(rng)
+        .ok_or_else(|| format!("no matrix C found for J = {j_mat}"))?;
+    Ok((unimodular.matrix.map(q), unimodular.inverse.map(q)))
 }
 
 fn render_page_to_png(page: &Page, scale: f32) -> Vec<u8> {
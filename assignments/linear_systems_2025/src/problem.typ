@@ -18,11 +18,17 @@
 #show math.eq: math.display
 #show math.cases: math.display
 
-#for (n, task) in inputs.tasks.enumerate() [
+#for task in inputs.tasks [
   = #task.problem
 
-  == #eval(task.equation_homo, mode: "math") #if n == 5 [#eval(task.char_roots, mode: "math")]
+  #if task.initial_conditions != "" [
+    #eval(task.equation + ", quad " + task.initial_conditions, mode: "math")
+  ] else if task.steps != "" [
+    #eval(task.equation, mode: "math")
+  ] else [
+  == #eval(task.equation_homo, mode: "math") #if task.show_char_roots [#eval(task.char_roots, mode: "math")]
   
   == #eval(task.equation, mode: "math")
+  ]
 
 ]
@@ -19,10 +19,33 @@
 #show math.vec: math.display
 #show math.mat: math.display
 
-#for (n, task) in inputs.tasks.enumerate() [
+#for task in inputs.tasks [
   = #task.problem
 
-  == #eval(task.equation_homo, mode: "math") #if n == 5 [#eval(task.char_roots, mode: "math")]
+  #if task.initial_conditions != "" [
+  #eval(task.equation + ", quad " + task.initial_conditions, mode: "math")
+
+  Характеристическое уравнение:
+  #eval(task.char_equation, mode: "math")
+
+  Корни:
+  #eval(task.char_roots, mode: "math")
+
+  Общее решение:
+  #eval(task.solution, mode: "math")
+
+  Из начальных условий:
+  #eval(task.constants, mode: "math")
+
+  Решение задачи Коши:
+  #eval(task.specific_solution, mode: "math")
+  ] else if task.steps != "" [
+  #eval(task.equation, mode: "math")
+
+  #eval(task.steps, mode: "markup")
+  ] else [
+
+  == #eval(task.equation_homo, mode: "math") #if task.show_char_roots [#eval(task.char_roots, mode: "math")]
 
   Характеристическое уравнение:
   #eval(task.char_equation, mode: "math")
@@ -37,5 +60,6 @@
 
   Решение:
   #eval(task.solution, mode: "math")
+  ]
 
 ]
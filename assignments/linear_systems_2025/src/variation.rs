@@ -0,0 +1,230 @@
+//! Second order equations for the variation of constants: the right-hand sides are
+//! not quasi-polynomials (`1/cos x`, `e^x/x`, `1/(1 + e^x)`), so undetermined
+//! coefficients do not apply. Every formula of the worked solution is an `Expr`,
+//! printed as is and checked against the previous ones by `verify::verify_variation`.
+
+use rand::prelude::*;
+use variant_generation::expr::{Expr, cos, ctg, exp, ln, ln_abs, num, rational, sin, sum, tg, var};
+
+use crate::{LinEq, Q, Root, q};
+
+pub struct Variation {
+    /// Name of the family and the parameters of the homogeneous part, e.g. `1/cos, 2`.
+    pub family: String,
+    /// The homogeneous part.
+    pub eq: LinEq,
+    /// The right-hand side.
+    pub f: Expr,
+    /// `y_1`, `y_2` and their derivatives.
+    pub basis: [Expr; 2],
+    pub basis_derivatives: [Expr; 2],
+    pub wronskian: Expr,
+    /// `c_1'`, `c_2'` and their integrals.
+    pub c_derivatives: [Expr; 2],
+    pub c: [Expr; 2],
+    /// `c_1 y_1 + c_2 y_2`.
+    pub y_part: Expr,
+    /// Points of the domain of the right-hand side.
+    pub sample_points: Vec<f64>,
+}
+
+fn x() -> Expr {
+    var("x")
+}
+
+/// `k x`.
+fn kx(k: i64) -> Expr {
+    num(k) * x()
+}
+
+/// `c_1 y_1 + c_2 y_2` with the products expanded.
+fn particular(c: &[Expr; 2], basis: &[Expr; 2]) -> Expr {
+    sum(c
+        .iter()
+        .zip(basis)
+        .flat_map(|(c, y)| c.clone().summands().into_iter().map(|t| t * y.clone())))
+}
+
+/// `y'' + k^2 y = A/cos(k x)`.
+fn secant(k: i64, a: i64) -> Variation {
+    let basis = [cos(kx(k)), sin(kx(k))];
+    let c = [
+        rational(Q::new(a, k * k)) * ln_abs(cos(kx(k))),
+        rational(Q::new(a, k)) * x(),
+    ];
+
+    Variation {
+        family: format!("1/cos, {k}"),
+        eq: LinEq::from_roots(vec![Root::Complex { re: q(0), im: q(k) }]),
+        f: num(a) / cos(kx(k)),
+        basis_derivatives: [-num(k) * sin(kx(k)), num(k) * cos(kx(k))],
+        wronskian: num(k),
+        c_derivatives: [rational(Q::new(-a, k)) * tg(kx(k)), rational(Q::new(a, k))],
+        y_part: particular(&c, &basis),
+        basis,
+        c,
+        sample_points: [-0.4, -0.1, 0.2, 0.4].iter().map(|x| x / k as f64).collect(),
+    }
+}
+
+/// `y'' + k^2 y = A/sin(k x)`.
+fn cosecant(k: i64, a: i64) -> Variation {
+    let basis = [cos(kx(k)), sin(kx(k))];
+    let c = [
+        rational(Q::new(-a, k)) * x(),
+        rational(Q::new(a, k * k)) * ln_abs(sin(kx(k))),
+    ];
+
+    Variation {
+        family: format!("1/sin, {k}"),
+        eq: LinEq::from_roots(vec![Root::Complex { re: q(0), im: q(k) }]),
+        f: num(a) / sin(kx(k)),
+        basis_derivatives: [-num(k) * sin(kx(k)), num(k) * cos(kx(k))],
+        wronskian: num(k),
+        c_derivatives: [rational(Q::new(-a, k)), rational(Q::new(a, k)) * ctg(kx(k))],
+        y_part: particular(&c, &basis),
+        basis,
+        c,
+        sample_points: [0.2, 0.5, 0.8, 1.2].iter().map(|x| x / k as f64).collect(),
+    }
+}
+
+/// `y'' - 2 r y' + r^2 y = A e^(r x)/x^power` for the power 1 or 2.
+fn exponent_over_power(r: i64, a: i64, power: i32) -> Variation {
+    let e = exp(kx(r));
+    let basis = [e.clone(), x() * e.clone()];
+    let (c_derivatives, c) = if power == 1 {
+        (
+            [-num(a), num(a) / x()],
+            [-num(a) * x(), num(a) * ln_abs(x())],
+        )
+    } else {
+        (
+            [-num(a) / x(), num(a) / x().pow(2)],
+            [-num(a) * ln_abs(x()), -num(a) / x()],
+        )
+    };
+
+    Variation {
+        family: format!("{}, {r}", if power == 1 { "e/x" } else { "e/x^2" }),
+        eq: LinEq::from_roots(vec![Root::Real(q(r)), Root::Real(q(r))]),
+        f: num(a) * e.clone() / x().pow(power),
+        basis_derivatives: [num(r) * e.clone(), (num(1) + kx(r)) * e],
+        wronskian: exp(kx(2 * r)),
+        c_derivatives,
+        y_part: particular(&c, &basis),
+        basis,
+        c,
+        sample_points: vec![0.5, 1., 1.5, 2.],
+    }
+}
+
+/// `(-1)^n`.
+fn sign(n: i64) -> i64 {
+    if n.rem_euclid(2) == 0 { 1 } else { -1 }
+}
+
+/// `∫ e^(-r x)/(1 + e^x) dx`: with `u = e^x` the integrand is `u^n/(1 + u)` for
+/// `n = -r - 1`, that is `(-1)^n/(1 + u)` plus powers of `u`.
+fn logistic_integral(r: i64) -> Expr {
+    let n = -r - 1;
+    let powers = if n >= 0 {
+        // u^n/(1 + u) = sum of (-1)^(n-1-j) u^j over 0 <= j < n, plus (-1)^n/(1 + u)
+        (0..n)
+            .map(|j| rational(Q::new(sign(n - 1 - j), j + 1)) * exp(kx(j + 1)))
+            .collect::<Vec<_>>()
+    } else {
+        // u^(-m)/(1 + u) = sum of (-1)^(m-j) u^(-j) over 1 <= j <= m, plus (-1)^m/(1 + u)
+        let m = -n;
+        (1..=m)
+            .map(|j| match j {
+                1 => num(sign(m - 1)) * x(),
+                j => rational(Q::new(sign(m - j), 1 - j)) * exp(kx(1 - j)),
+            })
+            .collect()
+    };
+    sum(powers) + num(sign(n)) * ln(num(1) + exp(x()))
+}
+
+/// `y'' - (r_1 + r_2) y' + r_1 r_2 y = A/(1 + e^x)` for distinct integer roots.
+fn logistic(r1: i64, r2: i64, a: i64) -> Variation {
+    let basis = [exp(kx(r1)), exp(kx(r2))];
+    // c_1' = -y_2 f/W and c_2' = y_1 f/W with W = (r_2 - r_1) e^((r_1 + r_2) x)
+    let scale = [Q::new(-a, r2 - r1), Q::new(a, r2 - r1)];
+    let over_logistic = |r: i64| exp(kx(-r)) / (num(1) + exp(x()));
+    let c = [
+        rational(scale[0]) * logistic_integral(r1),
+        rational(scale[1]) * logistic_integral(r2),
+    ];
+
+    Variation {
+        family: format!("1/(1+e^x), {r1}, {r2}"),
+        eq: LinEq::from_roots(vec![Root::Real(q(r1)), Root::Real(q(r2))]),
+        f: num(a) / (num(1) + exp(x())),
+        basis_derivatives: [num(r1) * exp(kx(r1)), num(r2) * exp(kx(r2))],
+        wronskian: num(r2 - r1) * exp(kx(r1 + r2)),
+        c_derivatives: [
+            rational(scale[0]) * over_logistic(r1),
+            rational(scale[1]) * over_logistic(r2),
+        ],
+        y_part: particular(&c, &basis),
+        basis,
+        c,
+        sample_points: vec![-1., 0., 0.5, 1.],
+    }
+}
+
+/// One of the families with random parameters.
+pub fn generate_variation(rng: &mut impl Rng) -> Variation {
+    let a = *[-3, -2, -1, 1, 2, 3].choose(rng).unwrap();
+    let k = *[1, 2, 3].choose(rng).unwrap();
+    let r = *[-2, -1, 1, 2].choose(rng).unwrap();
+    match rng.random_range(0..5) {
+        0 => secant(k, a),
+        1 => cosecant(k, a),
+        2 => exponent_over_power(r, a, 1),
+        3 => exponent_over_power(r, a, 2),
+        _ => {
+            let roots = [-2, -1, 0, 1].choose_multiple(rng, 2).collect::<Vec<_>>();
+            logistic(*roots[0], *roots[1], a)
+        }
+    }
+}
+
+impl Variation {
+    pub fn eq_as_typst(&self) -> String {
+        let homo = self.eq.eq_homo_as_typst();
+        let lhs = homo.strip_suffix("=0").unwrap_or(&homo);
+        format!("{lhs}={}", self.f)
+    }
+
+    pub fn solution_as_typst(&self) -> String {
+        let [y1, y2] = self.basis.clone();
+        let general = sum([var("C_1") * y1, var("C_2") * y2, self.y_part.clone()]);
+        format!("y={general}")
+    }
+
+    /// The worked solution, Typst markup.
+    pub fn steps_as_typst(&self) -> String {
+        let [y1, y2] = &self.basis;
+        let [dy1, dy2] = &self.basis_derivatives;
+        let [dc1, dc2] = &self.c_derivatives;
+        let [c1, c2] = &self.c;
+        let f = &self.f;
+        let w = &self.wronskian;
+
+        format!(
+            "Характеристическое уравнение: ${}$, корни: ${}$\n\n\
+             Фундаментальная система решений: $y_1={y1}, quad y_2={y2}$.\n\n\
+             Ищем решение в виде $y=c_1(x) y_1+c_2(x) y_2$, где\n\
+             $ cases(c'_1 dot ({y1})+c'_2 dot ({y2})=0, c'_1 dot ({dy1})+c'_2 dot ({dy2})={f}) $\n\n\
+             Определитель Вронского: $W=det mat({y1}, {y2}; {dy1}, {dy2})={w}$.\n\n\
+             $ c'_1=-(y_2 f)/W={dc1}, quad c_1={c1} + C_1, $\n\
+             $ c'_2=(y_1 f)/W={dc2}, quad c_2={c2} + C_2. $\n\n\
+             Ответ: ${}$",
+            self.eq.char_eq_as_typst(),
+            self.eq.char_roots_as_typst(),
+            self.solution_as_typst(),
+        )
+    }
+}
@@ -0,0 +1,353 @@
+//! Checks that the answers solve the printed equations before a variant is emitted.
+//!
+//! The solutions are substituted back into the equations numerically at a few
+//! sample points. The derivatives are taken as `D^k (P(x) e^(λ x))`, independently
+//! of `QPoly::derivative`, which is used to build the right-hand sides.
+
+use nalgebra::DMatrix;
+use num::{ToPrimitive, Zero, complex::Complex64};
+use variant_generation::expr::Expr;
+
+use crate::linear_system::{LinSys, VQPoly};
+use crate::euler::Euler;
+use crate::variation::Variation;
+use crate::{LinEq, Poly, Q, QPoly};
+
+const SAMPLE_POINTS: [f64; 5] = [-1., -0.5, 0., 0.5, 1.];
+
+/// Relative to the magnitude of the terms of the equation.
+const TOLERANCE: f64 = 1e-9;
+
+/// Points `x > 0` for the Euler equations.
+const EULER_SAMPLE_POINTS: [f64; 4] = [0.5, 1., 1.5, 2.];
+
+/// Step and tolerance of the derivatives by finite differences.
+const STEP: f64 = 1e-3;
+const DIFFERENCE_TOLERANCE: f64 = 1e-4;
+
+fn to_f64(x: &Q) -> f64 {
+    x.to_f64().unwrap_or(f64::NAN)
+}
+
+fn coeff(p: &Poly, i: usize) -> f64 {
+    p.coeffs().get(i).map_or(0., to_f64)
+}
+
+fn eval(p: &[Complex64], x: f64) -> Complex64 {
+    p.iter().rev().fold(Complex64::zero(), |acc, c| acc * x + c)
+}
+
+fn differentiate(p: &[Complex64]) -> Vec<Complex64> {
+    p.iter().enumerate().skip(1).map(|(i, c)| *c * i as f64).collect()
+}
+
+/// `D^k` of `e^(re x) (pcos(x) cos(im x) + psin(x) sin(im x))` at `x`: the real part
+/// of `D^k (P(x) e^(λ x)) = sum C(k, j) P^(j)(x) λ^(k - j) e^(λ x)` with
+/// `P = pcos - i psin`, `λ = re + i im`.
+fn derivative_at(term: &QPoly, k: usize, x: f64) -> f64 {
+    let lambda = Complex64::new(to_f64(&term.re), to_f64(&term.im));
+    let len = term.pcos.coeffs().len().max(term.psin.coeffs().len());
+    let mut p = (0..len)
+        .map(|i| Complex64::new(coeff(&term.pcos, i), -coeff(&term.psin, i)))
+        .collect::<Vec<_>>();
+
+    let mut sum = Complex64::zero();
+    let mut binomial = 1.;
+    for j in 0..=k {
+        sum += eval(&p, x) * lambda.powu((k - j) as u32) * binomial;
+        binomial *= (k - j) as f64 / (j + 1) as f64;
+        p = differentiate(&p);
+    }
+    (sum * (lambda * x).exp()).re
+}
+
+/// `y(x), y'(x), y''(x), y'''(x)` by central differences.
+fn differences(y: &dyn Fn(f64) -> f64, x: f64) -> [f64; 4] {
+    let h = STEP;
+    [
+        y(x),
+        (y(x + h) - y(x - h)) / (2. * h),
+        (y(x + h) - 2. * y(x) + y(x - h)) / (h * h),
+        (y(x + 2. * h) - 2. * y(x + h) + 2. * y(x - h) - y(x - 2. * h)) / (2. * h * h * h),
+    ]
+}
+
+fn is_negligible(residual: f64, scale: f64) -> bool {
+    residual.abs() <= TOLERANCE * (1. + scale)
+}
+
+fn component(y: &VQPoly, row: usize) -> QPoly {
+    QPoly {
+        re: y.re,
+        im: y.im,
+        pcos: y.pcos[row].clone(),
+        psin: y.psin[row].clone(),
+    }
+}
+
+/// `L y (x) - f(x)` for `L = sum coeffs[k] D^k`, and the magnitude of its terms.
+fn equation_residual(coeffs: &[Q], y: &[QPoly], f: &[QPoly], x: f64) -> (f64, f64) {
+    let mut residual = 0.;
+    let mut scale = 0.;
+    for (k, c) in coeffs.iter().enumerate() {
+        for term in y {
+            let value = to_f64(c) * derivative_at(term, k, x);
+            residual += value;
+            scale += value.abs();
+        }
+    }
+    for term in f {
+        let value = derivative_at(term, 0, x);
+        residual -= value;
+        scale += value.abs();
+    }
+    (residual, scale)
+}
+
+/// The largest relative residual of `y' - A y - f` over the rows, with its row.
+fn system_residual(a: &DMatrix<f64>, y: &[VQPoly], f: &[VQPoly], x: f64) -> (usize, f64, f64) {
+    let n = a.nrows();
+    let values = |terms: &[VQPoly], row: usize, k: usize| -> f64 {
+        terms.iter().map(|term| derivative_at(&component(term, row), k, x)).sum()
+    };
+
+    (0..n)
+        .map(|row| {
+            let derivative = values(y, row, 1);
+            let product = (0..n)
+                .map(|col| a[(row, col)] * values(y, col, 0))
+                .collect::<Vec<_>>();
+            let forcing = values(f, row, 0);
+
+            let residual = derivative - product.iter().sum::<f64>() - forcing;
+            let scale =
+                derivative.abs() + product.iter().map(|p| p.abs()).sum::<f64>() + forcing.abs();
+            (row, residual, scale)
+        })
+        .max_by(|a, b| (a.1.abs() / (1. + a.2)).total_cmp(&(b.1.abs() / (1. + b.2))))
+        .unwrap_or((0, 0., 0.))
+}
+
+/// Every element of `y0_basis` solves the homogeneous equation, they are linearly
+/// independent (non-zero Wronskian at 0) and there are as many of them as the order;
+/// the sum of `y_part` solves the equation with the right-hand side `f`; the constants
+/// of a Cauchy problem give its initial values.
+pub fn verify_equation(eq: &LinEq) -> Result<(), String> {
+    let order = eq.coeffs.len().saturating_sub(1);
+    if eq.y0_basis.len() != order {
+        return Err(format!(
+            "{} basis solutions for an equation of order {order}",
+            eq.y0_basis.len()
+        ));
+    }
+
+    for (i, y) in eq.y0_basis.iter().enumerate() {
+        for x in SAMPLE_POINTS {
+            let (residual, scale) =
+                equation_residual(&eq.coeffs, std::slice::from_ref(y), &[], x);
+            if !is_negligible(residual, scale) {
+                return Err(format!(
+                    "basis solution {} leaves {residual} in the homogeneous equation at x = {x}",
+                    i + 1
+                ));
+            }
+        }
+    }
+
+    let wronskian = DMatrix::from_fn(order, order, |k, i| derivative_at(&eq.y0_basis[i], k, 0.));
+    if wronskian.determinant().abs() < TOLERANCE {
+        return Err("basis solutions are linearly dependent".to_string());
+    }
+
+    for x in SAMPLE_POINTS {
+        let (residual, scale) = equation_residual(&eq.coeffs, &eq.y_part, &eq.f, x);
+        if !is_negligible(residual, scale) {
+            return Err(format!("particular solution leaves {residual} at x = {x}"));
+        }
+    }
+
+    if let Some(ivp) = &eq.ivp {
+        if ivp.initial_values.len() != order || ivp.constants.len() != order {
+            return Err(format!(
+                "{} initial values and {} constants for an equation of order {order}",
+                ivp.initial_values.len(),
+                ivp.constants.len()
+            ));
+        }
+        for (k, expected) in ivp.initial_values.iter().enumerate() {
+            let basis = ivp
+                .constants
+                .iter()
+                .zip(&eq.y0_basis)
+                .map(|(c, y)| to_f64(c) * derivative_at(y, k, 0.));
+            let part = eq.y_part.iter().map(|y| derivative_at(y, k, 0.));
+            let value = basis.chain(part).sum::<f64>();
+            let expected = to_f64(expected);
+            if !is_negligible(value - expected, expected.abs()) {
+                return Err(format!(
+                    "specific solution has {value} instead of {expected} as the derivative {k} at 0"
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Same as `verify_equation` for `y' = A y + f`.
+pub fn verify_system(sys: &LinSys) -> Result<(), String> {
+    let n = sys.a_matrix.nrows();
+    if sys.y0_basis.len() != n {
+        return Err(format!(
+            "{} basis solutions for a system of {n} equations",
+            sys.y0_basis.len()
+        ));
+    }
+    let a = sys.a_matrix.map(|c| to_f64(&c));
+
+    for (i, y) in sys.y0_basis.iter().enumerate() {
+        for x in SAMPLE_POINTS {
+            let (row, residual, scale) = system_residual(&a, std::slice::from_ref(y), &[], x);
+            if !is_negligible(residual, scale) {
+                return Err(format!(
+                    "basis solution {} leaves {residual} in equation {} of the homogeneous system at t = {x}",
+                    i + 1,
+                    row + 1
+                ));
+            }
+        }
+    }
+
+    let values = DMatrix::from_fn(n, n, |row, i| {
+        derivative_at(&component(&sys.y0_basis[i], row), 0, 0.)
+    });
+    if values.determinant().abs() < TOLERANCE {
+        return Err("basis solutions are linearly dependent".to_string());
+    }
+
+    for x in SAMPLE_POINTS {
+        let (row, residual, scale) = system_residual(&a, &sys.y_part, &sys.f, x);
+        if !is_negligible(residual, scale) {
+            return Err(format!(
+                "particular solution leaves {residual} in equation {} at t = {x}",
+                row + 1
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// The basis of the homogeneous part as in `verify_equation`, then every formula of
+/// the variation of constants against the previous ones: the printed basis and the
+/// particular solution substituted into the equation, the derivatives of the basis,
+/// the Wronskian, `c_1'`, `c_2'` and their integrals. The derivatives are taken by
+/// central differences at the sample points.
+pub fn verify_variation(task: &Variation) -> Result<(), String> {
+    verify_equation(&task.eq)?;
+
+    let in_x = |e: &Expr| {
+        let e = e.clone();
+        move |x: f64| e.value(&[("x", x)])
+    };
+    for &x in &task.sample_points {
+        let value = |e: &Expr| e.value(&[("x", x)]);
+        let f = value(&task.f);
+        let solutions = [(&task.basis[0], 0.), (&task.basis[1], 0.), (&task.y_part, f)];
+        for (y, f) in solutions {
+            let terms = task
+                .eq
+                .coeffs
+                .iter()
+                .zip(differences(&in_x(y), x))
+                .map(|(c, d)| to_f64(c) * d)
+                .collect::<Vec<_>>();
+
+            let residual = terms.iter().sum::<f64>() - f;
+            let scale = terms.iter().map(|t| t.abs()).sum::<f64>() + f.abs();
+            if residual.abs() > DIFFERENCE_TOLERANCE * (1. + scale) {
+                return Err(format!(
+                    "{y} of {} leaves {residual} at x = {x}",
+                    task.family
+                ));
+            }
+        }
+
+        let [y1, y2] = task.basis.each_ref().map(value);
+        let [dy1, dy2] = task.basis_derivatives.each_ref().map(value);
+        let w = value(&task.wronskian);
+        let [dc1, dc2] = task.c_derivatives.each_ref().map(value);
+        let checks = [
+            ("y_1'", dy1, differences(&in_x(&task.basis[0]), x)[1]),
+            ("y_2'", dy2, differences(&in_x(&task.basis[1]), x)[1]),
+            ("W", w, y1 * dy2 - y2 * dy1),
+            ("c_1'", dc1, -y2 * f / w),
+            ("c_2'", dc2, y1 * f / w),
+            ("c_1'", dc1, differences(&in_x(&task.c[0]), x)[1]),
+            ("c_2'", dc2, differences(&in_x(&task.c[1]), x)[1]),
+        ];
+        for (name, printed, expected) in checks {
+            if (printed - expected).abs()
+                > DIFFERENCE_TOLERANCE * (1. + printed.abs() + expected.abs())
+            {
+                return Err(format!(
+                    "{name} of {} is {printed} instead of {expected} at x = {x}",
+                    task.family
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The equation in `t` as in `verify_equation`, and the solutions in `x = e^t`
+/// substituted into the Euler equation, with the derivatives in `x` taken by central
+/// differences.
+pub fn verify_euler(task: &Euler) -> Result<(), String> {
+    verify_equation(&task.eq)?;
+    if task.coeffs.len() > 4 {
+        return Err(format!("Euler equation of order {}", task.coeffs.len() - 1));
+    }
+
+    let in_x = |terms: &[QPoly]| {
+        let terms = terms.to_vec();
+        move |x: f64| -> f64 { terms.iter().map(|term| derivative_at(term, 0, x.ln())).sum() }
+    };
+    let euler_residual = |y: &dyn Fn(f64) -> f64, f: &dyn Fn(f64) -> f64, x: f64| -> (f64, f64) {
+        let terms = task
+            .coeffs
+            .iter()
+            .zip(differences(y, x))
+            .enumerate()
+            .map(|(k, (c, d))| to_f64(c) * x.powi(k as i32) * d)
+            .collect::<Vec<_>>();
+        let f = f(x);
+        (
+            terms.iter().sum::<f64>() - f,
+            terms.iter().map(|t| t.abs()).sum::<f64>() + f.abs(),
+        )
+    };
+
+    for x in EULER_SAMPLE_POINTS {
+        for (i, y) in task.eq.y0_basis.iter().enumerate() {
+            let (residual, scale) =
+                euler_residual(&in_x(std::slice::from_ref(y)), &|_| 0., x);
+            if residual.abs() > DIFFERENCE_TOLERANCE * (1. + scale) {
+                return Err(format!(
+                    "basis solution {} of the Euler equation leaves {residual} at x = {x}",
+                    i + 1
+                ));
+            }
+        }
+
+        let (residual, scale) = euler_residual(&in_x(&task.eq.y_part), &in_x(&task.eq.f), x);
+        if residual.abs() > DIFFERENCE_TOLERANCE * (1. + scale) {
+            return Err(format!(
+                "particular solution of the Euler equation leaves {residual} at x = {x}"
+            ));
+        }
+    }
+
+    Ok(())
+}
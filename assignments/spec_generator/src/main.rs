@@ -0,0 +1,171 @@
+mod spec;
+
+use std::path::PathBuf;
+
+use base64::prelude::*;
+use derive_typst_intoval::{IntoDict, IntoValue};
+use image::{ImageBuffer, Rgba};
+use typst::{
+    foundations::{Dict, IntoValue},
+    layout::{Page, PagedDocument},
+};
+use typst_as_lib::typst_kit_options::TypstKitFontOptions;
+use typst_render::render;
+use variant_generation::{VariantGeneratorInput, VariantGeneratorOutput, least_overlapping};
+
+use spec::Spec;
+
+/// Random variants compared with the issued ones.
+const CANDIDATES: usize = 100;
+
+#[derive(Debug, Clone, IntoValue, IntoDict)]
+struct Content {
+    variant: String,
+    header: String,
+    tasks: Vec<Task>,
+}
+
+// Implement Into<Dict> manually, so we can just pass the struct
+// to the compile function.
+impl From<Content> for Dict {
+    fn from(value: Content) -> Self {
+        value.into_dict()
+    }
+}
+
+#[derive(Debug, Clone, IntoValue, IntoDict)]
+struct Task {
+    problem: String,
+    solution: String,
+}
+
+/// Where the bot's `generator` names of specs are resolved.
+fn specs_dir() -> PathBuf {
+    std::env::var("SPECS_DIR")
+        .unwrap_or_else(|_| "specs".to_string())
+        .into()
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    pretty_env_logger::init();
+
+    let args = std::env::args().collect::<Vec<_>>();
+    if let [_, flag, path] = args.as_slice()
+        && flag == "--check"
+    {
+        let spec = Spec::load(path.as_ref())?;
+        spec.check()?;
+        // Compiles a random variant to catch Typst errors in the markup and templates.
+        let content = Content {
+            variant: "0".to_string(),
+            header: spec.header.clone(),
+            tasks: tasks(&spec, &[]).1,
+        };
+        let problem_code = spec.template(&spec.problem_template, include_str!("problem.typ"))?;
+        let solution_code = spec.template(&spec.solution_template, include_str!("solution.typ"))?;
+        compile(&problem_code, content.clone())?;
+        compile(&solution_code, content)?;
+        println!("{path}: ok");
+        return Ok(());
+    }
+
+    let input = VariantGeneratorInput::from_stdin()?;
+    let spec = Spec::load(&specs_dir().join(&input.generator))?;
+    spec.check()?;
+
+    let problem_code = spec.template(&spec.problem_template, include_str!("problem.typ"))?;
+    let solution_code = spec.template(&spec.solution_template, include_str!("solution.typ"))?;
+
+    let (fingerprint, tasks) = tasks(&spec, &input.issued_fingerprints);
+    let content = Content {
+        variant: input.variant_number.to_string(),
+        header: spec.header.clone(),
+        tasks,
+    };
+
+    log::debug!("{content:?}");
+
+    let problem_output = compile(&problem_code, content.clone())?
+        .pages
+        .iter()
+        .map(|page| render_page_to_png(page, 300. / 72.))
+        .map(|img| BASE64_STANDARD.encode(img))
+        .collect::<Vec<_>>();
+    let solution_output = compile(&solution_code, content)?
+        .pages
+        .iter()
+        .map(|page| render_page_to_png(page, 300. / 72.))
+        .map(|img| BASE64_STANDARD.encode(img))
+        .collect::<Vec<_>>();
+
+    let output = VariantGeneratorOutput {
+        variant_number: input.variant_number,
+        generator: input.generator,
+        problem_code,
+        problem_images: problem_output,
+        solution_code,
+        solution_images: solution_output,
+        fingerprint: Some(fingerprint),
+    };
+
+    println!("{}", serde_json::to_string(&output)?);
+
+    Ok(())
+}
+
+/// The generated tasks least like the issued variants, with their fingerprint.
+fn tasks(spec: &Spec, issued_fingerprints: &[String]) -> (String, Vec<Task>) {
+    let mut rng = rand::rng();
+    let candidates = (0..CANDIDATES)
+        .map(|_| spec.generate(&mut rng))
+        .collect::<Vec<_>>();
+    let (fingerprint, tasks) =
+        least_overlapping(candidates, issued_fingerprints).expect("no candidates");
+
+    let tasks = tasks
+        .into_iter()
+        .map(|task| Task {
+            problem: task.problem,
+            solution: task.solution,
+        })
+        .collect();
+    (fingerprint, tasks)
+}
+
+fn compile(code: &str, content: Content) -> Result<PagedDocument, String> {
+    let engine = typst_as_lib::TypstEngine::builder()
+        .main_file(code.to_string())
+        .search_fonts_with(
+            TypstKitFontOptions::default()
+                .include_system_fonts(false)
+                .include_embedded_fonts(true),
+        )
+        .build();
+
+    engine
+        .compile_with_input(content)
+        .output
+        .map_err(|err| format!("typst::compile() returned an error: {err:?}"))
+}
+
+fn render_page_to_png(page: &Page, scale: f32) -> Vec<u8> {
+    let pixmap = render(page, scale);
+
+    log::debug!(
+        "Render page with {} width and {} height",
+        pixmap.width(),
+        pixmap.height()
+    );
+
+    let img = ImageBuffer::<Rgba<u8>, _>::from_raw(
+        pixmap.width(),
+        pixmap.height(),
+        pixmap.data().to_vec(),
+    )
+    .expect("invalid pixmap");
+
+    let mut buf = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut buf), image::ImageFormat::Png)
+        .unwrap();
+    buf
+}
@@ -0,0 +1,177 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use rand::prelude::*;
+use serde::Deserialize;
+use variant_generation::{double_braced_substitute, fingerprint};
+
+/// Assignment described in TOML, see `specs/example.toml`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Spec {
+    /// Page header of the problems and the solutions.
+    pub header: String,
+    /// Typst templates replacing the built-in ones, relative to the spec. They get
+    /// `inputs.variant`, `inputs.header` and `inputs.tasks` (`problem`, `solution`).
+    pub problem_template: Option<PathBuf>,
+    pub solution_template: Option<PathBuf>,
+    pub tasks: Vec<TaskSpec>,
+    /// Directory of the spec file.
+    #[serde(skip)]
+    pub dir: PathBuf,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TaskSpec {
+    /// Substituted for `{{name}}` in the problem and the solution.
+    #[serde(default)]
+    pub parameters: BTreeMap<String, Parameter>,
+    /// Alternatives, one of them is chosen for every variant.
+    pub variants: Vec<TaskVariant>,
+}
+
+/// Typst markup of a task.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TaskVariant {
+    pub problem: String,
+    pub solution: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Parameter {
+    /// One of the values.
+    Values { values: Vec<toml::Value> },
+    /// An integer from `range[0]` to `range[1]` inclusive, except `exclude`.
+    Range {
+        range: [i64; 2],
+        #[serde(default)]
+        exclude: Vec<i64>,
+    },
+}
+
+impl Parameter {
+    fn candidates(&self) -> Vec<String> {
+        match self {
+            Parameter::Values { values } => values
+                .iter()
+                .map(|value| match value {
+                    toml::Value::String(s) => s.clone(),
+                    value => value.to_string(),
+                })
+                .collect(),
+            Parameter::Range {
+                range: [from, to],
+                exclude,
+            } => (*from..=*to)
+                .filter(|x| !exclude.contains(x))
+                .map(|x| x.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Names of the `{{name}}` placeholders in the text.
+fn placeholders(text: &str) -> Vec<&str> {
+    let mut names = vec![];
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        names.push(rest[start + 2..start + 2 + end].trim());
+        rest = &rest[start + 2 + end + 2..];
+    }
+    names
+}
+
+impl Spec {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| format!("{}: {err}", path.display()))?;
+        let mut spec: Spec =
+            toml::from_str(&text).map_err(|err| format!("{}: {err}", path.display()))?;
+        spec.dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(spec)
+    }
+
+    /// Every task has variants, every parameter has values and every placeholder
+    /// is a parameter of its task.
+    pub fn check(&self) -> Result<(), String> {
+        if self.tasks.is_empty() {
+            return Err("no tasks".to_string());
+        }
+
+        for (i, task) in self.tasks.iter().enumerate() {
+            let task_no = i + 1;
+            if task.variants.is_empty() {
+                return Err(format!("task {task_no} has no variants"));
+            }
+            for (name, parameter) in &task.parameters {
+                if parameter.candidates().is_empty() {
+                    return Err(format!("parameter {name} of task {task_no} has no values"));
+                }
+            }
+            for (j, variant) in task.variants.iter().enumerate() {
+                for name in placeholders(&variant.problem)
+                    .into_iter()
+                    .chain(placeholders(&variant.solution))
+                {
+                    if !task.parameters.contains_key(name) {
+                        return Err(format!(
+                            "variant {} of task {task_no} uses {{{{{name}}}}}, which is not a parameter",
+                            j + 1
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn template(&self, template: &Option<PathBuf>, default: &str) -> std::io::Result<String> {
+        match template {
+            Some(path) => std::fs::read_to_string(self.dir.join(path)),
+            None => Ok(default.to_string()),
+        }
+    }
+
+    /// A variant and parameter values for every task, substituted, with the fingerprint
+    /// of the choices: the index of the variant and the values of every task.
+    pub fn generate(&self, rng: &mut impl Rng) -> (String, Vec<TaskVariant>) {
+        let mut choices = vec![];
+        let mut tasks = vec![];
+
+        for task in &self.tasks {
+            let substitutions = task
+                .parameters
+                .iter()
+                .map(|(name, parameter)| {
+                    let value = parameter.candidates().choose(rng).cloned().unwrap_or_default();
+                    (name.clone(), value)
+                })
+                .collect::<Vec<_>>();
+
+            let index = rng.random_range(0..task.variants.len());
+            let variant = task.variants[index].clone();
+
+            choices.push(
+                std::iter::once(index.to_string())
+                    .chain(substitutions.iter().map(|(name, value)| format!("{name}={value}")))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            );
+            tasks.push(TaskVariant {
+                problem: double_braced_substitute(variant.problem, &substitutions),
+                solution: double_braced_substitute(variant.solution, &substitutions),
+            });
+        }
+
+        (fingerprint(&choices), tasks)
+    }
+}
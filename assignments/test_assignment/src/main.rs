@@ -26,6 +26,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         VariantGeneratorInput {
             variant_number: 666,
             generator: "test_assignment".to_string(),
+            parameters: serde_json::Value::Null,
+            issued_fingerprints: vec![],
         }
     });
 
@@ -99,6 +101,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         problem_images: problem_output,
         solution_code,
         solution_images: solution_output,
+        fingerprint: None,
     };
 
     println!("{}", serde_json::to_string(&output)?);
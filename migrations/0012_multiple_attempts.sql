@@ -0,0 +1,94 @@
+-- Group assignments may allow several attempts. Each attempt is a separate
+-- submission with its own variant; `attempt_policy` selects the attempt that counts.
+alter table group_assignment
+    add column max_attempts     int     not null    default 1,
+    add column attempt_policy   text    not null    default 'last',
+    add check (max_attempts >= 1),
+    add check (attempt_policy in ('first', 'last', 'best'));
+
+alter table submission
+    add column attempt_no       int     not null    default 1,
+    add column score            real,
+    add check (attempt_no >= 1);
+
+alter table submission drop constraint submission_student_id_group_assignment_id_key;
+alter table submission add unique (student_id, group_assignment_id, attempt_no);
+
+
+-- One row per (student, group assignment): the finished attempt that counts
+-- according to the group assignment's `attempt_policy`. For 'best' the attempts
+-- without a score are considered only when none of them is scored.
+create or replace view counted_submission as
+select distinct on (s.student_id, s.group_assignment_id)
+    s.id as submission_id,
+    s.student_id,
+    s.group_assignment_id,
+    s.attempt_no
+from submission s
+inner join group_assignment ga on s.group_assignment_id = ga.id
+where s.finished_at is not null
+order by
+    s.student_id,
+    s.group_assignment_id,
+    case when ga.attempt_policy = 'first' then s.attempt_no end asc,
+    case when ga.attempt_policy = 'best'  then s.score      end desc nulls last,
+    s.attempt_no desc;
+
+
+-- Same as before, but every group entry may also carry optional
+-- "max_attempts" and "attempt_policy" keys, e.g.
+-- {"name": "МКН-21БО", "deadline": null, "max_attempts": 3, "attempt_policy": "best"}
+create or replace function insert_assignment_with_groups(
+    p_title text,
+    p_description text,
+    p_generator text,
+    p_duration time,
+    p_groups jsonb
+)
+returns void
+language plpgsql
+as $$
+declare
+    missing_count int;
+begin
+    select count(*) into missing_count
+    from jsonb_array_elements(p_groups) g
+    left join "group" gr
+      on gr.name = g->>'name'
+     and gr.academic_year = current_academic_year()
+    where gr.id is null;
+
+    if missing_count > 0 then
+        raise exception 'some groups do not exist';
+    end if;
+
+with new_assignment as (
+    insert into assignment (title, description, generator, duration)
+    values (p_title, p_description, p_generator, p_duration)
+    returning id
+),
+groups_with_deadlines as (
+    select
+        (g->>'name')::text        as name,
+        current_academic_year()  as academic_year,
+        (g->>'deadline')::timestamptz as deadline,
+        coalesce((g->>'max_attempts')::int, 1) as max_attempts,
+        coalesce(g->>'attempt_policy', 'last') as attempt_policy
+    from jsonb_array_elements(p_groups) as g
+)
+insert into group_assignment (group_id, assignment_id, deadline, max_attempts, attempt_policy)
+select
+    gr.id,
+    na.id,
+    gwd.deadline,
+    gwd.max_attempts,
+    gwd.attempt_policy
+from new_assignment na
+join groups_with_deadlines gwd
+    on true
+join "group" gr
+    on gr.name = gwd.name
+   and gr.academic_year = gwd.academic_year;
+
+end;
+$$;
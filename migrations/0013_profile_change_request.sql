@@ -0,0 +1,20 @@
+-- Student requests to correct the registered name or group, resolved by the admin chat.
+-- A null `full_name` or `group_id` means that part of the profile is not being changed.
+create table if not exists profile_change_request (
+    id                  uuid primary key            default uuid_generate_v1mc(),
+    student_id          uuid            not null    references student(id)  on delete cascade,
+    full_name           text                        collate "case_insensitive",
+    group_id            uuid                        references "group"(id)  on delete cascade,
+    status              text            not null    default 'pending',
+    resolved_by         bigint,
+    resolved_at         timestamptz,
+    created_at          timestamptz     not null    default now(),
+    updated_at          timestamptz,
+    check (status in ('pending', 'accepted', 'rejected')),
+    check (full_name is not null or group_id is not null)
+);
+select trigger_updated_at('"profile_change_request"');
+
+create unique index if not exists profile_change_request_one_pending
+    on profile_change_request (student_id)
+    where status = 'pending';
@@ -0,0 +1,30 @@
+-- Official list of students of a group, uploaded by the admin as CSV.
+-- An entry is claimed by at most one student (i.e. one Telegram account).
+create table if not exists roster_entry (
+    id                  uuid primary key            default uuid_generate_v1mc(),
+    group_id            uuid            not null    references "group"(id)  on delete cascade,
+    full_name           text            not null    collate "case_insensitive",
+    student_id          uuid                        references student(id)  on delete set null,
+    created_at          timestamptz     not null    default now(),
+    updated_at          timestamptz,
+    unique (group_id, full_name),
+    unique (student_id)
+);
+select trigger_updated_at('"roster_entry"');
+
+-- Registrations under a name that is not on the group's roster (or is already
+-- claimed), awaiting approval in the admin chat.
+create table if not exists registration_request (
+    id                  uuid primary key            default uuid_generate_v1mc(),
+    group_id            uuid            not null    references "group"(id)  on delete cascade,
+    telegram_id         bigint          not null,
+    chat_id             bigint          not null,
+    full_name           text            not null    collate "case_insensitive",
+    status              text            not null    default 'pending',
+    resolved_by         bigint,
+    resolved_at         timestamptz,
+    created_at          timestamptz     not null    default now(),
+    updated_at          timestamptz,
+    check (status in ('pending', 'accepted', 'rejected'))
+);
+select trigger_updated_at('"registration_request"');
@@ -0,0 +1,107 @@
+-- A course owns its groups and assignments, and has its own admin chat. Courses
+-- without `admin_chat_id` are administered from the chat given by `ADMIN_CHAT_ID`.
+create table if not exists course (
+    id                  uuid primary key            default uuid_generate_v1mc(),
+    title               text            not null    collate "case_insensitive",
+    instructor          text            not null    default '',
+    admin_chat_id       bigint,
+    created_at          timestamptz     not null    default now(),
+    updated_at          timestamptz,
+    unique (title)
+);
+select trigger_updated_at('"course"');
+
+insert into course (title, instructor)
+values ('Практика по дифференциальным уравнениям', 'Баин Данила Денисович');
+
+
+alter table "group" add column course_id uuid references course(id) on delete cascade;
+update "group" set course_id = (select id from course);
+alter table "group" alter column course_id set not null;
+
+-- the same academic group may attend several courses
+alter table "group" drop constraint group_name_academic_year_key;
+alter table "group" add unique (course_id, name, academic_year);
+
+
+alter table assignment add column course_id uuid references course(id) on delete cascade;
+update assignment set course_id = (select id from course);
+alter table assignment alter column course_id set not null;
+
+
+-- a Telegram account has one student row per course it is enrolled in
+alter table student drop constraint student_telegram_id_chat_id_key;
+
+
+drop function insert_assignment_with_groups(text, text, text, time, jsonb);
+
+-- Same as before, but the assignment and the groups belong to the course `p_course`
+-- (by title), e.g.
+-- SELECT insert_assignment_with_groups(
+--     'Практика по дифференциальным уравнениям', 'Название', 'Описание', 'generator', '01:30:00',
+--     '[{"name": "МКН-21БО", "deadline": null, "max_attempts": 3, "attempt_policy": "best"}]'
+-- );
+create or replace function insert_assignment_with_groups(
+    p_course text,
+    p_title text,
+    p_description text,
+    p_generator text,
+    p_duration time,
+    p_groups jsonb
+)
+returns void
+language plpgsql
+as $$
+declare
+    v_course_id uuid;
+    missing_count int;
+begin
+    select id into v_course_id from course where title = p_course;
+
+    if v_course_id is null then
+        raise exception 'course does not exist';
+    end if;
+
+    select count(*) into missing_count
+    from jsonb_array_elements(p_groups) g
+    left join "group" gr
+      on gr.name = g->>'name'
+     and gr.academic_year = current_academic_year()
+     and gr.course_id = v_course_id
+    where gr.id is null;
+
+    if missing_count > 0 then
+        raise exception 'some groups do not exist';
+    end if;
+
+with new_assignment as (
+    insert into assignment (course_id, title, description, generator, duration)
+    values (v_course_id, p_title, p_description, p_generator, p_duration)
+    returning id
+),
+groups_with_deadlines as (
+    select
+        (g->>'name')::text        as name,
+        current_academic_year()  as academic_year,
+        (g->>'deadline')::timestamptz as deadline,
+        coalesce((g->>'max_attempts')::int, 1) as max_attempts,
+        coalesce(g->>'attempt_policy', 'last') as attempt_policy
+    from jsonb_array_elements(p_groups) as g
+)
+insert into group_assignment (group_id, assignment_id, deadline, max_attempts, attempt_policy)
+select
+    gr.id,
+    na.id,
+    gwd.deadline,
+    gwd.max_attempts,
+    gwd.attempt_policy
+from new_assignment na
+join groups_with_deadlines gwd
+    on true
+join "group" gr
+    on gr.name = gwd.name
+   and gr.academic_year = gwd.academic_year
+   and gr.course_id = v_course_id;
+
+end;
+$$;
@@ -0,0 +1,10 @@
+-- Interface language of a chat: detected from the Telegram client on /start,
+-- or chosen with /language.
+create table if not exists chat_locale (
+    chat_id             bigint primary key,
+    locale              text            not null    default 'ru',
+    created_at          timestamptz     not null    default now(),
+    updated_at          timestamptz,
+    check (locale in ('ru', 'en'))
+);
+select trigger_updated_at('"chat_locale"');
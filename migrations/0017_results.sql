@@ -0,0 +1,5 @@
+-- Original name of the uploaded file, to send it back to the student.
+alter table submission_item add column file_name text;
+
+-- Reviewed submission, uploaded by the admin and available to the student.
+alter table submission add column graded_report bytea;
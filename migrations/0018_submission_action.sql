@@ -0,0 +1,24 @@
+-- Files attached by the admin on the student's behalf have no message in the student's chat.
+alter table submission_item alter column message_id drop not null;
+
+-- Set when the admin reopens a finished submission: the new end of the attempt,
+-- replacing the time limit and the deadline.
+alter table submission add column reopened_until timestamptz;
+
+-- Admin overrides of individual submissions, with who did them and why.
+create table if not exists submission_action (
+    id                  uuid primary key            default uuid_generate_v1mc(),
+    submission_id       uuid            not null    references submission(id) on delete cascade,
+    action              text            not null,
+    -- the deleted or attached item
+    submission_item_id  uuid,
+    admin_telegram_id   bigint          not null,
+    admin_name          text            not null,
+    reason              text            not null,
+    created_at          timestamptz     not null    default now(),
+    updated_at          timestamptz,
+    check (action in ('force_finish', 'reopen', 'reset', 'delete_item', 'attach'))
+);
+select trigger_updated_at('"submission_action"');
+
+create index if not exists submission_action_submission_id on submission_action (submission_id);
@@ -0,0 +1,5 @@
+-- Hashes for detecting solutions shared between students:
+-- SHA-256 of the uploaded file and a 64-bit perceptual hash (dHash) of every page.
+-- A page hash is null when the page has no image that could be decoded.
+alter table submission_item add column content_hash bytea;
+alter table submission_item add column page_hashes bigint[];
@@ -0,0 +1,27 @@
+-- Compiled solutions of a group assignment, split into volumes that fit into
+-- Telegram's upload limit. Volumes are sent to the admin chat until `delivered_at` is set.
+create table if not exists compiled_volume (
+    id                  uuid primary key            default uuid_generate_v1mc(),
+    group_assignment_id uuid            not null    references group_assignment(id) on delete cascade,
+    volume_no           int             not null,
+    volume_count        int             not null,
+    data                bytea           not null,
+    delivered_at        timestamptz,
+    created_at          timestamptz     not null    default now(),
+    updated_at          timestamptz,
+    unique (group_assignment_id, volume_no),
+    check (volume_no between 1 and volume_count)
+);
+select trigger_updated_at('"compiled_volume"');
+
+alter table group_assignment add column compiled_at timestamptz;
+
+-- solutions compiled so far were a single volume and have already been sent
+insert into compiled_volume (group_assignment_id, volume_no, volume_count, data, delivered_at)
+select id, 1, 1, solutions, now()
+from group_assignment
+where solutions is not null;
+
+update group_assignment set compiled_at = coalesce(updated_at, now()) where solutions is not null;
+
+alter table group_assignment drop column solutions;
@@ -0,0 +1,3 @@
+-- Pages of every submission in the volume, see `submissions::page_map::PageMap`.
+-- Null for the volumes compiled before the page map was introduced.
+alter table compiled_volume add column page_map jsonb;
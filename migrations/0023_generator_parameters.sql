@@ -0,0 +1,78 @@
+-- Settings passed to the assignment's generator with every variant, e.g. the
+-- difficulty for the group: `VariantGeneratorInput::parameters`.
+alter table group_assignment add column generator_parameters jsonb not null default '{}';
+
+-- Same as before, with the optional "generator_parameters" key of the groups, e.g.
+-- SELECT insert_assignment_with_groups(
+--     'Практика по дифференциальным уравнениям', 'Название', 'Описание', 'linear_systems_2025', '01:30:00',
+--     '[{"name": "МКН-21БО", "deadline": null, "generator_parameters": {"level": 3}}]'
+-- );
+create or replace function insert_assignment_with_groups(
+    p_course text,
+    p_title text,
+    p_description text,
+    p_generator text,
+    p_duration time,
+    p_groups jsonb
+)
+returns void
+language plpgsql
+as $$
+declare
+    v_course_id uuid;
+    missing_count int;
+begin
+    select id into v_course_id from course where title = p_course;
+
+    if v_course_id is null then
+        raise exception 'course does not exist';
+    end if;
+
+    select count(*) into missing_count
+    from jsonb_array_elements(p_groups) g
+    left join "group" gr
+      on gr.name = g->>'name'
+     and gr.academic_year = current_academic_year()
+     and gr.course_id = v_course_id
+    where gr.id is null;
+
+    if missing_count > 0 then
+        raise exception 'some groups do not exist';
+    end if;
+
+with new_assignment as (
+    insert into assignment (course_id, title, description, generator, duration)
+    values (v_course_id, p_title, p_description, p_generator, p_duration)
+    returning id
+),
+groups_with_deadlines as (
+    select
+        (g->>'name')::text        as name,
+        current_academic_year()  as academic_year,
+        (g->>'deadline')::timestamptz as deadline,
+        coalesce((g->>'max_attempts')::int, 1) as max_attempts,
+        coalesce(g->>'attempt_policy', 'last') as attempt_policy,
+        coalesce(g->>'release_solutions', 'never') as release_solutions,
+        coalesce(g->'generator_parameters', '{}') as generator_parameters
+    from jsonb_array_elements(p_groups) as g
+)
+insert into group_assignment
+    (group_id, assignment_id, deadline, max_attempts, attempt_policy, release_solutions, generator_parameters)
+select
+    gr.id,
+    na.id,
+    gwd.deadline,
+    gwd.max_attempts,
+    gwd.attempt_policy,
+    gwd.release_solutions,
+    gwd.generator_parameters
+from new_assignment na
+join groups_with_deadlines gwd
+    on true
+join "group" gr
+    on gr.name = gwd.name
+   and gr.academic_year = gwd.academic_year
+   and gr.course_id = v_course_id;
+
+end;
+$$;
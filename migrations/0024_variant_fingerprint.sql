@@ -0,0 +1,4 @@
+-- Canonical fingerprint of the task choices of the variant, reported by the
+-- generator: `VariantGeneratorOutput::fingerprint`. The fingerprints issued for a
+-- group assignment are passed to the generator, which avoids repeating them.
+alter table variant add column fingerprint text;
@@ -1,34 +1,19 @@
-use nalgebra::*;
-
-const LIMIT: i32 = 2;
+use nalgebra::DMatrix;
+use pretty_matrix_inverse::Constraints;
 
 fn main() {
-    let values = [-1.0, 0.0, 1.0];
-
-    'd: for n in 0..3usize.pow(9) {
-        let mut m = n;
-        let mut data = [0.0; 9];
-        let mut norm = 0.;
-        for i in 0..9 {
-            data[i] = values[m % 3];
-            norm += data[i].abs();
-            m /= 3;
-        }
-        let mat = Matrix3::from_row_slice(&data);
-        
-        let j = Matrix3::from_row_slice(&[1.,0.,0.,0.,2.,0.,0.,0.,-1.]);
+    let j = DMatrix::from_row_slice(3, 3, &[1, 0, 0, 0, 2, 0, 0, 0, -1]);
 
-        if norm >= 7. && let Some(inv) = mat.try_inverse() {
-            let elements = inv.as_slice();
-            for el in elements.iter() {
-                if &el.floor() != el {
-                    continue 'd;
-                }
-            }
+    let constraints = Constraints::new(3).with_max_entry(1, false);
 
-            let mat_a = mat * j * inv;
-
-            println!("Matrix:{mat}Inverse:{inv}New:{mat_a}\n\n");
+    for unimodular in constraints.enumerate() {
+        // at least 7 non-zero entries
+        if unimodular.matrix.iter().map(|x| x.abs()).sum::<i64>() < 7 {
+            continue;
         }
+        let mat_a = unimodular.similar(&j);
+        let (mat, inv) = (unimodular.matrix, unimodular.inverse);
+
+        println!("Matrix:{mat}Inverse:{inv}New:{mat_a}\n\n");
     }
 }
@@ -0,0 +1,206 @@
+//! Integer matrices with integer inverses (unimodular, determinant ±1), to be used
+//! as `C` in `A = C J C^-1`, so that `A` has integer entries and the eigenstructure
+//! of the Jordan matrix `J`.
+//!
+//! `Constraints::enumerate` lists all of them within the bounds (feasible for 2×2
+//! and small 3×3), `Constraints::sample` draws them with a given RNG.
+
+use nalgebra::DMatrix;
+use num::{Zero, rational::Rational64};
+use rand::prelude::*;
+
+/// Attempts of `Constraints::sample` before giving up.
+const MAX_SAMPLES: usize = 1_000_000;
+
+#[derive(Debug, Clone)]
+pub struct Constraints {
+    pub size: usize,
+    /// Entries lie in `-max_entry..=max_entry`.
+    pub max_entry: i64,
+    pub nonzero_entries: bool,
+    /// Bound of the absolute values of the entries of the inverse.
+    pub max_inverse_entry: Option<i64>,
+    pub nonzero_inverse_entries: bool,
+    /// Constraints on `C J C^-1`.
+    pub product: Option<Product>,
+}
+
+/// `C J C^-1` for a Jordan matrix `J` (any integer matrix, in fact).
+#[derive(Debug, Clone)]
+pub struct Product {
+    pub jordan: DMatrix<i64>,
+    /// Bound of the absolute values of the entries of the product.
+    pub max_entry: Option<i64>,
+    /// No zero entries, so that the structure of `J` does not show.
+    pub nonzero_entries: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unimodular {
+    pub matrix: DMatrix<i64>,
+    pub inverse: DMatrix<i64>,
+}
+
+impl Unimodular {
+    /// `C J C^-1`.
+    pub fn similar(&self, jordan: &DMatrix<i64>) -> DMatrix<i64> {
+        &self.matrix * jordan * &self.inverse
+    }
+}
+
+impl Constraints {
+    /// Entries from -2 to 2 without zeros, anything else goes.
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            max_entry: 2,
+            nonzero_entries: true,
+            max_inverse_entry: None,
+            nonzero_inverse_entries: false,
+            product: None,
+        }
+    }
+
+    pub fn with_max_entry(self, max_entry: i64, nonzero_entries: bool) -> Self {
+        Self {
+            max_entry,
+            nonzero_entries,
+            ..self
+        }
+    }
+
+    pub fn with_max_inverse_entry(self, max_entry: Option<i64>, nonzero_entries: bool) -> Self {
+        Self {
+            max_inverse_entry: max_entry,
+            nonzero_inverse_entries: nonzero_entries,
+            ..self
+        }
+    }
+
+    pub fn with_product(self, product: Product) -> Self {
+        Self {
+            product: Some(product),
+            ..self
+        }
+    }
+
+    /// Possible entries of the matrix.
+    fn entries(&self) -> Vec<i64> {
+        (-self.max_entry..=self.max_entry)
+            .filter(|&x| !(self.nonzero_entries && x == 0))
+            .collect()
+    }
+
+    /// The matrix with its inverse, if it satisfies the constraints.
+    pub fn check(&self, matrix: DMatrix<i64>) -> Option<Unimodular> {
+        let n = self.size;
+        if matrix.nrows() != n || matrix.ncols() != n {
+            return None;
+        }
+        if matrix
+            .iter()
+            .any(|&x| x.abs() > self.max_entry || (self.nonzero_entries && x == 0))
+        {
+            return None;
+        }
+
+        let inverse = integer_inverse(&matrix)?;
+        if inverse.iter().any(|&x| {
+            self.max_inverse_entry.is_some_and(|max| x.abs() > max)
+                || (self.nonzero_inverse_entries && x == 0)
+        }) {
+            return None;
+        }
+
+        let unimodular = Unimodular { matrix, inverse };
+        if let Some(product) = &self.product {
+            if product.jordan.nrows() != n || product.jordan.ncols() != n {
+                return None;
+            }
+            let a = unimodular.similar(&product.jordan);
+            if a.iter().any(|&x| {
+                product.max_entry.is_some_and(|max| x.abs() > max)
+                    || (product.nonzero_entries && x == 0)
+            }) {
+                return None;
+            }
+        }
+        Some(unimodular)
+    }
+
+    /// Every matrix satisfying the constraints, in lexicographic order of the entries
+    /// by rows. There are `entries^(size^2)` candidates, so keep the bounds small.
+    pub fn enumerate(&self) -> impl Iterator<Item = Unimodular> + '_ {
+        let entries = self.entries();
+        let cells = self.size * self.size;
+        let count = entries.len().checked_pow(cells as u32).unwrap_or(usize::MAX);
+
+        (0..count).filter_map(move |mut index| {
+            let mut data = vec![0; cells];
+            for cell in data.iter_mut().rev() {
+                *cell = entries[index % entries.len()];
+                index /= entries.len();
+            }
+            self.check(DMatrix::from_row_slice(self.size, self.size, &data))
+        })
+    }
+
+    /// A random matrix satisfying the constraints, with uniformly random entries
+    /// until one fits; `None` if none does in a million attempts.
+    pub fn sample(&self, rng: &mut impl Rng) -> Option<Unimodular> {
+        let entries = self.entries();
+        if entries.is_empty() {
+            return None;
+        }
+        (0..MAX_SAMPLES).find_map(|_| {
+            let matrix =
+                DMatrix::from_fn(self.size, self.size, |_, _| *entries.choose(rng).unwrap());
+            self.check(matrix)
+        })
+    }
+}
+
+/// Exact inverse by Gauss–Jordan elimination, `None` if the matrix is singular.
+pub fn try_inverse(matrix: &DMatrix<Rational64>) -> Option<DMatrix<Rational64>> {
+    let n = matrix.nrows();
+    if matrix.ncols() != n {
+        return None;
+    }
+
+    let mut a = matrix.clone();
+    let mut inverse = DMatrix::<Rational64>::identity(n, n);
+    for col in 0..n {
+        let pivot_row = (col..n).find(|&row| !a[(row, col)].is_zero())?;
+        a.swap_rows(col, pivot_row);
+        inverse.swap_rows(col, pivot_row);
+
+        let pivot = a[(col, col)];
+        for j in 0..n {
+            a[(col, j)] /= pivot;
+            inverse[(col, j)] /= pivot;
+        }
+
+        for row in (0..n).filter(|&row| row != col) {
+            let factor = a[(row, col)];
+            if factor.is_zero() {
+                continue;
+            }
+            for j in 0..n {
+                let (a_col, inverse_col) = (a[(col, j)], inverse[(col, j)]);
+                a[(row, j)] -= factor * a_col;
+                inverse[(row, j)] -= factor * inverse_col;
+            }
+        }
+    }
+    Some(inverse)
+}
+
+/// The inverse, `None` unless it exists and has integer entries.
+pub fn integer_inverse(matrix: &DMatrix<i64>) -> Option<DMatrix<i64>> {
+    let inverse = try_inverse(&matrix.map(Rational64::from_integer))?;
+    if inverse.iter().all(|x| x.is_integer()) {
+        Some(inverse.map(|x| x.to_integer()))
+    } else {
+        None
+    }
+}
@@ -1,42 +1,186 @@
-// use teloxide::dptree::{self, Handler};
-//
-// use crate::bot::HandlerResult;
+use sqlx::PgPool;
+use teloxide::{
+    Bot,
+    dispatching::UpdateFilterExt,
+    dptree::{self, Handler},
+    prelude::Requester,
+    types::{CallbackQuery, ChatId, Message, Update},
+    utils::command::BotCommands,
+};
+use uuid::Uuid;
+
+use crate::{
+    bot::{
+        HandlerResult, MyDialogue, MyError, archive, compile, language, overrides, profile,
+        registration, results, roster, status,
+    },
+    locale::Locale,
+};
 
 #[derive(Debug, Clone)]
 pub enum AdminState {
     AwaitingCommand,
 }
 
-// pub fn admin_handler()
-// -> Handler<'static, HandlerResult, teloxide::dispatching::DpHandlerDescription> {
-//     dptree::entry()
-//     // dptree::case![BotState::Idle(idle_state)]
-//     //     .branch(
-//     //         Update::filter_message().branch(
-//     //             dptree::case![IdleState::AwaitingCommand { student_id }]
-//     //                 .branch(
-//     //                     teloxide::filter_command::<IdleCommand, HandlerResult>()
-//     //                         .branch(dptree::case![IdleCommand::Help].endpoint(help))
-//     //                         .branch(
-//     //                             dptree::case![IdleCommand::ShowAssignments]
-//     //                                 .endpoint(show_assignments_list),
-//     //                         ),
-//     //                 )
-//     //                 .branch(dptree::endpoint(unknown_command)),
-//     //         ),
-//     //     )
-//     //     .branch(
-//     //         Update::filter_callback_query()
-//     //             .branch(
-//     //                 dptree::case![IdleState::AwaitingCommand { student_id }]
-//     //                     .endpoint(show_assignment),
-//     //             )
-//     //             .branch(
-//     //                 dptree::case![IdleState::AwaitingAssignmentStart {
-//     //                     student_id,
-//     //                     group_assignment_id
-//     //                 }]
-//     //                 .endpoint(awaiting_assignment_start),
-//     //             ),
-//     //     )
-// }
+#[derive(BotCommands, Clone)]
+#[command(rename_rule = "snake_case", description = "Команды преподавателя:")]
+pub enum AdminCommand {
+    #[command(description = "показать это сообщение.")]
+    Help,
+    #[command(
+        description = "загрузить список группы: CSV-файл (ФИО в первом столбце) с подписью /import_roster <группа>."
+    )]
+    ImportRoster(String),
+    #[command(
+        description = "загрузить проверенную работу: PDF с подписью /upload_report <id работы> [баллы]."
+    )]
+    UploadReport(String),
+    #[command(description = "показать ход выполнения открытых заданий.")]
+    Status,
+    #[command(description = "собрать присланные решения, не дожидаясь крайнего срока.")]
+    Compile,
+    #[command(description = "найти работы: /submissions <ФИО или id работы>.")]
+    Submissions(String),
+    #[command(description = "завершить работу: /force_finish <id работы> <причина>.")]
+    ForceFinish(String),
+    #[command(
+        description = "заново открыть завершённую работу: /reopen <id работы> <минуты> <причина>."
+    )]
+    Reopen(String),
+    #[command(description = "перезапустить работу с новым вариантом: /reset <id работы> <причина>.")]
+    Reset(String),
+    #[command(description = "удалить файл из работы: /delete_item <id файла> <причина>.")]
+    DeleteItem(String),
+    #[command(
+        description = "прикрепить файл к работе: файл с подписью /attach <id работы> <причина>."
+    )]
+    Attach(String),
+    #[command(description = "выгрузить задание группы со всеми работами в zip-архив.")]
+    Export,
+    #[command(
+        description = "восстановить задание из архива: zip-файл с подписью /import_archive или /import_archive <файл> для архива на сервере."
+    )]
+    ImportArchive(String),
+}
+
+/// Admin chat of the courses that do not have their own.
+pub fn admin_chat_id() -> Result<ChatId, MyError> {
+    Ok(ChatId(std::env::var("ADMIN_CHAT_ID")?.parse()?))
+}
+
+pub async fn course_admin_chat_id(course_id: Uuid, pool: &PgPool) -> Result<ChatId, MyError> {
+    let chat_id = sqlx::query_scalar!(
+        r#"select admin_chat_id from course where id = $1"#,
+        course_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    match chat_id {
+        Some(chat_id) => Ok(ChatId(chat_id)),
+        None => admin_chat_id(),
+    }
+}
+
+pub async fn group_admin_chat_id(group_id: Uuid, pool: &PgPool) -> Result<ChatId, MyError> {
+    let course_id = sqlx::query_scalar!(r#"select course_id from "group" where id = $1"#, group_id)
+        .fetch_one(pool)
+        .await?;
+    course_admin_chat_id(course_id, pool).await
+}
+
+/// Courses administered from the chat.
+pub async fn administered_courses(chat_id: ChatId, pool: &PgPool) -> Result<Vec<Uuid>, MyError> {
+    let is_default_admin_chat = admin_chat_id().is_ok_and(|admin_chat_id| admin_chat_id == chat_id);
+
+    Ok(sqlx::query_scalar!(
+        r#"
+        select id from course
+        where admin_chat_id = $1 or (admin_chat_id is null and $2)
+        "#,
+        chat_id.0,
+        is_default_admin_chat,
+    )
+    .fetch_all(pool)
+    .await?)
+}
+
+async fn is_admin_chat(dialogue: MyDialogue, pool: PgPool) -> bool {
+    administered_courses(dialogue.chat_id(), &pool)
+        .await
+        .is_ok_and(|courses| !courses.is_empty())
+}
+
+fn callback_prefix(prefix: &'static str) -> impl Fn(CallbackQuery) -> bool + Clone {
+    move |q: CallbackQuery| q.data.as_deref().is_some_and(|data| data.starts_with(prefix))
+}
+
+/// Updates from the admin chat. Commands and button presses are handled regardless
+/// of the dialogue state, since the admin chat may not have been `/start`ed since
+/// the bot was restarted.
+pub fn admin_handler()
+-> Handler<'static, HandlerResult, teloxide::dispatching::DpHandlerDescription> {
+    dptree::filter_async(is_admin_chat)
+        .branch(
+            Update::filter_message().branch(
+                teloxide::filter_command::<AdminCommand, HandlerResult>()
+                    .branch(dptree::case![AdminCommand::Help].endpoint(help))
+                    .branch(
+                        dptree::case![AdminCommand::ImportRoster(group_name)]
+                            .endpoint(roster::import_roster),
+                    )
+                    .branch(
+                        dptree::case![AdminCommand::UploadReport(args)]
+                            .endpoint(results::upload_report),
+                    )
+                    .branch(dptree::case![AdminCommand::Status].endpoint(status::show_status))
+                    .branch(
+                        dptree::case![AdminCommand::Compile]
+                            .endpoint(compile::choose_group_assignment),
+                    )
+                    .branch(
+                        dptree::case![AdminCommand::Submissions(args)]
+                            .endpoint(overrides::show_submissions),
+                    )
+                    .branch(
+                        dptree::case![AdminCommand::ForceFinish(args)]
+                            .endpoint(overrides::force_finish),
+                    )
+                    .branch(dptree::case![AdminCommand::Reopen(args)].endpoint(overrides::reopen))
+                    .branch(dptree::case![AdminCommand::Reset(args)].endpoint(overrides::reset))
+                    .branch(
+                        dptree::case![AdminCommand::DeleteItem(args)]
+                            .endpoint(overrides::delete_item),
+                    )
+                    .branch(dptree::case![AdminCommand::Attach(args)].endpoint(overrides::attach))
+                    .branch(
+                        dptree::case![AdminCommand::Export]
+                            .endpoint(archive::choose_group_assignment),
+                    )
+                    .branch(
+                        dptree::case![AdminCommand::ImportArchive(file_name)]
+                            .endpoint(archive::import_archive),
+                    ),
+            ),
+        )
+        .branch(
+            Update::filter_callback_query()
+                .branch(dptree::filter(callback_prefix("profile:")).endpoint(profile::resolve_request))
+                .branch(
+                    dptree::filter(callback_prefix("registration:"))
+                        .endpoint(registration::resolve_request),
+                )
+                .branch(dptree::filter(callback_prefix("status:")).endpoint(status::refresh))
+                .branch(dptree::filter(callback_prefix("compile:")).endpoint(compile::compile))
+                .branch(dptree::filter(callback_prefix("export:")).endpoint(archive::export)),
+        )
+}
+
+pub async fn help(bot: Bot, msg: Message, locale: Locale) -> HandlerResult {
+    bot.send_message(
+        msg.chat.id,
+        language::command_help::<AdminCommand>(locale.messages().admin_commands_header, locale),
+    )
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use std::{fs::File, io::Cursor, path::Path};
+
+use sqlx::PgPool;
+use teloxide::{
+    Bot,
+    net::Download,
+    payloads::{SendDocumentSetters, SendMessageSetters},
+    prelude::Requester,
+    sugar::request::RequestReplyExt,
+    types::{CallbackQuery, InputFile, Message},
+};
+use uuid::Uuid;
+
+use crate::{
+    bot::{HandlerResult, admin, compile},
+    locale::{self, Locale},
+    submissions::{
+        archive::{self, ImportError},
+        max_volume_size,
+    },
+};
+
+/// `/export`: asks which group assignment to archive.
+pub async fn choose_group_assignment(
+    bot: Bot,
+    msg: Message,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    let t = locale.messages();
+    let Some(keyboard) = compile::group_assignment_keyboard(msg.chat.id, "export:", &pool).await?
+    else {
+        bot.send_message(msg.chat.id, t.compile_none).await?;
+        return Ok(());
+    };
+
+    bot.send_message(msg.chat.id, t.export_choose)
+        .reply_markup(keyboard)
+        .await?;
+    Ok(())
+}
+
+/// Handles the buttons attached by `choose_group_assignment`: writes the zip archive
+/// of the group assignment to the server and sends it, unless it is too large for
+/// Telegram, then only its path is reported.
+pub async fn export(bot: Bot, q: CallbackQuery, locale: Locale, pool: PgPool) -> HandlerResult {
+    let t = locale.messages();
+    bot.answer_callback_query(q.id.clone()).await?;
+    let Some(message) = q.regular_message() else {
+        return Ok(());
+    };
+    let Some(group_assignment_id) = q
+        .data
+        .as_deref()
+        .and_then(|data| data.strip_prefix("export:"))
+        .and_then(|id| Uuid::parse_str(id).ok())
+    else {
+        return Ok(());
+    };
+
+    let courses = admin::administered_courses(message.chat.id, &pool).await?;
+    let Some(group_assignment) = sqlx::query!(
+        r#"
+        select a.title, g.name as "group_name"
+        from group_assignment ga
+        inner join assignment a on ga.assignment_id = a.id
+        inner join "group" g on ga.group_id = g.id
+        where ga.id = $1 and a.course_id = any($2)
+        "#,
+        group_assignment_id,
+        &courses,
+    )
+    .fetch_optional(&pool)
+    .await?
+    else {
+        return Ok(());
+    };
+
+    let substitutions = [
+        (
+            "title",
+            &group_assignment.title as &(dyn std::fmt::Display + Sync),
+        ),
+        ("group", &group_assignment.group_name),
+    ];
+    bot.edit_message_text(
+        message.chat.id,
+        message.id,
+        locale::fill(t.export_started, &substitutions),
+    )
+    .await?;
+
+    let path = archive::export_group_assignment(group_assignment_id, &pool).await?;
+    if std::fs::metadata(&path)?.len() as usize <= max_volume_size() {
+        bot.send_document(message.chat.id, InputFile::file(&path))
+            .caption(locale::fill(t.export_caption, &substitutions))
+            .await?;
+    } else {
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        bot.send_message(
+            message.chat.id,
+            locale::fill(
+                t.export_saved,
+                &[
+                    ("title", &group_assignment.title),
+                    ("group", &group_assignment.group_name),
+                    ("path", &path.display()),
+                    ("file", &file_name),
+                ],
+            ),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// `/import_archive` sent as the caption of a zip made by `/export`, or
+/// `/import_archive <file>` naming an archive in the archive directory of the server,
+/// for the archives too large to go through Telegram.
+pub async fn import_archive(
+    bot: Bot,
+    msg: Message,
+    file_name: String,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    let t = locale.messages();
+    let file_name = file_name.trim();
+    let result = if let Some(document) = msg.document() {
+        let file = bot.get_file(document.file.id.clone()).await?;
+        let mut data: Vec<u8> = Vec::new();
+        bot.download_file(&file.path, &mut data).await?;
+        archive::import_archive(Cursor::new(data), msg.chat.id, &pool).await
+    } else if !file_name.is_empty()
+        && Path::new(file_name).file_name().is_some_and(|name| name == file_name)
+    {
+        let Ok(file) = File::open(archive::archive_dir().join(file_name)) else {
+            bot.send_message(
+                msg.chat.id,
+                locale::fill(t.import_file_not_found, &[("file", &file_name)]),
+            )
+            .await?;
+            return Ok(());
+        };
+        archive::import_archive(file, msg.chat.id, &pool).await
+    } else {
+        bot.send_message(msg.chat.id, t.import_send_archive).await?;
+        return Ok(());
+    };
+
+    let text = match result {
+        Ok(summary) => locale::fill(
+            t.import_done,
+            &[
+                ("title", &summary.title),
+                ("group", &summary.group_name),
+                ("students", &summary.students),
+                ("submissions", &summary.submissions),
+            ],
+        ),
+        Err(ImportError::InvalidArchive(error)) => {
+            locale::fill(t.import_invalid, &[("error", &error)])
+        }
+        Err(ImportError::AlreadyImported) => t.import_already_imported.to_string(),
+        Err(ImportError::NotAdministered) => t.import_not_administered.to_string(),
+        Err(ImportError::Other(err)) => return Err(err),
+    };
+    bot.send_message(msg.chat.id, text)
+        .reply_to(msg.id)
+        .await?;
+
+    Ok(())
+}
@@ -12,7 +12,9 @@ use uuid::Uuid;
 use variant_generation::{VariantGeneratorInput, VariantGeneratorOutput};
 
 use crate::{
-    bot::{BotState, HandlerResult, MyDialogue, MyError, idle::{self, IdleState}},
+    bot::{BotState, HandlerResult, MyDialogue, MyError, idle::{self, IdleState}, language},
+    locale::{self, Locale},
+    submissions::similarity,
 };
 
 #[derive(Debug, Clone)]
@@ -77,14 +79,18 @@ pub mod commands {
 
     use super::*;
     use sqlx::query_scalar;
-    use teloxide::{types::{InlineKeyboardButton, LinkPreviewOptions}, utils::command::BotCommands};
+    use teloxide::types::{InlineKeyboardButton, LinkPreviewOptions};
 
 
     pub async fn help(
         bot: Bot,
         dialogue: MyDialogue,
+        locale: Locale,
     ) -> HandlerResult {
-        bot.send_message(dialogue.chat_id(), AssignmentCommand::descriptions().to_string())
+        bot.send_message(
+            dialogue.chat_id(),
+            language::command_help::<AssignmentCommand>(locale.messages().student_commands_header, locale),
+        )
             .await?;
         Ok(())
     }
@@ -94,17 +100,18 @@ pub mod commands {
         bot: Bot,
         dialogue: MyDialogue,
         (submission_id, variant_id): (Uuid, Uuid),
+        locale: Locale,
         pool: PgPool,
     ) -> HandlerResult {
+        let t = locale.messages();
 
-
-        commands::update_and_show_solutions(bot.clone(), dialogue.clone(), (submission_id, variant_id), pool.clone()).await?;
+        commands::update_and_show_solutions(bot.clone(), dialogue.clone(), (submission_id, variant_id), locale, pool.clone()).await?;
 
         let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![vec![
-            InlineKeyboardButton::callback("Завершить", "finish"),
-            InlineKeyboardButton::callback("Нет, продолжить", "continue"),
+            InlineKeyboardButton::callback(t.finish, "finish"),
+            InlineKeyboardButton::callback(t.continue_assignment, "continue"),
         ]]);
-        bot.send_message(dialogue.chat_id(), "Завершить выполнение задания?")
+        bot.send_message(dialogue.chat_id(), t.confirm_finish)
             .reply_markup(keyboard)
             .await?;
 
@@ -124,6 +131,7 @@ pub mod commands {
         bot: Bot,
         dialogue: MyDialogue,
         (submission_id, _variant_id): (Uuid, Uuid),
+        locale: Locale,
         pool: PgPool,
     ) -> HandlerResult {
 
@@ -131,11 +139,11 @@ pub mod commands {
            r#"select student_id from submission where id = $1"#, submission_id 
         ).fetch_one(&pool).await?;
 
-        bot.send_message(dialogue.chat_id(), "Чтобы продолжить выполнение задания, просто заново его откройте.")
+        bot.send_message(dialogue.chat_id(), locale.messages().go_back_hint)
             .await?;
 
         dialogue.update(BotState::Idle(IdleState::AwaitingCommand {student_id})).await?;
-        idle::help(bot, dialogue, student_id, pool).await?;
+        idle::help(bot, dialogue, student_id, locale, pool).await?;
 
         Ok(())
     }
@@ -159,8 +167,10 @@ pub mod commands {
         bot: Bot,
         dialogue: MyDialogue,
         (submission_id, _variant_id): (Uuid, Uuid),
+        locale: Locale,
         pool: PgPool,
     ) -> HandlerResult {
+        let t = locale.messages();
 
         let rec = sqlx::query!(
             r#"
@@ -179,18 +189,25 @@ pub mod commands {
         let (title, description, duration, deadline) = (rec.title, rec.description, rec.duration, rec.deadline);
         let deadline = match deadline {
             Some(dt) => dt.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string(),
-            None => "(не указано)".to_string(),
+            None => t.deadline_not_set.to_string(),
         };
 
         let time = match duration {
-            None => "(до крайнего срока)".to_string(),
-            Some(duration) => duration.format("%H часов %M минут").to_string(),
+            None => t.duration_until_deadline.to_string(),
+            Some(duration) => duration.format(t.duration_format).to_string(),
         };
 
 
-        let text = format!(
-            "{title}: \n\n{description}\n\nВремя на выполнение: {time}\n\nКрайний срок выполнения: {deadline}.\n\n{}",
-            include_str!("long_messages/submission_rules.txt"),
+        let text = locale::fill(
+            t.assignment_info,
+            &[
+                ("title", &title),
+                ("description", &description),
+                ("time", &time),
+                ("deadline", &deadline),
+                ("attempts", &""),
+                ("rules", &t.submission_rules),
+            ],
         );
 
 
@@ -213,12 +230,15 @@ pub mod commands {
         bot: Bot,
         dialogue: MyDialogue,
         (submission_id, _variant_id): (Uuid, Uuid),
+        locale: Locale,
         pool: PgPool,
     ) -> HandlerResult {
 
+        // items attached by the admin have no message to reply to
         let msg_ids = sqlx::query_scalar!(
             r#"
-            select message_id from submission_item where submission_id = $1
+            select message_id as "message_id!" from submission_item
+            where submission_id = $1 and message_id is not null
             "#,
             submission_id
         ).fetch_all(&pool).await?;
@@ -238,7 +258,7 @@ pub mod commands {
         }
 
         if no_solutions {
-            bot.send_message(dialogue.chat_id(), "Пока не прислано ни одного файла с решениями.").await?;
+            bot.send_message(dialogue.chat_id(), locale.messages().no_solutions_yet).await?;
         }
 
         Ok(())
@@ -249,27 +269,67 @@ pub mod commands {
         bot: Bot,
         dialogue: MyDialogue,
         (submission_id, _variant_id): (Uuid, Uuid),
+        locale: Locale,
         pool: PgPool) -> HandlerResult {
 
         let time_left = time_left(submission_id, pool.clone()).await?;
 
-        let time_left_text = match time_left {
-            None => "(оставшееся время не известно)".to_string(),
-            Some(time_left) => {
-                if time_left > TimeDelta::zero() {
-                    format!("{} мин. до конца", time_left.num_minutes())
-                } else {
-                    format!("просрочено на {} мин.", time_left.num_minutes().abs())
-                }
-            },
-        };
-
-        bot.send_message(dialogue.chat_id(), format!("Оставшееся время: {time_left_text}")).await?;
+        bot.send_message(dialogue.chat_id(), time_left_text(time_left, locale)).await?;
 
         Ok(())
     }
 }
 
+/// "N min. left" or "overdue by N min.".
+pub fn minutes_left_text(time_left: Option<TimeDelta>, locale: Locale) -> String {
+    let t = locale.messages();
+    match time_left {
+        None => t.time_left_unknown.to_string(),
+        Some(time_left) => {
+            if time_left > TimeDelta::zero() {
+                locale::fill(t.minutes_left, &[("minutes", &time_left.num_minutes())])
+            } else {
+                locale::fill(t.minutes_overdue, &[("minutes", &time_left.num_minutes().abs())])
+            }
+        },
+    }
+}
+
+fn time_left_text(time_left: Option<TimeDelta>, locale: Locale) -> String {
+    locale::fill(
+        locale.messages().time_left,
+        &[("time", &minutes_left_text(time_left, locale))],
+    )
+}
+
+/// Reminders before and after the end of the attempt.
+pub async fn set_time_left_reminders(
+    bot: Bot,
+    chat_id: ChatId,
+    submission_id: Uuid,
+    pool: PgPool,
+) -> HandlerResult {
+    let Some(time_left) = time_left(submission_id, pool.clone()).await? else {
+        return Ok(());
+    };
+
+    for time in [
+        time_left + TimeDelta::minutes(15), // overdue
+        time_left + TimeDelta::minutes(5),  // overdue
+        time_left,
+        time_left - TimeDelta::minutes(5),
+        time_left - TimeDelta::minutes(15),
+        time_left - TimeDelta::minutes(30),
+        time_left - TimeDelta::minutes(60),
+    ] {
+        if time > TimeDelta::zero() {
+            set_time_left_reminder(time, bot.clone(), chat_id, submission_id, pool.clone()).await?;
+        }
+    }
+
+    Ok(())
+}
+
 pub async fn set_time_left_reminder(
     reminder_time: TimeDelta,
     bot: Bot,
@@ -288,18 +348,10 @@ pub async fn set_time_left_reminder(
 
         if !is_finished {
             let time_left = time_left(submission_id, pool.clone()).await?;
-            let time_left_text = match time_left {
-                None => "(оставшееся время не известно)".to_string(),
-                Some(time_left) => {
-                    if time_left > TimeDelta::zero() {
-                        format!("{} мин. до конца", time_left.num_minutes())
-                    } else {
-                        format!("просрочено на {} мин.", time_left.num_minutes().abs())
-                    }
-                },
-            };
+            // the language may have been changed since the reminder was set
+            let locale = Locale::of_chat(chat_id, &pool).await;
 
-            bot.send_message(chat_id, format!("Оставшееся время: {time_left_text}")).await?;
+            bot.send_message(chat_id, time_left_text(time_left, locale)).await?;
         }
 
         Result::<(), MyError>::Ok(())
@@ -314,24 +366,58 @@ pub async fn insert_new_submission_with_new_variant(
     pool: PgPool,
 ) -> Result<(Uuid, Uuid), MyError> {
 
-    let assignment_id = sqlx::query_scalar!(
+    let variant_id = generate_variant(group_assignment_id, pool.clone()).await?;
+
+    let attempt_no: i32 = 1 + sqlx::query_scalar!(
         r#"
-        select assignment_id from group_assignment
-        where id = $1
+        select max(attempt_no)
+        from submission
+        where student_id = $1 and group_assignment_id = $2
         "#,
-        group_assignment_id
+        student_id,
+        group_assignment_id,
+    )
+        .fetch_one(&pool)
+        .await?
+        .unwrap_or(0);
+
+    let submission_id = sqlx::query_scalar!(
+        r#"
+        insert into submission (student_id, variant_id, group_assignment_id, attempt_no)
+        values ($1, $2, $3, $4)
+        returning id
+        "#,
+        student_id,
+        variant_id,
+        group_assignment_id,
+        attempt_no,
     ).fetch_one(&pool).await?;
 
-    let generator = sqlx::query_scalar!(
+    Ok((submission_id, variant_id))
+}
+
+/// Executable running assignments described by TOML specs.
+const SPEC_GENERATOR: &str = "spec_generator";
+
+/// Runs the assignment's generator for the next variant number, with the generator
+/// parameters of the group assignment, and stores the variant.
+pub async fn generate_variant(
+    group_assignment_id: Uuid,
+    pool: PgPool,
+) -> Result<Uuid, MyError> {
+
+    let rec = sqlx::query!(
         r#"
-        select generator
-        from assignment
-        where id = $1
+        select a.id as "assignment_id", a.generator, ga.generator_parameters
+        from group_assignment ga
+        inner join assignment a on ga.assignment_id = a.id
+        where ga.id = $1
         "#,
-        assignment_id
+        group_assignment_id
     )
         .fetch_one(&pool)
         .await?;
+    let (assignment_id, generator) = (rec.assignment_id, rec.generator);
 
     let variant_no: i32 = 1 + sqlx::query_scalar!(
         r#"
@@ -345,13 +431,34 @@ pub async fn insert_new_submission_with_new_variant(
         .await?
         .unwrap_or(0);
 
+    // variants of the neighbours, which the generator tries not to repeat
+    let issued_fingerprints = sqlx::query_scalar!(
+        r#"
+        select v.fingerprint as "fingerprint!"
+        from submission s
+        inner join variant v on v.id = s.variant_id
+        where s.group_assignment_id = $1 and v.fingerprint is not null
+        "#,
+        group_assignment_id
+    )
+        .fetch_all(&pool)
+        .await?;
+
+    // Specs are run by the generic generator, which finds the spec by `generator`.
+    let exe_name = if generator.ends_with(".toml") {
+        SPEC_GENERATOR.to_string()
+    } else {
+        generator.clone()
+    };
     let exe_path = std::env::current_exe()
         .unwrap()
-        .with_file_name(generator.clone());
+        .with_file_name(exe_name);
 
     let input = VariantGeneratorInput {
         variant_number: variant_no,
         generator,
+        parameters: rec.generator_parameters,
+        issued_fingerprints,
     };
 
     let output = 
@@ -394,8 +501,8 @@ pub async fn insert_new_submission_with_new_variant(
 
     let variant_id = sqlx::query_scalar!(
         r#"
-        insert into variant (variant_no, assignment_id, problem_code, solution_code, problem_images, solution_images)
-        values ($1, $2, $3, $4, $5, $6)
+        insert into variant (variant_no, assignment_id, problem_code, solution_code, problem_images, solution_images, fingerprint)
+        values ($1, $2, $3, $4, $5, $6, $7)
         returning id
         "#,
         variant_no,
@@ -404,21 +511,11 @@ pub async fn insert_new_submission_with_new_variant(
         solution_code,
         &problem_images,
         &solution_images,
+        output.fingerprint,
     ).fetch_one(&pool)
         .await?;
 
-    let submission_id = sqlx::query_scalar!(
-        r#"
-        insert into submission (student_id, variant_id, group_assignment_id)
-        values ($1, $2, $3)
-        returning id
-        "#,
-        student_id,
-        variant_id,
-        group_assignment_id,
-    ).fetch_one(&pool).await?;
-
-    Ok((submission_id, variant_id))
+    Ok(variant_id)
 }
 
 pub async fn send_problems(
@@ -450,16 +547,38 @@ pub async fn send_problems(
     Ok(())
 }
 
+/// Solutions of the variant, once they are released to the student.
+pub async fn send_solutions(
+    bot: Bot,
+    chat_id: ChatId,
+    variant_id: Uuid,
+    pool: PgPool,
+) -> HandlerResult {
+    let solution_images = sqlx::query_scalar!(
+        r#"select solution_images from variant where id = $1"#,
+        variant_id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    let media_group = solution_images.into_iter().map(|image| InputMedia::Photo(InputMediaPhoto::new(InputFile::memory(image)))).collect::<Vec<_>>();
+
+    if !media_group.is_empty() {
+        bot.send_media_group(chat_id, media_group).await?;
+    }
+    Ok(())
+}
+
 pub async fn time_left(
     submission_id: Uuid,
     pool: PgPool) -> Result<Option<TimeDelta>, MyError> {
 
     let now = Utc::now();
 
-    let (started_at, deadline, duration) = {
+    let (started_at, deadline, duration, reopened_until) = {
         let rec = sqlx::query!(
             r#"
-            select s.started_at, ga.deadline, a.duration
+            select s.started_at, ga.deadline, a.duration, s.reopened_until
             from submission s
             inner join group_assignment ga on s.group_assignment_id = ga.id
             inner join assignment a on ga.assignment_id = a.id
@@ -467,8 +586,14 @@ pub async fn time_left(
             "#,
             submission_id
         ).fetch_one(&pool).await?;
-        (rec.started_at, rec.deadline, rec.duration)
+        (rec.started_at, rec.deadline, rec.duration, rec.reopened_until)
     };
+
+    // a reopened submission lasts until the time set by the admin
+    if let Some(reopened_until) = reopened_until {
+        return Ok(Some(reopened_until - now));
+    }
+
     let time_left = {
         let duration_left = duration.map(|d| (d - NaiveTime::from_hms_opt(0, 0, 0).unwrap()) - (now - started_at));
         let deadline_left = deadline.map(|d| d - now);
@@ -489,8 +614,10 @@ pub async fn awaiting_solutions(
     dialogue: MyDialogue,
     msg: Message,
     (submission_id, variant_id): (Uuid, Uuid),
+    locale: Locale,
     pool: PgPool,
 ) -> HandlerResult {
+    let t = locale.messages();
 
     if sqlx::query_scalar!(
         r#"select finished_at from submission where id = $1"#, 
@@ -499,12 +626,12 @@ pub async fn awaiting_solutions(
 
         bot.send_message(
             dialogue.chat_id(),
-            "Время закончилось, выполнение задания уже завершено."
+            t.already_finished
         ).await?;
 
-        commands::update_and_show_solutions(bot.clone(), dialogue.clone(), (submission_id, variant_id), pool.clone()).await?;
+        commands::update_and_show_solutions(bot.clone(), dialogue.clone(), (submission_id, variant_id), locale, pool.clone()).await?;
             
-        finish_assignment(bot, dialogue, submission_id, pool).await?;
+        finish_assignment(bot, dialogue, submission_id, locale, pool).await?;
 
         return Ok(());
     };
@@ -512,7 +639,7 @@ pub async fn awaiting_solutions(
     let MessageKind::Common(MessageCommon{media_kind: data, ..}) = msg.kind else {
         bot.send_message(
             dialogue.chat_id(),
-            "Данный тип сообщений не поддерживается (попробуйте обычные сообщения)."
+            t.unsupported_message_kind
         ).await?;
         return Ok(());
     };
@@ -523,19 +650,20 @@ pub async fn awaiting_solutions(
         Text(_) => {
             bot.send_message(
             dialogue.chat_id(),
-                "Неизвестная команда, попробуйте /help."
+                t.unknown_command_try_help
             ).await?;
         },
         Photo(_) => {
             bot.delete_message(dialogue.chat_id(), msg.id).await?;
             bot.send_message(
             dialogue.chat_id(),
-                "Пожалуйста, пришлите фотографии в виде несжатых файлов (см. /get_rules)"
+                t.send_photos_as_files
             ).await?;
         },
         Document(MediaDocument {document, ..} ) => {
 
             let mime = document.mime_type.map(|m| m.to_string()).unwrap_or("bin".into());
+            let file_name = document.file_name;
             let file = bot.get_file(document.file.id).await?;
             let mut data: Vec<u8> = Vec::new();
             bot.download_file(&file.path, &mut data).await?;
@@ -547,22 +675,30 @@ pub async fn awaiting_solutions(
                 },
                 _ => 1,
             } as i32;
+            let hashes = similarity::hash_item(&data, &mime);
 
             sqlx::query!(
                 r#"
-                insert into submission_item (submission_id, message_id, data, pages, extension)
-                values ($1, $2, $3, $4, $5)
+                insert into submission_item
+                    (submission_id, message_id, data, pages, extension, file_name, content_hash, page_hashes)
+                values ($1, $2, $3, $4, $5, $6, $7, $8)
                 on conflict(submission_id, message_id)
                 do update set
                     data = excluded.data,
                     extension = excluded.extension,
-                    pages = excluded.pages
+                    pages = excluded.pages,
+                    file_name = excluded.file_name,
+                    content_hash = excluded.content_hash,
+                    page_hashes = excluded.page_hashes
                 "#,
                 submission_id,
                 msg.id.0,
                 data,
                 pages,
-                mime
+                mime,
+                file_name,
+                hashes.content_hash,
+                &hashes.page_hashes as &[Option<i64>],
             ).execute(&pool).await?;
 
             bot.send_message(
@@ -575,7 +711,7 @@ pub async fn awaiting_solutions(
         _ => {
             bot.send_message(
                 dialogue.chat_id(), 
-                "Данный тип сообщений не поддерживается"
+                t.unsupported_message
             ).await?;
         }
     };
@@ -589,14 +725,15 @@ pub async fn awaiting_finish(
     dialogue: MyDialogue,
     q: CallbackQuery,
     (submission_id, variant_id): (Uuid, Uuid),
+    locale: Locale,
     pool: PgPool,
 ) -> HandlerResult {
     let answer = q.data.clone().unwrap();
     let msg = q.regular_message().unwrap();
 
     if answer == "finish".to_string()  {
-        bot.edit_message_text(dialogue.chat_id(), msg.id, "Выполнение задания завершено!").await?;
-        finish_assignment(bot, dialogue, submission_id, pool).await?;
+        bot.edit_message_text(dialogue.chat_id(), msg.id, locale.messages().assignment_finished).await?;
+        finish_assignment(bot, dialogue, submission_id, locale, pool).await?;
     } else {
         bot.delete_message(dialogue.chat_id(), msg.id).await?;
 
@@ -614,6 +751,7 @@ pub async fn finish_assignment (
     bot: Bot,
     dialogue: MyDialogue,
     submission_id: Uuid,
+    locale: Locale,
     pool: PgPool,
 ) -> HandlerResult {
         // bot.edit_message_text(dialogue.chat_id(), msg.id, "Выполнение задания завершено!").await?;
@@ -633,7 +771,7 @@ pub async fn finish_assignment (
         dialogue.update(BotState::Idle(IdleState::AwaitingCommand {
             student_id
         })).await?;
-        idle::help(bot, dialogue, student_id, pool).await?;
+        idle::help(bot, dialogue, student_id, locale, pool).await?;
 
     Ok(())
 }
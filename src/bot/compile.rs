@@ -0,0 +1,217 @@
+use sqlx::PgPool;
+use teloxide::{
+    Bot,
+    payloads::{EditMessageTextSetters, SendDocumentSetters, SendMessageSetters},
+    prelude::Requester,
+    types::{
+        CallbackQuery, ChatId, InlineKeyboardButton, InlineKeyboardMarkup, InputFile, Message,
+    },
+};
+use uuid::Uuid;
+
+use crate::{
+    bot::{HandlerResult, MyError, admin},
+    locale::{self, Locale},
+    submissions,
+};
+
+/// Started group assignments offered by `/compile` and `/export`.
+const MAX_GROUP_ASSIGNMENTS: i64 = 30;
+
+/// Buttons `<prefix><group assignment id>` for the started group assignments of the
+/// courses administered from the chat, `None` if there are none.
+pub async fn group_assignment_keyboard(
+    chat_id: ChatId,
+    prefix: &str,
+    pool: &PgPool,
+) -> Result<Option<InlineKeyboardMarkup>, MyError> {
+    let courses = admin::administered_courses(chat_id, pool).await?;
+
+    let group_assignments = sqlx::query!(
+        r#"
+        select ga.id, a.title, g.name as "group_name"
+        from group_assignment ga
+        inner join assignment a on ga.assignment_id = a.id
+        inner join "group" g on ga.group_id = g.id
+        where a.course_id = any($1) and ga.available_at <= now()
+        order by ga.available_at desc, a.title, g.name
+        limit $2
+        "#,
+        &courses,
+        MAX_GROUP_ASSIGNMENTS,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    if group_assignments.is_empty() {
+        return Ok(None);
+    }
+
+    let keyboard = group_assignments
+        .into_iter()
+        .map(|group_assignment| {
+            vec![InlineKeyboardButton::callback(
+                format!("{} — {}", group_assignment.title, group_assignment.group_name),
+                format!("{prefix}{}", group_assignment.id),
+            )]
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Some(InlineKeyboardMarkup::new(keyboard)))
+}
+
+/// `/compile`: lets the admin compile the solutions of a group assignment before
+/// its deadline, e.g. to start grading the students who finished early.
+pub async fn choose_group_assignment(
+    bot: Bot,
+    msg: Message,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    let t = locale.messages();
+    let Some(keyboard) = group_assignment_keyboard(msg.chat.id, "compile:", &pool).await? else {
+        bot.send_message(msg.chat.id, t.compile_none).await?;
+        return Ok(());
+    };
+
+    bot.send_message(msg.chat.id, t.compile_choose)
+        .reply_markup(keyboard)
+        .await?;
+    Ok(())
+}
+
+/// Handles the buttons attached by `choose_group_assignment`: first asks whether to
+/// include the submissions in progress, then compiles and sends the volumes.
+pub async fn compile(bot: Bot, q: CallbackQuery, locale: Locale, pool: PgPool) -> HandlerResult {
+    let t = locale.messages();
+    bot.answer_callback_query(q.id.clone()).await?;
+    let Some(message) = q.regular_message() else {
+        return Ok(());
+    };
+    let Some((group_assignment_id, mode)) = q.data.as_deref().and_then(|data| {
+        let mut parts = data.strip_prefix("compile:")?.split(':');
+        let id = Uuid::parse_str(parts.next()?).ok()?;
+        Some((id, parts.next().map(str::to_string)))
+    }) else {
+        return Ok(());
+    };
+
+    let courses = admin::administered_courses(message.chat.id, &pool).await?;
+    let Some(group_assignment) = sqlx::query!(
+        r#"
+        select a.title, g.name as "group_name",
+               count(s.id) filter (where s.finished_at is not null) as "finished!",
+               count(s.id) filter (where s.finished_at is null) as "in_progress!"
+        from group_assignment ga
+        inner join assignment a on ga.assignment_id = a.id
+        inner join "group" g on ga.group_id = g.id
+        left join submission s on s.group_assignment_id = ga.id
+        where ga.id = $1 and a.course_id = any($2)
+        group by a.title, g.name
+        "#,
+        group_assignment_id,
+        &courses,
+    )
+    .fetch_optional(&pool)
+    .await?
+    else {
+        return Ok(());
+    };
+
+    let counts = [
+        (
+            "title",
+            &group_assignment.title as &(dyn std::fmt::Display + Sync),
+        ),
+        ("group", &group_assignment.group_name),
+        ("finished", &group_assignment.finished),
+        ("in_progress", &group_assignment.in_progress),
+    ];
+
+    let include_in_progress = match mode.as_deref() {
+        Some("finished") => false,
+        Some("all") => true,
+        _ => {
+            let keyboard = InlineKeyboardMarkup::new(vec![vec![
+                InlineKeyboardButton::callback(
+                    t.compile_finished_only,
+                    format!("compile:{group_assignment_id}:finished"),
+                ),
+                InlineKeyboardButton::callback(
+                    t.compile_include_in_progress,
+                    format!("compile:{group_assignment_id}:all"),
+                ),
+            ]]);
+            bot.edit_message_text(
+                message.chat.id,
+                message.id,
+                locale::fill(t.compile_choose_mode, &counts),
+            )
+            .reply_markup(keyboard)
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let submission_count = if include_in_progress {
+        group_assignment.finished + group_assignment.in_progress
+    } else {
+        group_assignment.finished
+    };
+    if submission_count == 0 {
+        bot.edit_message_text(message.chat.id, message.id, t.compile_empty)
+            .await?;
+        return Ok(());
+    }
+
+    bot.edit_message_text(
+        message.chat.id,
+        message.id,
+        locale::fill(t.compile_started, &counts),
+    )
+    .await?;
+
+    let time = chrono::Local::now().format("%Y-%m-%d %H:%M").to_string();
+    let volumes = submissions::compile_group_assignment_volumes(
+        group_assignment_id,
+        include_in_progress,
+        locale,
+        submissions::max_volume_size(),
+        pool,
+    )
+    .await?;
+
+    let volume_count = volumes.len();
+    for (i, volume) in volumes.into_iter().enumerate() {
+        let volume_no = i + 1;
+        let mut substitutions = counts.to_vec();
+        substitutions.extend([
+            ("time", &time as &(dyn std::fmt::Display + Sync)),
+            ("volume", &volume_no),
+            ("count", &volume_count),
+        ]);
+        let (caption, file_name) = if volume_count == 1 {
+            (
+                locale::fill(t.compile_caption, &substitutions),
+                format!("solutions_{group_assignment_id}_partial"),
+            )
+        } else {
+            (
+                locale::fill(t.compile_volume_caption, &substitutions),
+                format!("solutions_{group_assignment_id}_partial_{volume_no}"),
+            )
+        };
+
+        let document = bot
+            .send_document(
+                message.chat.id,
+                InputFile::memory(volume.pdf).file_name(format!("{file_name}.pdf")),
+            )
+            .caption(caption)
+            .await?;
+        submissions::send_page_map(&bot, message.chat.id, &volume.page_map, &file_name, document.id)
+            .await?;
+    }
+
+    Ok(())
+}
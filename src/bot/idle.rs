@@ -1,4 +1,4 @@
-use chrono::{DateTime, TimeDelta, Utc};
+use chrono::{DateTime, Utc};
 use sqlx::{PgPool, query, query_scalar};
 use teloxide::{
     Bot,
@@ -12,9 +12,13 @@ use teloxide::{
 };
 use uuid::Uuid;
 
-use crate::bot::{
-    BotState, HandlerResult, MyDialogue,
-    assignment::{self, AssignmentState},
+use crate::{
+    bot::{
+        BotState, HandlerResult, MyDialogue,
+        assignment::{self, AssignmentState},
+        language, profile, registration, results,
+    },
+    locale::{self, Locale},
 };
 
 #[derive(BotCommands, Clone)]
@@ -24,6 +28,16 @@ pub enum IdleCommand {
     Help,
     #[command(description = "показать доступные задания.")]
     ShowAssignments,
+    #[command(description = "показать результаты выполненных заданий.")]
+    MyResults,
+    #[command(description = "показать данные профиля.")]
+    Me,
+    #[command(description = "исправить ФИО.")]
+    ChangeName,
+    #[command(description = "сменить группу (с подтверждения преподавателя).")]
+    ChangeGroup,
+    #[command(description = "переключиться на другой курс или записаться на новый.")]
+    Courses,
 }
 
 #[derive(Debug, Clone)]
@@ -35,6 +49,15 @@ pub enum IdleState {
         student_id: Uuid,
         group_assignment_id: Uuid,
     },
+    AwaitingNewName {
+        student_id: Uuid,
+    },
+    AwaitingNewGroup {
+        student_id: Uuid,
+    },
+    AwaitingCourseChoice {
+        student_id: Uuid,
+    },
 }
 
 pub fn idle_handler() -> Handler<'static, HandlerResult, teloxide::dispatching::DpHandlerDescription>
@@ -49,16 +72,42 @@ pub fn idle_handler() -> Handler<'static, HandlerResult, teloxide::dispatching::
                             .branch(
                                 dptree::case![IdleCommand::ShowAssignments]
                                     .endpoint(show_assignments_list),
-                            ),
+                            )
+                            .branch(
+                                dptree::case![IdleCommand::MyResults]
+                                    .endpoint(results::show_results),
+                            )
+                            .branch(dptree::case![IdleCommand::Me].endpoint(profile::show_profile))
+                            .branch(
+                                dptree::case![IdleCommand::ChangeName]
+                                    .endpoint(profile::change_name),
+                            )
+                            .branch(
+                                dptree::case![IdleCommand::ChangeGroup]
+                                    .endpoint(profile::change_group),
+                            )
+                            .branch(dptree::case![IdleCommand::Courses].endpoint(show_courses)),
                     )
                     .branch(dptree::endpoint(unknown_command)),
+            )
+            .branch(
+                dptree::case![IdleState::AwaitingNewName { student_id }]
+                    .endpoint(profile::awaiting_new_name),
             ),
         )
         .branch(
             Update::filter_callback_query()
                 .branch(
                     dptree::case![IdleState::AwaitingCommand { student_id }]
-                        .endpoint(show_assignment),
+                        .branch(
+                            dptree::filter(|q: CallbackQuery| {
+                                q.data
+                                    .as_deref()
+                                    .is_some_and(|data| data.starts_with("results:"))
+                            })
+                            .endpoint(results::send_item),
+                        )
+                        .branch(dptree::endpoint(show_assignment)),
                 )
                 .branch(
                     dptree::case![IdleState::AwaitingAssignmentStart {
@@ -66,6 +115,14 @@ pub fn idle_handler() -> Handler<'static, HandlerResult, teloxide::dispatching::
                         group_assignment_id
                     }]
                     .endpoint(awaiting_assignment_start),
+                )
+                .branch(
+                    dptree::case![IdleState::AwaitingNewGroup { student_id }]
+                        .endpoint(profile::awaiting_new_group),
+                )
+                .branch(
+                    dptree::case![IdleState::AwaitingCourseChoice { student_id }]
+                        .endpoint(awaiting_course_choice),
                 ),
         )
 }
@@ -74,10 +131,14 @@ pub async fn help(
     bot: Bot,
     dialogue: MyDialogue,
     _student_id: Uuid,
+    locale: Locale,
     _pool: PgPool,
 ) -> HandlerResult {
-    bot.send_message(dialogue.chat_id(), IdleCommand::descriptions().to_string())
-        .await?;
+    bot.send_message(
+        dialogue.chat_id(),
+        language::command_help::<IdleCommand>(locale.messages().student_commands_header, locale),
+    )
+    .await?;
     Ok(())
 }
 
@@ -85,11 +146,129 @@ pub async fn unknown_command(
     bot: Bot,
     dialogue: MyDialogue,
     _student_id: Uuid,
+    locale: Locale,
     _pool: PgPool,
 ) -> HandlerResult {
-    bot.send_message(dialogue.chat_id(), "Ошибка: Неизвестная команда.".to_string())
+    bot.send_message(dialogue.chat_id(), locale.messages().unknown_command)
+        .await?;
+    help(bot, dialogue, _student_id, locale, _pool).await?;
+    Ok(())
+}
+
+/// Lists the courses the account is enrolled in (one student row per course) and
+/// offers to enroll in another one.
+pub async fn show_courses(
+    bot: Bot,
+    dialogue: MyDialogue,
+    student_id: Uuid,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    let t = locale.messages();
+    let courses = query!(
+        r#"
+        select other.id, c.title, g.name as "group_name"
+        from student st
+        inner join student other on other.telegram_id = st.telegram_id
+        inner join "group" g on other.group_id = g.id
+        inner join course c on g.course_id = c.id
+        where st.id = $1
+        order by c.title
+        "#,
+        student_id
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    let mut keyboard = courses
+        .into_iter()
+        .map(|course| {
+            let mark = if course.id == student_id { "✓ " } else { "" };
+            vec![InlineKeyboardButton::callback(
+                format!("{mark}{} ({})", course.title, course.group_name),
+                format!("student:{}", course.id),
+            )]
+        })
+        .collect::<Vec<_>>();
+    keyboard.push(vec![InlineKeyboardButton::callback(t.enroll, "enroll")]);
+
+    bot.send_message(dialogue.chat_id(), t.your_courses)
+        .reply_markup(teloxide::types::InlineKeyboardMarkup::new(keyboard))
+        .await?;
+
+    dialogue
+        .update(BotState::Idle(IdleState::AwaitingCourseChoice { student_id }))
+        .await?;
+    Ok(())
+}
+
+async fn awaiting_course_choice(
+    bot: Bot,
+    dialogue: MyDialogue,
+    q: CallbackQuery,
+    student_id: Uuid,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    if let Some(message) = q.regular_message() {
+        bot.edit_message_reply_markup(message.chat.id, message.id)
+            .await?;
+    }
+
+    let data = q.data.clone().unwrap_or_default();
+
+    if data == "enroll" {
+        let telegram_id = query_scalar!(
+            r#"select telegram_id from student where id = $1"#,
+            student_id
+        )
+        .fetch_one(&pool)
+        .await?;
+
+        if !registration::start_registration(bot, dialogue.clone(), telegram_id, locale, pool)
+            .await?
+        {
+            dialogue
+                .update(BotState::Idle(IdleState::AwaitingCommand { student_id }))
+                .await?;
+        }
+        return Ok(());
+    }
+
+    let chosen = match data.strip_prefix("student:").and_then(|id| Uuid::parse_str(id).ok()) {
+        Some(chosen_id) => query!(
+            r#"
+            select other.id, c.title
+            from student st
+            inner join student other on other.telegram_id = st.telegram_id
+            inner join "group" g on other.group_id = g.id
+            inner join course c on g.course_id = c.id
+            where st.id = $1 and other.id = $2
+            "#,
+            student_id,
+            chosen_id
+        )
+        .fetch_optional(&pool)
+        .await?
+        .map(|rec| (rec.id, rec.title)),
+        None => None,
+    };
+
+    let student_id = match chosen {
+        Some((chosen_id, title)) => {
+            bot.send_message(
+                dialogue.chat_id(),
+                locale::fill(locale.messages().current_course, &[("title", &title)]),
+            )
+            .await?;
+            chosen_id
+        }
+        None => student_id,
+    };
+
+    dialogue
+        .update(BotState::Idle(IdleState::AwaitingCommand { student_id }))
         .await?;
-    help(bot, dialogue, _student_id, _pool).await?;
     Ok(())
 }
 
@@ -97,24 +276,44 @@ pub async fn show_assignments_list(
     bot: Bot,
     dialogue: MyDialogue,
     student_id: Uuid,
+    locale: Locale,
     pool: PgPool,
 ) -> HandlerResult {
-    fn button_text(title: &String, deadline: &Option<DateTime<Utc>>) -> String {
+    let t = locale.messages();
+    let button_text = |title: &String,
+                       deadline: &Option<DateTime<Utc>>,
+                       attempts_used: i64,
+                       max_attempts: i32|
+     -> String {
         format!(
-            "{}{}",
+            "{}{}{}",
             title,
             if let Some(deadline) = deadline {
-                format!(
-                    " до {}",
-                    deadline
-                        .with_timezone(&chrono::Local)
-                        .format("%Y-%m-%d %H:%M")
+                locale::fill(
+                    t.assignment_until,
+                    &[(
+                        "deadline",
+                        &deadline
+                            .with_timezone(&chrono::Local)
+                            .format("%Y-%m-%d %H:%M"),
+                    )],
+                )
+            } else {
+                t.assignment_no_deadline.to_string()
+            },
+            if max_attempts > 1 {
+                locale::fill(
+                    t.assignment_attempt,
+                    &[
+                        ("attempt", &(attempts_used + 1)),
+                        ("max_attempts", &max_attempts),
+                    ],
                 )
             } else {
-                " (крайний срок сдачи не указан)".to_string()
+                "".to_string()
             }
         )
-    }
+    };
     let group_id = query_scalar!(
         r#"
                     select group_id
@@ -129,19 +328,20 @@ pub async fn show_assignments_list(
 
     let data = sqlx::query!(
         r#"
-        SELECT a.title, a.generator, ga.deadline
+        SELECT a.title, a.generator, ga.deadline, ga.max_attempts, attempts.used as "attempts_used!"
         FROM assignment a
         INNER JOIN group_assignment ga ON a.id = ga.assignment_id
-        WHERE ga.group_id = $1
-        AND (ga.deadline IS NULL OR now() < ga.deadline)
-        AND NOT ga.completed
-        AND NOT EXISTS (
-            SELECT 1
+        CROSS JOIN LATERAL (
+            SELECT count(*) as used
             FROM submission s
             WHERE s.student_id = $2
             AND s.group_assignment_id = ga.id
             AND s.finished_at IS NOT NULL
-        )
+        ) attempts
+        WHERE ga.group_id = $1
+        AND (ga.deadline IS NULL OR now() < ga.deadline)
+        AND NOT ga.completed
+        AND attempts.used < ga.max_attempts
         "#,
         group_id,
         student_id
@@ -149,25 +349,25 @@ pub async fn show_assignments_list(
     .fetch_all(&pool)
     .await?
     .into_iter()
-    .map(|row| (row.title, row.generator, row.deadline))
+    .map(|row| (row.title, row.generator, row.deadline, row.attempts_used, row.max_attempts))
     .collect::<Vec<_>>();
 
     if data.is_empty() {
-        bot.send_message(dialogue.chat_id(), "Сейчас нет доступных заданий.")
+        bot.send_message(dialogue.chat_id(), t.no_assignments)
             .await?;
     } else {
         let keyboard = teloxide::types::InlineKeyboardMarkup::new(
             data.into_iter()
-                .map(|(title, generator, deadline)| {
+                .map(|(title, generator, deadline, attempts_used, max_attempts)| {
                     vec![InlineKeyboardButton::callback(
-                        button_text(&title, &deadline),
+                        button_text(&title, &deadline, attempts_used, max_attempts),
                         generator,
                     )]
                 })
                 .collect::<Vec<_>>(),
         );
 
-        bot.send_message(dialogue.chat_id(), "Выбирите задание:")
+        bot.send_message(dialogue.chat_id(), t.choose_assignment)
             .reply_markup(keyboard)
             .await?;
     }
@@ -180,8 +380,10 @@ async fn show_assignment(
     dialogue: MyDialogue,
     q: CallbackQuery,
     student_id: Uuid,
+    locale: Locale,
     pool: PgPool,
 ) -> HandlerResult {
+    let t = locale.messages();
     let assignment_generator = q.data.clone().unwrap();
 
     let group_id = query_scalar!(
@@ -198,20 +400,21 @@ async fn show_assignment(
 
     let assignment_rec = query!(
         r#"
-        SELECT ga.id, a.title, a.description, a.duration, ga.deadline
+        SELECT ga.id, a.title, a.description, a.duration, ga.deadline, ga.max_attempts, attempts.used as "attempts_used!"
         FROM assignment a
         INNER JOIN group_assignment ga ON a.id = ga.assignment_id
-        WHERE ga.group_id = $1
-        AND a.generator = $2
-        AND (ga.deadline IS NULL OR now() < ga.deadline)
-        AND NOT ga.completed
-        AND NOT EXISTS (
-            SELECT 1
+        CROSS JOIN LATERAL (
+            SELECT count(*) as used
             FROM submission s
             WHERE s.student_id = $3
             AND s.group_assignment_id = ga.id
             AND s.finished_at IS NOT NULL
-        )
+        ) attempts
+        WHERE ga.group_id = $1
+        AND a.generator = $2
+        AND (ga.deadline IS NULL OR now() < ga.deadline)
+        AND NOT ga.completed
+        AND attempts.used < ga.max_attempts
         "#,
         group_id,
         assignment_generator,
@@ -226,29 +429,58 @@ async fn show_assignment(
             rec.description,
             rec.duration,
             rec.deadline,
+            rec.attempts_used,
+            rec.max_attempts,
         )
     });
 
     let message = q.regular_message().unwrap();
 
     match assignment_rec {
-        Some((group_assignment_id, title, description, duration, deadline)) => {
+        Some((
+            group_assignment_id,
+            title,
+            description,
+            duration,
+            deadline,
+            attempts_used,
+            max_attempts,
+        )) => {
             let deadline = match deadline {
                 Some(dt) => dt
                     .with_timezone(&chrono::Local)
                     .format("%Y-%m-%d %H:%M")
                     .to_string(),
-                None => "(не указано)".to_string(),
+                None => t.deadline_not_set.to_string(),
             };
 
             let time = match duration {
-                None => "(до крайнего срока)".to_string(),
-                Some(duration) => duration.format("%H часов %M минут").to_string(),
+                None => t.duration_until_deadline.to_string(),
+                Some(duration) => duration.format(t.duration_format).to_string(),
+            };
+
+            let attempts = if max_attempts > 1 {
+                locale::fill(
+                    t.attempts_info,
+                    &[
+                        ("attempt", &(attempts_used + 1)),
+                        ("max_attempts", &max_attempts),
+                    ],
+                )
+            } else {
+                "".to_string()
             };
 
-            let text = format!(
-                "{title}: \n\n{description}\n\nВремя на выполнение: {time}\n\nКрайний срок выполнения: {deadline}.\n\n{}",
-                include_str!("long_messages/submission_rules.txt"),
+            let text = locale::fill(
+                t.assignment_info,
+                &[
+                    ("title", &title),
+                    ("description", &description),
+                    ("time", &time),
+                    ("deadline", &deadline),
+                    ("attempts", &attempts),
+                    ("rules", &t.submission_rules),
+                ],
             );
 
             bot.edit_message_text(message.chat.id, message.id, text)
@@ -263,10 +495,10 @@ async fn show_assignment(
                 .await?;
 
             let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![vec![
-                InlineKeyboardButton::callback("Приступить", "start"),
-                InlineKeyboardButton::callback("Назад", "back"),
+                InlineKeyboardButton::callback(t.start_assignment, "start"),
+                InlineKeyboardButton::callback(t.back, "back"),
             ]]);
-            bot.send_message(message.chat.id, "Можно приступить прямо сейчас:")
+            bot.send_message(message.chat.id, t.can_start_now)
                 .reply_markup(keyboard)
                 .await?;
 
@@ -278,12 +510,8 @@ async fn show_assignment(
                 .await?;
         }
         None => {
-            bot.edit_message_text(
-                message.chat.id,
-                message.id,
-                "Ошибка: выбранное задание недоступно.",
-            )
-            .await?;
+            bot.edit_message_text(message.chat.id, message.id, t.assignment_unavailable)
+                .await?;
         }
     }
 
@@ -300,19 +528,21 @@ async fn awaiting_assignment_start(
     dialogue: MyDialogue,
     q: CallbackQuery,
     (student_id, group_assignment_id): (Uuid, Uuid),
+    locale: Locale,
     pool: PgPool,
 ) -> HandlerResult {
+    let t = locale.messages();
     let answer = q.data.clone().unwrap();
     let msg = q.regular_message().unwrap();
 
     if answer == "start".to_string() {
-        bot.edit_message_text(dialogue.chat_id(), msg.id, "Генерируем вариант...")
+        bot.edit_message_text(dialogue.chat_id(), msg.id, t.generating_variant)
             .await?;
 
         let rec = query!(
             r#"
             select id, variant_id from submission
-            where student_id = $1 and group_assignment_id = $2
+            where student_id = $1 and group_assignment_id = $2 and finished_at is null
             "#,
             student_id,
             group_assignment_id,
@@ -333,48 +563,32 @@ async fn awaiting_assignment_start(
             {
                 Ok((submissinon_id, variant_id)) => (submissinon_id, variant_id),
                 Err(_) => {
-                    bot.edit_message_text(dialogue.chat_id(), msg.id, "Не удалось сгенерировать задачи. Для устранения ошибки, обратитесь к преподавателю.")
+                    bot.edit_message_text(dialogue.chat_id(), msg.id, t.generation_failed)
                         .await?;
 
                     dialogue
                         .update(BotState::Idle(IdleState::AwaitingCommand { student_id }))
                         .await?;
-                    help(bot, dialogue, student_id, pool).await?;
+                    help(bot, dialogue, student_id, locale, pool).await?;
 
                     return Ok(());
                 }
             }
         };
 
-        bot.edit_message_text(dialogue.chat_id(), msg.id, "Задачи: ")
+        bot.edit_message_text(dialogue.chat_id(), msg.id, t.problems)
             .await?;
 
         assignment::send_problems(bot.clone(), dialogue.chat_id(), variant_id, pool.clone()).await?;
 
-        let time_left = assignment::time_left(submission_id, pool.clone()).await?;
-        if let Some(time_left) = time_left {
-            for time in [
-                time_left + TimeDelta::minutes(15), // overdue
-                time_left + TimeDelta::minutes(5),  // overdue
-                time_left,
-                time_left - TimeDelta::minutes(5),
-                time_left - TimeDelta::minutes(15),
-                time_left - TimeDelta::minutes(30),
-                time_left - TimeDelta::minutes(60),
-            ] {
-                if time > TimeDelta::zero() {
-                    assignment::set_time_left_reminder(
-                        time,
-                        bot.clone(),
-                        dialogue.chat_id(),
-                        submission_id,
-                        pool.clone(),
-                    )
-                    .await?;
-                }
-            }
-        }
-        assignment::commands::help(bot, dialogue.clone()).await?;
+        assignment::set_time_left_reminders(
+            bot.clone(),
+            dialogue.chat_id(),
+            submission_id,
+            pool.clone(),
+        )
+        .await?;
+        assignment::commands::help(bot, dialogue.clone(), locale).await?;
 
         dialogue
             .update(BotState::Assignment(AssignmentState::AwaitingSolutions {
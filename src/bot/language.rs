@@ -0,0 +1,98 @@
+use sqlx::PgPool;
+use teloxide::{
+    Bot,
+    dispatching::UpdateFilterExt,
+    dptree::{self, Handler},
+    payloads::SendMessageSetters,
+    prelude::Requester,
+    types::{BotCommand, CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup, Message, Update},
+    utils::command::BotCommands,
+};
+
+use crate::{
+    bot::{HandlerResult, idle::IdleCommand},
+    locale::{self, Locale},
+};
+
+#[derive(BotCommands, Clone)]
+#[command(rename_rule = "snake_case")]
+pub enum LanguageCommand {
+    #[command(description = "сменить язык / change language.")]
+    Language,
+}
+
+/// `/language` is available in every dialogue state.
+pub fn language_handler()
+-> Handler<'static, HandlerResult, teloxide::dispatching::DpHandlerDescription> {
+    dptree::entry()
+        .branch(
+            Update::filter_message().branch(
+                teloxide::filter_command::<LanguageCommand, HandlerResult>()
+                    .endpoint(choose_language),
+            ),
+        )
+        .branch(
+            Update::filter_callback_query()
+                .filter(|q: CallbackQuery| {
+                    q.data
+                        .as_deref()
+                        .is_some_and(|data| data.starts_with("language:"))
+                })
+                .endpoint(set_language),
+        )
+}
+
+/// Help text for the commands `C` together with `/language`.
+pub fn command_help<C: BotCommands>(header: &str, locale: Locale) -> String {
+    locale::describe_commands(
+        header,
+        C::bot_commands()
+            .into_iter()
+            .chain(LanguageCommand::bot_commands())
+            .collect(),
+        locale,
+    )
+}
+
+/// Commands shown in the menu of the students' chats.
+pub fn student_menu(locale: Locale) -> Vec<BotCommand> {
+    locale::localize_commands(
+        IdleCommand::bot_commands()
+            .into_iter()
+            .chain(LanguageCommand::bot_commands())
+            .collect(),
+        locale,
+    )
+}
+
+async fn choose_language(bot: Bot, msg: Message, locale: Locale) -> HandlerResult {
+    let keyboard = InlineKeyboardMarkup::new(vec![vec![
+        InlineKeyboardButton::callback("Русский", "language:ru"),
+        InlineKeyboardButton::callback("English", "language:en"),
+    ]]);
+
+    bot.send_message(msg.chat.id, locale.messages().choose_language)
+        .reply_markup(keyboard)
+        .await?;
+    Ok(())
+}
+
+async fn set_language(bot: Bot, q: CallbackQuery, pool: PgPool) -> HandlerResult {
+    let Some(locale) = q
+        .data
+        .as_deref()
+        .and_then(|data| data.strip_prefix("language:"))
+        .and_then(Locale::from_code)
+    else {
+        return Ok(());
+    };
+    let Some(message) = q.regular_message() else {
+        return Ok(());
+    };
+
+    locale.save(message.chat.id, true, &pool).await?;
+
+    bot.edit_message_text(message.chat.id, message.id, locale.messages().language_set)
+        .await?;
+    Ok(())
+}
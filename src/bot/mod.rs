@@ -5,6 +5,8 @@ use teloxide::{
     types::Update,
 };
 
+use crate::locale::Locale;
+
 pub type MyDialogue = Dialogue<BotState, InMemStorage<BotState>>;
 pub type MyError = Box<dyn std::error::Error + Send + Sync>;
 pub type HandlerResult = Result<(), MyError>;
@@ -14,6 +16,14 @@ pub mod idle;
 pub mod registration;
 pub mod start;
 pub mod admin;
+pub mod profile;
+pub mod roster;
+pub mod language;
+pub mod results;
+pub mod status;
+pub mod overrides;
+pub mod compile;
+pub mod archive;
 
 #[derive(Debug, Clone, Default)]
 pub enum BotState {
@@ -28,6 +38,9 @@ pub enum BotState {
 pub fn main_handler() -> Handler<'static, HandlerResult, teloxide::dispatching::DpHandlerDescription>
 {
     dialogue::enter::<Update, InMemStorage<BotState>, BotState, _>()
+        .map_async(Locale::detect)
+        .branch(language::language_handler())
+        .branch(admin::admin_handler())
         .branch(start::start_handler())
         .branch(registration::registration_handler())
         .branch(idle::idle_handler())
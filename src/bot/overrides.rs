@@ -0,0 +1,783 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use sqlx::PgPool;
+use teloxide::{
+    Bot,
+    dispatching::dialogue::InMemStorage,
+    net::Download,
+    prelude::Requester,
+    sugar::request::RequestReplyExt,
+    types::{ChatId, Message, User},
+};
+use uuid::Uuid;
+
+use crate::{
+    bot::{
+        BotState, HandlerResult, MyDialogue, MyError, admin,
+        assignment::{self, AssignmentState},
+        status,
+    },
+    locale::{self, Locale},
+    submissions::similarity,
+};
+
+/// Admin overrides of a submission, recorded in `submission_action`.
+#[derive(Debug, Clone, Copy)]
+enum Action {
+    ForceFinish,
+    Reopen,
+    Reset,
+    DeleteItem,
+    Attach,
+}
+
+impl Action {
+    fn code(self) -> &'static str {
+        match self {
+            Action::ForceFinish => "force_finish",
+            Action::Reopen => "reopen",
+            Action::Reset => "reset",
+            Action::DeleteItem => "delete_item",
+            Action::Attach => "attach",
+        }
+    }
+
+    fn from_code(code: &str) -> Option<Action> {
+        [
+            Action::ForceFinish,
+            Action::Reopen,
+            Action::Reset,
+            Action::DeleteItem,
+            Action::Attach,
+        ]
+        .into_iter()
+        .find(|action| action.code() == code)
+    }
+
+    fn name(self, locale: Locale) -> &'static str {
+        let t = locale.messages();
+        match self {
+            Action::ForceFinish => t.action_force_finish,
+            Action::Reopen => t.action_reopen,
+            Action::Reset => t.action_reset,
+            Action::DeleteItem => t.action_delete_item,
+            Action::Attach => t.action_attach,
+        }
+    }
+}
+
+/// Submission of a course administered from the admin chat.
+struct Submission {
+    id: Uuid,
+    student_id: Uuid,
+    chat_id: ChatId,
+    full_name: String,
+    title: String,
+    group_assignment_id: Uuid,
+    finished_at: Option<DateTime<Utc>>,
+}
+
+async fn find_submission(
+    submission_id: Uuid,
+    admin_chat_id: ChatId,
+    pool: &PgPool,
+) -> Result<Option<Submission>, MyError> {
+    let courses = admin::administered_courses(admin_chat_id, pool).await?;
+    let rec = sqlx::query!(
+        r#"
+        select s.student_id, st.chat_id, st.full_name, a.title, s.group_assignment_id,
+               s.finished_at
+        from submission s
+        inner join student st on s.student_id = st.id
+        inner join group_assignment ga on s.group_assignment_id = ga.id
+        inner join assignment a on ga.assignment_id = a.id
+        where s.id = $1 and a.course_id = any($2)
+        "#,
+        submission_id,
+        &courses,
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(rec.map(|rec| Submission {
+        id: submission_id,
+        student_id: rec.student_id,
+        chat_id: ChatId(rec.chat_id),
+        full_name: rec.full_name,
+        title: rec.title,
+        group_assignment_id: rec.group_assignment_id,
+        finished_at: rec.finished_at,
+    }))
+}
+
+async fn record_action(
+    submission_id: Uuid,
+    action: Action,
+    submission_item_id: Option<Uuid>,
+    admin: &User,
+    reason: &str,
+    executor: impl sqlx::PgExecutor<'_>,
+) -> Result<(), MyError> {
+    sqlx::query!(
+        r#"
+        insert into submission_action
+            (submission_id, action, submission_item_id, admin_telegram_id, admin_name, reason)
+        values ($1, $2, $3, $4, $5, $6)
+        "#,
+        submission_id,
+        action.code(),
+        submission_item_id,
+        admin.id.0 as i64,
+        admin.full_name(),
+        reason,
+    )
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// `<id> <reason>`, the reason is required.
+fn id_and_reason(args: &str) -> Option<(Uuid, String)> {
+    let (id, reason) = args.trim().split_once(char::is_whitespace)?;
+    let reason = reason.trim();
+    if reason.is_empty() {
+        return None;
+    }
+    Some((Uuid::parse_str(id).ok()?, reason.to_string()))
+}
+
+/// Whether the student has an unfinished submission other than this one.
+/// Reopening would leave the student with two attempts in progress.
+async fn other_in_progress(submission: &Submission, pool: &PgPool) -> Result<bool, MyError> {
+    Ok(sqlx::query_scalar!(
+        r#"
+        select exists (
+            select 1 from submission
+            where student_id = $1 and id <> $2 and finished_at is null
+        ) as "exists!"
+        "#,
+        submission.student_id,
+        submission.id,
+    )
+    .fetch_one(pool)
+    .await?)
+}
+
+/// Whether the student's chat is in this submission right now.
+async fn is_current_submission(dialogue: &MyDialogue, submission_id: Uuid) -> Result<bool, MyError> {
+    Ok(matches!(
+        dialogue.get().await?,
+        Some(BotState::Assignment(
+            AssignmentState::AwaitingSolutions { submission_id: current, .. }
+            | AssignmentState::AwaitingFinish { submission_id: current, .. }
+        )) if current == submission_id
+    ))
+}
+
+/// Puts the student's chat back into the submission, as if the student had just
+/// started it.
+async fn resume_submission(
+    bot: Bot,
+    storage: Arc<InMemStorage<BotState>>,
+    submission: &Submission,
+    variant_id: Uuid,
+    pool: PgPool,
+) -> HandlerResult {
+    let dialogue = MyDialogue::new(storage, submission.chat_id);
+    let student_locale = Locale::of_chat(submission.chat_id, &pool).await;
+
+    dialogue
+        .update(BotState::Assignment(AssignmentState::AwaitingSolutions {
+            submission_id: submission.id,
+            variant_id,
+        }))
+        .await?;
+    assignment::set_time_left_reminders(bot.clone(), submission.chat_id, submission.id, pool)
+        .await?;
+    assignment::commands::help(bot, dialogue, student_locale).await?;
+    Ok(())
+}
+
+async fn reply_done(
+    bot: &Bot,
+    msg: &Message,
+    action: Action,
+    submission: &Submission,
+    locale: Locale,
+) -> HandlerResult {
+    bot.send_message(
+        msg.chat.id,
+        locale::fill(
+            locale.messages().override_done,
+            &[
+                ("action", &action.name(locale)),
+                ("name", &submission.full_name),
+                ("title", &submission.title),
+            ],
+        ),
+    )
+    .reply_to(msg.id)
+    .await?;
+    Ok(())
+}
+
+async fn reply_not_found(bot: &Bot, msg: &Message, id: Uuid, locale: Locale) -> HandlerResult {
+    bot.send_message(
+        msg.chat.id,
+        locale::fill(locale.messages().report_not_found, &[("id", &id)]),
+    )
+    .await?;
+    Ok(())
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.with_timezone(&chrono::Local)
+        .format("%Y-%m-%d %H:%M")
+        .to_string()
+}
+
+/// `/submissions <id>`: the submission with its files and the history of the
+/// overrides. `/submissions <name>`: submissions of the students with matching names.
+pub async fn show_submissions(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    let t = locale.messages();
+    let query = args.trim();
+    if query.is_empty() {
+        bot.send_message(msg.chat.id, t.submissions_usage).await?;
+        return Ok(());
+    }
+
+    let text = match Uuid::parse_str(query) {
+        Ok(submission_id) => match submission_details(submission_id, msg.chat.id, locale, &pool).await? {
+            Some(text) => text,
+            None => return reply_not_found(&bot, &msg, submission_id, locale).await,
+        },
+        Err(_) => submission_list(query, msg.chat.id, locale, &pool).await?,
+    };
+
+    bot.send_message(msg.chat.id, status::truncate_message(text))
+        .await?;
+    Ok(())
+}
+
+async fn submission_list(
+    query: &str,
+    admin_chat_id: ChatId,
+    locale: Locale,
+    pool: &PgPool,
+) -> Result<String, MyError> {
+    let t = locale.messages();
+    let courses = admin::administered_courses(admin_chat_id, pool).await?;
+
+    let submissions = sqlx::query!(
+        r#"
+        select s.id, st.full_name, g.name as "group_name", a.title, v.variant_no,
+               s.attempt_no, s.finished_at
+        from submission s
+        inner join student st on s.student_id = st.id
+        inner join "group" g on st.group_id = g.id
+        inner join variant v on s.variant_id = v.id
+        inner join group_assignment ga on s.group_assignment_id = ga.id
+        inner join assignment a on ga.assignment_id = a.id
+        where a.course_id = any($1)
+            and strpos(lower(st.full_name collate "default"), lower($2)) > 0
+        order by st.full_name, s.started_at
+        "#,
+        &courses,
+        query,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    if submissions.is_empty() {
+        return Ok(locale::fill(t.submissions_not_found, &[("query", &query)]));
+    }
+
+    Ok(submissions
+        .into_iter()
+        .map(|submission| {
+            let status = match submission.finished_at {
+                Some(finished_at) => {
+                    locale::fill(t.results_finished, &[("finished_at", &format_time(finished_at))])
+                }
+                None => t.results_in_progress.to_string(),
+            };
+            locale::fill(
+                t.submissions_item,
+                &[
+                    ("name", &submission.full_name),
+                    ("group", &submission.group_name),
+                    ("title", &submission.title),
+                    ("variant", &submission.variant_no),
+                    ("attempt", &submission.attempt_no),
+                    ("status", &status),
+                    ("id", &submission.id),
+                ],
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n"))
+}
+
+async fn submission_details(
+    submission_id: Uuid,
+    admin_chat_id: ChatId,
+    locale: Locale,
+    pool: &PgPool,
+) -> Result<Option<String>, MyError> {
+    let t = locale.messages();
+    let courses = admin::administered_courses(admin_chat_id, pool).await?;
+
+    let Some(submission) = sqlx::query!(
+        r#"
+        select st.full_name, g.name as "group_name", a.title, v.variant_no, s.attempt_no,
+               s.started_at, s.finished_at, s.reopened_until, s.score
+        from submission s
+        inner join student st on s.student_id = st.id
+        inner join "group" g on st.group_id = g.id
+        inner join variant v on s.variant_id = v.id
+        inner join group_assignment ga on s.group_assignment_id = ga.id
+        inner join assignment a on ga.assignment_id = a.id
+        where s.id = $1 and a.course_id = any($2)
+        "#,
+        submission_id,
+        &courses,
+    )
+    .fetch_optional(pool)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    let items = sqlx::query!(
+        r#"
+        select id, file_name, pages, message_id
+        from submission_item
+        where submission_id = $1
+        order by created_at
+        "#,
+        submission_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let actions = sqlx::query!(
+        r#"
+        select action, submission_item_id, admin_name, reason, created_at
+        from submission_action
+        where submission_id = $1
+        order by created_at
+        "#,
+        submission_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let status = match submission.finished_at {
+        Some(finished_at) => {
+            locale::fill(t.results_finished, &[("finished_at", &format_time(finished_at))])
+        }
+        None => t.results_in_progress.to_string(),
+    };
+    let reopened = submission.reopened_until.map_or(String::new(), |reopened_until| {
+        locale::fill(t.submission_reopened_until, &[("time", &format_time(reopened_until))])
+    });
+    let score = submission
+        .score
+        .map_or(t.results_not_graded.to_string(), |score| score.to_string());
+
+    let mut lines = vec![locale::fill(
+        t.submission_details,
+        &[
+            ("id", &submission_id),
+            ("name", &submission.full_name),
+            ("group", &submission.group_name),
+            ("title", &submission.title),
+            ("variant", &submission.variant_no),
+            ("attempt", &submission.attempt_no),
+            ("started_at", &format_time(submission.started_at)),
+            ("status", &status),
+            ("reopened", &reopened),
+            ("score", &score),
+        ],
+    )];
+
+    lines.push(String::new());
+    lines.push(locale::fill(t.submission_files, &[("count", &items.len())]));
+    for item in items {
+        lines.push(locale::fill(
+            t.submission_file,
+            &[
+                ("id", &item.id),
+                ("name", &item.file_name.unwrap_or_default()),
+                ("pages", &item.pages),
+                (
+                    "attached",
+                    &if item.message_id.is_none() { t.submission_file_attached } else { "" },
+                ),
+            ],
+        ));
+    }
+
+    if !actions.is_empty() {
+        lines.push(String::new());
+        lines.push(t.submission_history.to_string());
+        for action in actions {
+            let name = Action::from_code(&action.action).map_or(action.action.clone(), |a| {
+                match action.submission_item_id {
+                    Some(item_id) => format!("{} {item_id}", a.name(locale)),
+                    None => a.name(locale).to_string(),
+                }
+            });
+            lines.push(locale::fill(
+                t.submission_action,
+                &[
+                    ("time", &format_time(action.created_at)),
+                    ("action", &name),
+                    ("admin", &action.admin_name),
+                    ("reason", &action.reason),
+                ],
+            ));
+        }
+    }
+
+    Ok(Some(lines.join("\n")))
+}
+
+/// `/force_finish <id> <reason>`: finishes the submission as if the time were up.
+pub async fn force_finish(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    storage: Arc<InMemStorage<BotState>>,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    let t = locale.messages();
+    let (Some((submission_id, reason)), Some(admin)) = (id_and_reason(&args), msg.from.as_ref()) else {
+        bot.send_message(msg.chat.id, t.force_finish_usage).await?;
+        return Ok(());
+    };
+    let Some(submission) = find_submission(submission_id, msg.chat.id, &pool).await? else {
+        return reply_not_found(&bot, &msg, submission_id, locale).await;
+    };
+    if submission.finished_at.is_some() {
+        bot.send_message(msg.chat.id, t.override_already_finished).await?;
+        return Ok(());
+    }
+
+    record_action(submission.id, Action::ForceFinish, None, admin, &reason, &pool).await?;
+
+    let student_locale = Locale::of_chat(submission.chat_id, &pool).await;
+    bot.send_message(
+        submission.chat_id,
+        locale::fill(
+            student_locale.messages().student_force_finished,
+            &[("title", &submission.title), ("reason", &reason)],
+        ),
+    )
+    .await?;
+
+    let dialogue = MyDialogue::new(storage, submission.chat_id);
+    if is_current_submission(&dialogue, submission.id).await? {
+        assignment::finish_assignment(bot.clone(), dialogue, submission.id, student_locale, pool)
+            .await?;
+    } else {
+        sqlx::query!(
+            r#"update submission set finished_at = now() where id = $1"#,
+            submission.id
+        )
+        .execute(&pool)
+        .await?;
+    }
+
+    reply_done(&bot, &msg, Action::ForceFinish, &submission, locale).await
+}
+
+/// `/reopen <id> <minutes> <reason>`: lets the student continue a finished
+/// submission for the given number of minutes, regardless of the time limit and
+/// the deadline.
+pub async fn reopen(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    storage: Arc<InMemStorage<BotState>>,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    let t = locale.messages();
+    let parsed = args.trim().split_once(char::is_whitespace).and_then(|(id, rest)| {
+        let (minutes, reason) = rest.trim().split_once(char::is_whitespace)?;
+        Some((id_and_reason(&format!("{id} {reason}"))?, minutes.to_string()))
+    });
+    let (Some(((submission_id, reason), minutes)), Some(admin)) = (parsed, msg.from.as_ref()) else {
+        bot.send_message(msg.chat.id, t.reopen_usage).await?;
+        return Ok(());
+    };
+    let Some(minutes) = minutes.parse::<i64>().ok().filter(|minutes| *minutes > 0) else {
+        bot.send_message(
+            msg.chat.id,
+            locale::fill(t.override_invalid_minutes, &[("minutes", &minutes)]),
+        )
+        .await?;
+        return Ok(());
+    };
+    let Some(submission) = find_submission(submission_id, msg.chat.id, &pool).await? else {
+        return reply_not_found(&bot, &msg, submission_id, locale).await;
+    };
+    if submission.finished_at.is_none() {
+        bot.send_message(msg.chat.id, t.override_not_finished).await?;
+        return Ok(());
+    }
+    if other_in_progress(&submission, &pool).await? {
+        bot.send_message(
+            msg.chat.id,
+            locale::fill(t.override_other_in_progress, &[("name", &submission.full_name)]),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let variant_id = sqlx::query_scalar!(
+        r#"
+        update submission
+        set finished_at = null, reopened_until = $1
+        where id = $2
+        returning variant_id
+        "#,
+        Utc::now() + TimeDelta::minutes(minutes),
+        submission.id,
+    )
+    .fetch_one(&pool)
+    .await?;
+    record_action(submission.id, Action::Reopen, None, admin, &reason, &pool).await?;
+
+    let student_locale = Locale::of_chat(submission.chat_id, &pool).await;
+    bot.send_message(
+        submission.chat_id,
+        locale::fill(
+            student_locale.messages().student_reopened,
+            &[
+                ("title", &submission.title),
+                ("minutes", &minutes),
+                ("reason", &reason),
+            ],
+        ),
+    )
+    .await?;
+    resume_submission(bot.clone(), storage, &submission, variant_id, pool).await?;
+
+    reply_done(&bot, &msg, Action::Reopen, &submission, locale).await
+}
+
+/// `/reset <id> <reason>`: replaces the variant of the submission with a freshly
+/// generated one and restarts it. The sent files are deleted.
+pub async fn reset(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    storage: Arc<InMemStorage<BotState>>,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    let t = locale.messages();
+    let (Some((submission_id, reason)), Some(admin)) = (id_and_reason(&args), msg.from.as_ref()) else {
+        bot.send_message(msg.chat.id, t.reset_usage).await?;
+        return Ok(());
+    };
+    let Some(submission) = find_submission(submission_id, msg.chat.id, &pool).await? else {
+        return reply_not_found(&bot, &msg, submission_id, locale).await;
+    };
+    if other_in_progress(&submission, &pool).await? {
+        bot.send_message(
+            msg.chat.id,
+            locale::fill(t.override_other_in_progress, &[("name", &submission.full_name)]),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let Ok(variant_id) =
+        assignment::generate_variant(submission.group_assignment_id, pool.clone()).await
+    else {
+        bot.send_message(msg.chat.id, t.override_generation_failed).await?;
+        return Ok(());
+    };
+
+    // Past the deadline the new attempt gets its own time limit, as after /reopen,
+    // otherwise close_overdue_submissions would finish it right away.
+    let mut tx = pool.begin().await?;
+    sqlx::query!(
+        r#"
+        update submission s
+        set variant_id = $1, started_at = now(), finished_at = null,
+            reopened_until = case
+                when ga.deadline < now()
+                    then now() + coalesce(a.duration::interval, ga.deadline - s.started_at)
+            end,
+            score = null, graded_report = null
+        from group_assignment ga
+        inner join assignment a on a.id = ga.assignment_id
+        where s.id = $2 and ga.id = s.group_assignment_id
+        "#,
+        variant_id,
+        submission.id,
+    )
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query!(
+        r#"delete from submission_item where submission_id = $1"#,
+        submission.id
+    )
+    .execute(&mut *tx)
+    .await?;
+    record_action(submission.id, Action::Reset, None, admin, &reason, &mut *tx).await?;
+    tx.commit().await?;
+
+    let student_locale = Locale::of_chat(submission.chat_id, &pool).await;
+    bot.send_message(
+        submission.chat_id,
+        locale::fill(
+            student_locale.messages().student_reset,
+            &[("title", &submission.title), ("reason", &reason)],
+        ),
+    )
+    .await?;
+    assignment::send_problems(bot.clone(), submission.chat_id, variant_id, pool.clone()).await?;
+    resume_submission(bot.clone(), storage, &submission, variant_id, pool).await?;
+
+    reply_done(&bot, &msg, Action::Reset, &submission, locale).await
+}
+
+/// `/delete_item <file id> <reason>`: removes a sent file from the submission.
+pub async fn delete_item(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    let t = locale.messages();
+    let (Some((item_id, reason)), Some(admin)) = (id_and_reason(&args), msg.from.as_ref()) else {
+        bot.send_message(msg.chat.id, t.delete_item_usage).await?;
+        return Ok(());
+    };
+
+    let item = sqlx::query!(
+        r#"select submission_id, file_name from submission_item where id = $1"#,
+        item_id
+    )
+    .fetch_optional(&pool)
+    .await?;
+    let submission = match &item {
+        Some(item) => find_submission(item.submission_id, msg.chat.id, &pool).await?,
+        None => None,
+    };
+    let (Some(item), Some(submission)) = (item, submission) else {
+        bot.send_message(
+            msg.chat.id,
+            locale::fill(t.override_item_not_found, &[("id", &item_id)]),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    sqlx::query!(r#"delete from submission_item where id = $1"#, item_id)
+        .execute(&pool)
+        .await?;
+    record_action(submission.id, Action::DeleteItem, Some(item_id), admin, &reason, &pool).await?;
+
+    let student_locale = Locale::of_chat(submission.chat_id, &pool).await;
+    bot.send_message(
+        submission.chat_id,
+        locale::fill(
+            student_locale.messages().student_item_deleted,
+            &[
+                ("file", &item.file_name.unwrap_or_else(|| item_id.to_string())),
+                ("title", &submission.title),
+                ("reason", &reason),
+            ],
+        ),
+    )
+    .await?;
+
+    reply_done(&bot, &msg, Action::DeleteItem, &submission, locale).await
+}
+
+/// `/attach <id> <reason>` sent as the caption of a file: adds the file to the
+/// submission on the student's behalf.
+pub async fn attach(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    let t = locale.messages();
+    let (Some((submission_id, reason)), Some(admin), Some(document)) =
+        (id_and_reason(&args), msg.from.as_ref(), msg.document())
+    else {
+        bot.send_message(msg.chat.id, t.attach_usage).await?;
+        return Ok(());
+    };
+    let Some(submission) = find_submission(submission_id, msg.chat.id, &pool).await? else {
+        return reply_not_found(&bot, &msg, submission_id, locale).await;
+    };
+
+    let mime = document
+        .mime_type
+        .as_ref()
+        .map(|m| m.to_string())
+        .unwrap_or("bin".into());
+    let file_name = document.file_name.clone();
+    let file = bot.get_file(document.file.id.clone()).await?;
+    let mut data: Vec<u8> = Vec::new();
+    bot.download_file(&file.path, &mut data).await?;
+
+    let pages = match mime.as_str() {
+        "application/pdf" => lopdf::Document::load_mem(&data)?.get_pages().len(),
+        _ => 1,
+    } as i32;
+    let hashes = similarity::hash_item(&data, &mime);
+
+    let item_id = sqlx::query_scalar!(
+        r#"
+        insert into submission_item
+            (submission_id, message_id, data, pages, extension, file_name, content_hash, page_hashes)
+        values ($1, null, $2, $3, $4, $5, $6, $7)
+        returning id
+        "#,
+        submission.id,
+        data,
+        pages,
+        mime,
+        file_name,
+        hashes.content_hash,
+        &hashes.page_hashes as &[Option<i64>],
+    )
+    .fetch_one(&pool)
+    .await?;
+    record_action(submission.id, Action::Attach, Some(item_id), admin, &reason, &pool).await?;
+
+    let student_locale = Locale::of_chat(submission.chat_id, &pool).await;
+    bot.send_message(
+        submission.chat_id,
+        locale::fill(
+            student_locale.messages().student_item_attached,
+            &[
+                ("file", &file_name.unwrap_or_else(|| item_id.to_string())),
+                ("title", &submission.title),
+                ("reason", &reason),
+            ],
+        ),
+    )
+    .await?;
+
+    reply_done(&bot, &msg, Action::Attach, &submission, locale).await
+}
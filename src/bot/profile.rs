@@ -0,0 +1,446 @@
+use chrono::Utc;
+use sqlx::PgPool;
+use teloxide::{
+    Bot,
+    dispatching::dialogue::GetChatId,
+    payloads::{AnswerCallbackQuerySetters, SendMessageSetters},
+    prelude::Requester,
+    types::{CallbackQuery, ChatId, InlineKeyboardButton, InlineKeyboardMarkup, Message},
+};
+use uuid::Uuid;
+
+use crate::{
+    bot::{
+        BotState, HandlerResult, MyDialogue, MyError, admin, idle::IdleState, registration,
+        roster,
+    },
+    locale::{self, Locale},
+};
+
+pub async fn show_profile(
+    bot: Bot,
+    dialogue: MyDialogue,
+    student_id: Uuid,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    let t = locale.messages();
+    let rec = sqlx::query!(
+        r#"
+        select st.full_name, g.name as "group_name", c.title as "course_title", st.created_at
+        from student st
+        inner join "group" g on st.group_id = g.id
+        inner join course c on g.course_id = c.id
+        where st.id = $1
+        "#,
+        student_id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    let pending = sqlx::query!(
+        r#"
+        select pcr.full_name, g.name as "group_name?"
+        from profile_change_request pcr
+        left join "group" g on pcr.group_id = g.id
+        where pcr.student_id = $1 and pcr.status = 'pending'
+        "#,
+        student_id
+    )
+    .fetch_optional(&pool)
+    .await?;
+
+    let mut text = locale::fill(
+        t.profile,
+        &[
+            ("course", &rec.course_title),
+            ("name", &rec.full_name),
+            ("group", &rec.group_name),
+            (
+                "date",
+                &rec.created_at
+                    .with_timezone(&chrono::Local)
+                    .format(t.date_format),
+            ),
+        ],
+    );
+
+    if let Some(pending) = pending {
+        text.push_str(t.profile_pending);
+        if let Some(full_name) = pending.full_name {
+            text.push_str(&locale::fill(t.profile_pending_name, &[("name", &full_name)]));
+        }
+        if let Some(group_name) = pending.group_name {
+            text.push_str(&locale::fill(t.profile_pending_group, &[("group", &group_name)]));
+        }
+    }
+
+    bot.send_message(dialogue.chat_id(), text).await?;
+    Ok(())
+}
+
+async fn has_pending_request(student_id: Uuid, pool: &PgPool) -> Result<bool, MyError> {
+    Ok(sqlx::query_scalar!(
+        r#"
+        select exists (
+            select 1 from profile_change_request
+            where student_id = $1 and status = 'pending'
+        ) as "exists!"
+        "#,
+        student_id
+    )
+    .fetch_one(pool)
+    .await?)
+}
+
+pub async fn change_name(
+    bot: Bot,
+    dialogue: MyDialogue,
+    student_id: Uuid,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    let t = locale.messages();
+    if has_pending_request(student_id, &pool).await? {
+        bot.send_message(dialogue.chat_id(), t.profile_request_pending)
+            .await?;
+        return Ok(());
+    }
+
+    bot.send_message(dialogue.chat_id(), t.enter_new_name)
+        .await?;
+    dialogue
+        .update(BotState::Idle(IdleState::AwaitingNewName { student_id }))
+        .await?;
+    Ok(())
+}
+
+pub async fn awaiting_new_name(
+    bot: Bot,
+    dialogue: MyDialogue,
+    msg: Message,
+    student_id: Uuid,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    let t = locale.messages();
+    let Some(full_name) = msg.text().map(str::trim).filter(|name| !name.is_empty()) else {
+        bot.send_message(msg.chat.id, t.send_full_name).await?;
+        return Ok(());
+    };
+
+    if full_name.starts_with('/') {
+        bot.send_message(msg.chat.id, t.name_change_cancelled)
+            .await?;
+        dialogue
+            .update(BotState::Idle(IdleState::AwaitingCommand { student_id }))
+            .await?;
+        return Ok(());
+    }
+
+    let has_submissions = sqlx::query_scalar!(
+        r#"select exists (select 1 from submission where student_id = $1) as "exists!""#,
+        student_id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    let group_id = sqlx::query_scalar!(
+        r#"select group_id from student where id = $1"#,
+        student_id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    if has_submissions || roster::group_has_roster(group_id, &pool).await? {
+        let request_id = sqlx::query_scalar!(
+            r#"
+            insert into profile_change_request (student_id, full_name)
+            values ($1, $2)
+            returning id
+            "#,
+            student_id,
+            full_name,
+        )
+        .fetch_one(&pool)
+        .await?;
+
+        send_request_to_admin(bot.clone(), request_id, pool.clone()).await?;
+
+        bot.send_message(msg.chat.id, t.name_change_requested)
+            .await?;
+    } else {
+        sqlx::query!(
+            r#"update student set full_name = $1 where id = $2"#,
+            full_name,
+            student_id
+        )
+        .execute(&pool)
+        .await?;
+
+        bot.send_message(
+            msg.chat.id,
+            locale::fill(t.name_changed, &[("name", &full_name)]),
+        )
+        .await?;
+    }
+
+    dialogue
+        .update(BotState::Idle(IdleState::AwaitingCommand { student_id }))
+        .await?;
+    Ok(())
+}
+
+pub async fn change_group(
+    bot: Bot,
+    dialogue: MyDialogue,
+    student_id: Uuid,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    if has_pending_request(student_id, &pool).await? {
+        bot.send_message(dialogue.chat_id(), locale.messages().profile_request_pending)
+            .await?;
+        return Ok(());
+    }
+
+    let course_id = sqlx::query_scalar!(
+        r#"
+        select g.course_id
+        from student st
+        inner join "group" g on st.group_id = g.id
+        where st.id = $1
+        "#,
+        student_id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    registration::request_group(bot, dialogue.chat_id(), course_id, locale, pool).await?;
+    dialogue
+        .update(BotState::Idle(IdleState::AwaitingNewGroup { student_id }))
+        .await?;
+    Ok(())
+}
+
+pub async fn awaiting_new_group(
+    bot: Bot,
+    dialogue: MyDialogue,
+    q: CallbackQuery,
+    student_id: Uuid,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    let t = locale.messages();
+    let Some(chat_id) = q.chat_id() else {
+        return Ok(());
+    };
+    let current = sqlx::query!(
+        r#"
+        select st.group_id, g.course_id
+        from student st
+        inner join "group" g on st.group_id = g.id
+        where st.id = $1
+        "#,
+        student_id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    let group = registration::group_by_callback(&q, current.course_id, &pool).await?;
+
+    let text = match group {
+        None => t.invalid_group.to_string(),
+        Some((group_id, group_name)) if group_id == current.group_id => {
+            locale::fill(t.already_in_group, &[("group", &group_name)])
+        }
+        Some((group_id, group_name)) => {
+            let request_id = sqlx::query_scalar!(
+                r#"
+                insert into profile_change_request (student_id, group_id)
+                values ($1, $2)
+                returning id
+                "#,
+                student_id,
+                group_id,
+            )
+            .fetch_one(&pool)
+            .await?;
+
+            send_request_to_admin(bot.clone(), request_id, pool.clone()).await?;
+
+            locale::fill(t.group_change_requested, &[("group", &group_name)])
+        }
+    };
+
+    if let Some(message) = q.regular_message() {
+        bot.edit_message_text(message.chat.id, message.id, text)
+            .await?;
+    } else {
+        bot.send_message(chat_id, text).await?;
+    }
+
+    dialogue
+        .update(BotState::Idle(IdleState::AwaitingCommand { student_id }))
+        .await?;
+    Ok(())
+}
+
+async fn send_request_to_admin(bot: Bot, request_id: Uuid, pool: PgPool) -> HandlerResult {
+    let rec = sqlx::query!(
+        r#"
+        select st.full_name as "current_name",
+               g.name as "current_group",
+               g.id as "current_group_id",
+               pcr.full_name as "new_name",
+               ng.name as "new_group?"
+        from profile_change_request pcr
+        inner join student st on pcr.student_id = st.id
+        inner join "group" g on st.group_id = g.id
+        left join "group" ng on pcr.group_id = ng.id
+        where pcr.id = $1
+        "#,
+        request_id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    let admin_chat_id = admin::group_admin_chat_id(rec.current_group_id, &pool).await?;
+    let t = Locale::of_chat(admin_chat_id, &pool).await.messages();
+
+    let mut text = locale::fill(
+        t.admin_profile_request,
+        &[("name", &rec.current_name), ("group", &rec.current_group)],
+    );
+    if let Some(new_name) = rec.new_name {
+        text.push_str(&locale::fill(t.admin_profile_request_name, &[("name", &new_name)]));
+    }
+    if let Some(new_group) = rec.new_group {
+        text.push_str(&locale::fill(t.admin_profile_request_group, &[("group", &new_group)]));
+    }
+
+    let keyboard = InlineKeyboardMarkup::new(vec![vec![
+        InlineKeyboardButton::callback(t.accept, format!("profile:accept:{request_id}")),
+        InlineKeyboardButton::callback(t.reject, format!("profile:reject:{request_id}")),
+    ]]);
+
+    bot.send_message(admin_chat_id, text)
+        .reply_markup(keyboard)
+        .await?;
+    Ok(())
+}
+
+/// Handles the accept/reject buttons attached by `send_request_to_admin`.
+pub async fn resolve_request(
+    bot: Bot,
+    q: CallbackQuery,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    let t = locale.messages();
+    let Some((accept, request_id)) = q.data.as_deref().and_then(|data| {
+        let (action, id) = data.strip_prefix("profile:")?.split_once(':')?;
+        Some((action == "accept", Uuid::parse_str(id).ok()?))
+    }) else {
+        bot.answer_callback_query(q.id.clone()).await?;
+        return Ok(());
+    };
+
+    let Some(rec) = sqlx::query!(
+        r#"
+        update profile_change_request
+        set status = $1, resolved_by = $2, resolved_at = $3
+        where id = $4 and status = 'pending'
+        returning student_id, full_name, group_id
+        "#,
+        if accept { "accepted" } else { "rejected" },
+        q.from.id.0 as i64,
+        Utc::now(),
+        request_id,
+    )
+    .fetch_optional(&pool)
+    .await?
+    else {
+        bot.answer_callback_query(q.id.clone())
+            .text(t.request_already_resolved)
+            .await?;
+        return Ok(());
+    };
+    bot.answer_callback_query(q.id.clone()).await?;
+
+    if accept {
+        sqlx::query!(
+            r#"
+            update student
+            set full_name = coalesce($1, full_name),
+                group_id = coalesce($2, group_id)
+            where id = $3
+            "#,
+            rec.full_name,
+            rec.group_id,
+            rec.student_id,
+        )
+        .execute(&pool)
+        .await?;
+
+        if let Some(group_id) = rec.group_id {
+            // the roster entry of the old group is released, and the one of the new
+            // group with exactly the same name is claimed if it is still free
+            sqlx::query!(
+                r#"update roster_entry set student_id = null where student_id = $1"#,
+                rec.student_id
+            )
+            .execute(&pool)
+            .await?;
+
+            sqlx::query!(
+                r#"
+                update roster_entry re
+                set student_id = st.id
+                from student st
+                where st.id = $1
+                    and re.group_id = $2
+                    and re.full_name = st.full_name
+                    and re.student_id is null
+                "#,
+                rec.student_id,
+                group_id,
+            )
+            .execute(&pool)
+            .await?;
+        }
+    }
+
+    if let Some(message) = q.regular_message() {
+        let text = format!(
+            "{}\n\n{} ({})",
+            message.text().unwrap_or_default(),
+            if accept { t.accepted } else { t.rejected },
+            q.from.full_name(),
+        );
+        bot.edit_message_text(message.chat.id, message.id, text)
+            .await?;
+    }
+
+    let student_chat_id = sqlx::query_scalar!(
+        r#"select chat_id from student where id = $1"#,
+        rec.student_id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    let student_t = Locale::of_chat(ChatId(student_chat_id), &pool)
+        .await
+        .messages();
+    bot.send_message(
+        ChatId(student_chat_id),
+        if accept {
+            student_t.profile_change_accepted
+        } else {
+            student_t.profile_change_rejected
+        },
+    )
+    .await?;
+
+    Ok(())
+}
@@ -1,28 +1,40 @@
+use std::sync::Arc;
+
 use sqlx::{PgPool};
 use teloxide::{
     Bot,
-    dispatching::{UpdateFilterExt, dialogue::GetChatId},
+    dispatching::{UpdateFilterExt, dialogue::{GetChatId, InMemStorage}},
     dptree,
     prelude::*,
-    types::{CallbackQuery, Message, Update},
+    types::{CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup, Message, Update},
 };
 use uuid::Uuid;
 
 use crate::{
     bot::{
-        BotState, HandlerResult, MyDialogue,
+        BotState, HandlerResult, MyDialogue, MyError, admin,
         idle::{self, IdleState},
+        roster::{self, RosterMatch},
     },
     db::helpers::current_academic_year,
+    locale::{self, Locale},
 };
 
 #[derive(Debug, Clone, Default)]
 pub enum RegistrationState {
     #[default]
-    AwaitingGroup,
+    AwaitingCourse,
+    AwaitingGroup {
+        course_id: Uuid,
+    },
     AwaitingFullName {
         group_id: Uuid,
     },
+    AwaitingRosterChoice {
+        group_id: Uuid,
+        full_name: String,
+    },
+    AwaitingApproval,
 }
 
 pub fn registration_handler()
@@ -31,7 +43,18 @@ pub fn registration_handler()
         .branch(
             Update::filter_callback_query()
                 // .enter_dialogue::<CallbackQuery, InMemStorage<BotState>, BotState>()
-                .branch(dptree::case![RegistrationState::AwaitingGroup].endpoint(awaiting_group)),
+                .branch(dptree::case![RegistrationState::AwaitingCourse].endpoint(awaiting_course))
+                .branch(
+                    dptree::case![RegistrationState::AwaitingGroup { course_id }]
+                        .endpoint(awaiting_group),
+                )
+                .branch(
+                    dptree::case![RegistrationState::AwaitingRosterChoice {
+                        group_id,
+                        full_name
+                    }]
+                    .endpoint(awaiting_roster_choice),
+                ),
         )
         .branch(
             Update::filter_message()
@@ -39,21 +62,181 @@ pub fn registration_handler()
                 .branch(
                     dptree::case![RegistrationState::AwaitingFullName { group_id }]
                         .endpoint(awaiting_full_name),
+                )
+                .branch(
+                    dptree::case![RegistrationState::AwaitingApproval].endpoint(awaiting_approval),
                 ),
         )
 }
 
-pub async fn request_group(bot: Bot, msg: Message, pool: PgPool) -> HandlerResult {
-    fn group_button(group_name: &str) -> teloxide::types::InlineKeyboardButton {
-        teloxide::types::InlineKeyboardButton::callback::<&str, &str>(group_name, group_name)
+/// Offers the courses with groups in the current academic year that the account is
+/// not enrolled in yet. With a single such course, goes straight to choosing a group.
+/// Returns `false` if there is nothing to enroll in.
+pub async fn start_registration(
+    bot: Bot,
+    dialogue: MyDialogue,
+    telegram_id: i64,
+    locale: Locale,
+    pool: PgPool,
+) -> Result<bool, MyError> {
+    let t = locale.messages();
+    let courses = sqlx::query!(
+        r#"
+        select c.id, c.title, c.instructor
+        from course c
+        where exists (
+            select 1 from "group" g
+            where g.course_id = c.id and g.academic_year = $1
+        )
+        and not exists (
+            select 1 from student st
+            inner join "group" g on st.group_id = g.id
+            where g.course_id = c.id and st.telegram_id = $2
+        )
+        order by c.title
+        "#,
+        current_academic_year(),
+        telegram_id,
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    match courses.as_slice() {
+        [] => {
+            bot.send_message(dialogue.chat_id(), t.no_courses).await?;
+            Ok(false)
+        }
+        [course] => {
+            send_course_info(
+                bot.clone(),
+                dialogue.chat_id(),
+                &course.title,
+                &course.instructor,
+                locale,
+            )
+            .await?;
+            request_group(bot, dialogue.chat_id(), course.id, locale, pool).await?;
+            dialogue
+                .update(BotState::Registration(RegistrationState::AwaitingGroup {
+                    course_id: course.id,
+                }))
+                .await?;
+            Ok(true)
+        }
+        courses => {
+            let keyboard = InlineKeyboardMarkup::new(
+                courses
+                    .iter()
+                    .map(|course| {
+                        vec![InlineKeyboardButton::callback(
+                            course.title.clone(),
+                            course.id.to_string(),
+                        )]
+                    })
+                    .collect::<Vec<_>>(),
+            );
+            bot.send_message(dialogue.chat_id(), t.choose_course)
+                .reply_markup(keyboard)
+                .await?;
+            dialogue
+                .update(BotState::Registration(RegistrationState::AwaitingCourse))
+                .await?;
+            Ok(true)
+        }
+    }
+}
+
+async fn send_course_info(
+    bot: Bot,
+    chat_id: ChatId,
+    title: &str,
+    instructor: &str,
+    locale: Locale,
+) -> HandlerResult {
+    let t = locale.messages();
+    let text = if instructor.is_empty() {
+        locale::fill(t.course_info, &[("title", &title)])
+    } else {
+        locale::fill(
+            t.course_info_with_instructor,
+            &[("title", &title), ("instructor", &instructor)],
+        )
+    };
+    bot.send_message(chat_id, text)
+        .parse_mode(teloxide::types::ParseMode::Html)
+        .await?;
+    Ok(())
+}
+
+async fn awaiting_course(
+    bot: Bot,
+    dialogue: MyDialogue,
+    q: CallbackQuery,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    let course_id = q.data.as_deref().and_then(|data| Uuid::parse_str(data).ok());
+    let course = match course_id {
+        Some(course_id) => {
+            sqlx::query!(
+                r#"select id, title, instructor from course where id = $1"#,
+                course_id
+            )
+            .fetch_optional(&pool)
+            .await?
+        }
+        None => None,
+    };
+
+    if let Some(message) = q.regular_message() {
+        bot.edit_message_reply_markup(message.chat.id, message.id)
+            .await?;
+    }
+
+    let Some(course) = course else {
+        bot.send_message(dialogue.chat_id(), locale.messages().invalid_course)
+            .await?;
+        dialogue.update(BotState::Start).await?;
+        return Ok(());
+    };
+
+    send_course_info(
+        bot.clone(),
+        dialogue.chat_id(),
+        &course.title,
+        &course.instructor,
+        locale,
+    )
+    .await?;
+    request_group(bot, dialogue.chat_id(), course.id, locale, pool).await?;
+    dialogue
+        .update(BotState::Registration(RegistrationState::AwaitingGroup {
+            course_id: course.id,
+        }))
+        .await?;
+
+    Ok(())
+}
+
+pub async fn request_group(
+    bot: Bot,
+    chat_id: ChatId,
+    course_id: Uuid,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    fn group_button(group_id: Uuid, group_name: &str) -> teloxide::types::InlineKeyboardButton {
+        teloxide::types::InlineKeyboardButton::callback(group_name, group_id.to_string())
     }
 
-    let groups = sqlx::query_scalar!(
+    let groups = sqlx::query!(
         r#"
-            select name from "group"
-            where academic_year = ($1)
+            select id, name from "group"
+            where academic_year = ($1) and course_id = ($2)
+            order by name
         "#,
-        current_academic_year()
+        current_academic_year(),
+        course_id,
     )
     .fetch_all(&pool)
     .await?;
@@ -61,47 +244,63 @@ pub async fn request_group(bot: Bot, msg: Message, pool: PgPool) -> HandlerResul
     let group_keyboard = teloxide::types::InlineKeyboardMarkup::new(
         groups
             .into_iter()
-            .map(|group| vec![group_button(group.as_str())])
+            .map(|group| vec![group_button(group.id, group.name.as_str())])
             .collect::<Vec<_>>(),
     );
-    bot.send_message(msg.chat.id, "Выбирите группу:")
+    bot.send_message(chat_id, locale.messages().choose_group)
         .reply_markup(group_keyboard)
         .await?;
 
     Ok(())
 }
 
+/// Group of the course in the current academic year by the id sent with a group button.
+pub async fn group_by_callback(
+    q: &CallbackQuery,
+    course_id: Uuid,
+    pool: &PgPool,
+) -> Result<Option<(Uuid, String)>, MyError> {
+    let Some(group_id) = q.data.as_deref().and_then(|data| Uuid::parse_str(data).ok()) else {
+        return Ok(None);
+    };
+
+    Ok(sqlx::query!(
+        r#"
+            select id, name from "group"
+            where id = ($1) and course_id = ($2) and academic_year = ($3);
+        "#,
+        group_id,
+        course_id,
+        current_academic_year(),
+    )
+    .fetch_optional(pool)
+    .await?
+    .map(|rec| (rec.id, rec.name)))
+}
+
 pub async fn awaiting_group(
     bot: Bot,
     dialogue: MyDialogue,
     q: CallbackQuery,
+    course_id: Uuid,
+    locale: Locale,
     pool: PgPool,
 ) -> HandlerResult {
+    let t = locale.messages();
     log::debug!("Callback query: {q:?}");
-    if let Some(ref group_name) = q.data {
-        log::info!("Выбранная группа: {group_name}");
-
-        let group_id = sqlx::query_scalar!(
-            r#"
-                select id from "group"
-                where name = ($1) and academic_year = ($2);
-            "#,
-            group_name,
-            current_academic_year(),
-        )
-        .fetch_optional(&pool)
-        .await?;
+    if q.data.is_some() {
+        if let Some((group_id, group_name)) = group_by_callback(&q, course_id, &pool).await? {
+            log::info!("Выбранная группа: {group_name}");
 
-        if let Some(group_id) = group_id {
             let Some(chat_id) = q.chat_id() else {
                 dialogue.update(BotState::Start).await?;
                 return Ok(());
             };
-            let text = format!("Выбранная группа: {group_name}");
+            let text = locale::fill(t.chosen_group, &[("group", &group_name)]);
 
             bot.send_message(chat_id, text).await?;
 
-            bot.send_message(chat_id, "Введите ваше ФИО:").await?;
+            bot.send_message(chat_id, t.enter_full_name).await?;
 
             dialogue
                 .update(BotState::Registration(
@@ -109,7 +308,7 @@ pub async fn awaiting_group(
                 ))
                 .await?;
         } else {
-            let text = format!("Выбрана некорректная группа.");
+            let text = t.invalid_group;
             if let Some(message) = q.regular_message() {
                 bot.edit_message_text(message.chat.id, message.id, text)
                     .await?;
@@ -128,25 +327,164 @@ async fn awaiting_full_name(
     dialogue: MyDialogue,
     msg: Message,
     group_id: Uuid,
+    locale: Locale,
     pool: PgPool,
 ) -> HandlerResult {
-    let Some(full_name) = msg.text() else {
-        bot.send_message(msg.chat.id, "Пожалуйста, пришлите своё ФИО:")
-            .await?;
+    let t = locale.messages();
+    let Some(full_name) = msg.text().map(str::trim).filter(|name| !name.is_empty()) else {
+        bot.send_message(msg.chat.id, t.send_full_name).await?;
         return Ok(());
     };
     let Some(user) = msg.clone().from else {
-        bot.send_message(msg.chat.id, "Сообщения из каналов не поддерживаются.")
+        bot.send_message(msg.chat.id, t.channels_not_supported)
             .await?;
         return Ok(());
     };
     let telegram_id = user.id.0 as i64;
-    let chat_id = msg.chat.id.0 as i64;
 
-    let student_id = sqlx::query_scalar!(
+    if !roster::group_has_roster(group_id, &pool).await? {
+        register(bot, dialogue, telegram_id, group_id, full_name, None, locale, pool).await?;
+        return Ok(());
+    }
+
+    match roster::find_match(group_id, full_name, &pool).await? {
+        RosterMatch::Exact {
+            roster_entry_id,
+            full_name,
+        } => {
+            register(
+                bot,
+                dialogue,
+                telegram_id,
+                group_id,
+                &full_name,
+                Some(roster_entry_id),
+                locale,
+                pool,
+            )
+            .await?;
+        }
+        RosterMatch::Claimed => {
+            bot.send_message(msg.chat.id, t.name_claimed).await?;
+            request_approval(bot, dialogue, telegram_id, group_id, full_name, locale, pool)
+                .await?;
+        }
+        RosterMatch::Similar(candidates) if candidates.is_empty() => {
+            request_approval(bot, dialogue, telegram_id, group_id, full_name, locale, pool)
+                .await?;
+        }
+        RosterMatch::Similar(candidates) => {
+            let mut buttons = candidates
+                .into_iter()
+                .map(|(id, name)| vec![InlineKeyboardButton::callback(name, format!("roster:{id}"))])
+                .collect::<Vec<_>>();
+            buttons.push(vec![InlineKeyboardButton::callback(
+                t.not_in_roster,
+                "roster:none",
+            )]);
+
+            bot.send_message(msg.chat.id, t.did_you_mean)
+                    .reply_markup(InlineKeyboardMarkup::new(buttons))
+                .await?;
+
+            dialogue
+                .update(BotState::Registration(
+                    RegistrationState::AwaitingRosterChoice {
+                        group_id,
+                        full_name: full_name.to_string(),
+                    },
+                ))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn awaiting_roster_choice(
+    bot: Bot,
+    dialogue: MyDialogue,
+    q: CallbackQuery,
+    (group_id, full_name): (Uuid, String),
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    let telegram_id = q.from.id.0 as i64;
+    let choice = q.data.as_deref().and_then(|data| data.strip_prefix("roster:"));
+
+    if let Some(message) = q.regular_message() {
+        bot.edit_message_reply_markup(message.chat.id, message.id)
+            .await?;
+    }
+
+    match choice.map(Uuid::parse_str) {
+        Some(Ok(roster_entry_id)) => {
+            let Some(roster_name) = sqlx::query_scalar!(
+                r#"
+                select full_name from roster_entry
+                where id = $1 and group_id = $2 and student_id is null
+                "#,
+                roster_entry_id,
+                group_id,
+            )
+            .fetch_optional(&pool)
+            .await?
+            else {
+                bot.send_message(dialogue.chat_id(), locale.messages().roster_entry_taken)
+                    .await?;
+                dialogue
+                    .update(BotState::Registration(
+                        RegistrationState::AwaitingFullName { group_id },
+                    ))
+                    .await?;
+                return Ok(());
+            };
+
+            register(
+                bot,
+                dialogue,
+                telegram_id,
+                group_id,
+                &roster_name,
+                Some(roster_entry_id),
+                locale,
+                pool,
+            )
+            .await?;
+        }
+        _ => {
+            request_approval(bot, dialogue, telegram_id, group_id, &full_name, locale, pool)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn awaiting_approval(bot: Bot, msg: Message, locale: Locale) -> HandlerResult {
+    bot.send_message(msg.chat.id, locale.messages().registration_pending)
+        .await?;
+    Ok(())
+}
+
+/// Inserts the student and, if given, claims the roster entry in the same transaction.
+/// Returns `None` if the entry has been claimed in the meantime or the account is
+/// already registered in the group.
+async fn insert_student(
+    telegram_id: i64,
+    chat_id: i64,
+    group_id: Uuid,
+    full_name: &str,
+    roster_entry_id: Option<Uuid>,
+    pool: &PgPool,
+) -> Result<Option<Uuid>, MyError> {
+    let mut tx = pool.begin().await?;
+
+    let Some(student_id) = sqlx::query_scalar!(
         r#"
         insert into student (group_id, telegram_id, chat_id, full_name)
         values ($1, $2, $3, $4)
+        on conflict do nothing
         returning id
         "#,
         group_id,
@@ -154,13 +492,61 @@ async fn awaiting_full_name(
         chat_id,
         full_name,
     )
-    .fetch_one(&pool)
-    .await?;
+    .fetch_optional(&mut *tx)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    if let Some(roster_entry_id) = roster_entry_id {
+        let claimed = sqlx::query!(
+            r#"
+            update roster_entry set student_id = $1
+            where id = $2 and student_id is null
+            "#,
+            student_id,
+            roster_entry_id,
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        if claimed == 0 {
+            return Ok(None);
+        }
+    }
+
+    tx.commit().await?;
+    Ok(Some(student_id))
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn register(
+    bot: Bot,
+    dialogue: MyDialogue,
+    telegram_id: i64,
+    group_id: Uuid,
+    full_name: &str,
+    roster_entry_id: Option<Uuid>,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    let t = locale.messages();
+    let chat_id = dialogue.chat_id().0;
+
+    let Some(student_id) =
+        insert_student(telegram_id, chat_id, group_id, full_name, roster_entry_id, &pool).await?
+    else {
+        bot.send_message(dialogue.chat_id(), t.registration_failed)
+            .await?;
+        dialogue.update(BotState::Start).await?;
+        return Ok(());
+    };
 
-    bot.send_message(msg.chat.id, "Регистрация пройдена!")
+    bot.send_message(dialogue.chat_id(), t.registration_done)
         .await?;
 
-    idle::help(bot, dialogue.clone(), student_id, pool).await?;
+    idle::help(bot, dialogue.clone(), student_id, locale, pool).await?;
     dialogue
         .update(BotState::Idle(IdleState::AwaitingCommand {
             student_id: student_id,
@@ -169,3 +555,176 @@ async fn awaiting_full_name(
 
     Ok(())
 }
+
+async fn request_approval(
+    bot: Bot,
+    dialogue: MyDialogue,
+    telegram_id: i64,
+    group_id: Uuid,
+    full_name: &str,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    let rec = sqlx::query!(
+        r#"
+        with request as (
+            insert into registration_request (group_id, telegram_id, chat_id, full_name)
+            values ($1, $2, $3, $4)
+            returning id, group_id
+        )
+        select request.id as "id!", g.name as "group_name!"
+        from request
+        inner join "group" g on request.group_id = g.id
+        "#,
+        group_id,
+        telegram_id,
+        dialogue.chat_id().0,
+        full_name,
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    let admin_chat_id = admin::group_admin_chat_id(group_id, &pool).await?;
+    let admin_t = Locale::of_chat(admin_chat_id, &pool).await.messages();
+
+    let keyboard = InlineKeyboardMarkup::new(vec![vec![
+        InlineKeyboardButton::callback(admin_t.accept, format!("registration:accept:{}", rec.id)),
+        InlineKeyboardButton::callback(admin_t.reject, format!("registration:reject:{}", rec.id)),
+    ]]);
+    bot.send_message(
+        admin_chat_id,
+        locale::fill(
+            admin_t.admin_registration_request,
+            &[("name", &full_name), ("group", &rec.group_name)],
+        ),
+    )
+    .reply_markup(keyboard)
+    .await?;
+
+    bot.send_message(dialogue.chat_id(), locale.messages().registration_request_sent)
+        .await?;
+
+    dialogue
+        .update(BotState::Registration(RegistrationState::AwaitingApproval))
+        .await?;
+    Ok(())
+}
+
+/// Handles the accept/reject buttons attached by `request_approval`.
+pub async fn resolve_request(
+    bot: Bot,
+    q: CallbackQuery,
+    storage: Arc<InMemStorage<BotState>>,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    let t = locale.messages();
+    let Some((accept, request_id)) = q.data.as_deref().and_then(|data| {
+        let (action, id) = data.strip_prefix("registration:")?.split_once(':')?;
+        Some((action == "accept", Uuid::parse_str(id).ok()?))
+    }) else {
+        bot.answer_callback_query(q.id.clone()).await?;
+        return Ok(());
+    };
+
+    let Some(rec) = sqlx::query!(
+        r#"
+        update registration_request
+        set status = $1, resolved_by = $2, resolved_at = now()
+        where id = $3 and status = 'pending'
+        returning group_id, telegram_id, chat_id, full_name
+        "#,
+        if accept { "accepted" } else { "rejected" },
+        q.from.id.0 as i64,
+        request_id,
+    )
+    .fetch_optional(&pool)
+    .await?
+    else {
+        bot.answer_callback_query(q.id.clone())
+            .text(t.request_already_resolved)
+            .await?;
+        return Ok(());
+    };
+    bot.answer_callback_query(q.id.clone()).await?;
+
+    let dialogue = MyDialogue::new(storage, ChatId(rec.chat_id));
+    let student_locale = Locale::of_chat(dialogue.chat_id(), &pool).await;
+    let student_t = student_locale.messages();
+
+    let student_id = if accept {
+        let student_id = insert_student(
+            rec.telegram_id,
+            rec.chat_id,
+            rec.group_id,
+            &rec.full_name,
+            None,
+            &pool,
+        )
+        .await?;
+
+        if let Some(student_id) = student_id {
+            sqlx::query!(
+                r#"
+                insert into roster_entry (group_id, full_name, student_id)
+                values ($1, $2, $3)
+                on conflict do nothing
+                "#,
+                rec.group_id,
+                rec.full_name,
+                student_id,
+            )
+            .execute(&pool)
+            .await?;
+        }
+        student_id
+    } else {
+        None
+    };
+
+    if let Some(message) = q.regular_message() {
+        let text = format!(
+            "{}\n\n{} ({})",
+            message.text().unwrap_or_default(),
+            if accept { t.accepted } else { t.rejected },
+            q.from.full_name(),
+        );
+        bot.edit_message_text(message.chat.id, message.id, text)
+            .await?;
+    }
+
+    match student_id {
+        Some(student_id) => {
+            bot.send_message(dialogue.chat_id(), student_t.registration_accepted)
+                .await?;
+            idle::help(bot, dialogue.clone(), student_id, student_locale, pool).await?;
+            dialogue
+                .update(BotState::Idle(IdleState::AwaitingCommand { student_id }))
+                .await?;
+        }
+        None if accept => {
+            // the account has been registered in the group since the request was sent
+            bot.send_message(dialogue.chat_id(), student_t.registration_already_done)
+                .await?;
+            dialogue.update(BotState::Start).await?;
+
+            let admin_chat_id = admin::group_admin_chat_id(rec.group_id, &pool).await?;
+            let admin_t = Locale::of_chat(admin_chat_id, &pool).await.messages();
+            bot.send_message(
+                admin_chat_id,
+                locale::fill(
+                    admin_t.admin_registration_not_applied,
+                    &[("name", &rec.full_name)],
+                ),
+            )
+            .await?;
+        }
+        None => {
+            bot.send_message(dialogue.chat_id(), student_t.registration_rejected)
+                .await?;
+            dialogue.update(BotState::Start).await?;
+        }
+    }
+
+    Ok(())
+}
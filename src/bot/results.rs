@@ -0,0 +1,344 @@
+use sqlx::PgPool;
+use teloxide::{
+    Bot,
+    dispatching::dialogue::GetChatId,
+    net::Download,
+    payloads::{AnswerCallbackQuerySetters, SendMessageSetters},
+    prelude::Requester,
+    sugar::request::RequestReplyExt,
+    types::{
+        CallbackQuery, ChatId, InlineKeyboardButton, InlineKeyboardMarkup, InputFile, Message,
+    },
+};
+use uuid::Uuid;
+
+use crate::{
+    bot::{HandlerResult, MyDialogue, admin, assignment},
+    locale::{self, Locale},
+};
+
+/// `/my_results`: every submission of the student with buttons to download its
+/// problems, the submitted files, the released solutions and the graded report.
+pub async fn show_results(
+    bot: Bot,
+    dialogue: MyDialogue,
+    student_id: Uuid,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    let t = locale.messages();
+
+    let submissions = sqlx::query!(
+        r#"
+        select s.id, a.title, v.variant_no, s.attempt_no, ga.max_attempts,
+               s.started_at, s.finished_at, s.score,
+               s.graded_report is not null as "has_report!",
+               s.solutions_released_at is not null as "solutions_released!",
+               exists (
+                   select 1 from counted_submission cs where cs.submission_id = s.id
+               ) as "counted!"
+        from submission s
+        inner join variant v on s.variant_id = v.id
+        inner join group_assignment ga on s.group_assignment_id = ga.id
+        inner join assignment a on ga.assignment_id = a.id
+        where s.student_id = $1
+        order by s.started_at
+        "#,
+        student_id
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    if submissions.is_empty() {
+        bot.send_message(dialogue.chat_id(), t.results_empty).await?;
+        return Ok(());
+    }
+
+    let format_time = |time: chrono::DateTime<chrono::Utc>| {
+        time.with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    };
+
+    let mut items = vec![];
+    let mut keyboard = vec![];
+    for (i, submission) in submissions.into_iter().enumerate() {
+        let n = i + 1;
+
+        let attempt = if submission.max_attempts > 1 {
+            format!(
+                "{}{}",
+                locale::fill(t.results_attempt, &[("attempt", &submission.attempt_no)]),
+                if submission.counted { t.results_counted } else { "" },
+            )
+        } else {
+            "".to_string()
+        };
+        let status = match submission.finished_at {
+            Some(finished_at) => {
+                locale::fill(t.results_finished, &[("finished_at", &format_time(finished_at))])
+            }
+            None => t.results_in_progress.to_string(),
+        };
+        let score = submission
+            .score
+            .map_or(t.results_not_graded.to_string(), |score| score.to_string());
+
+        items.push(locale::fill(
+            t.results_item,
+            &[
+                ("n", &n),
+                ("title", &submission.title),
+                ("variant", &submission.variant_no),
+                ("attempt", &attempt),
+                ("started_at", &format_time(submission.started_at)),
+                ("status", &status),
+                ("score", &score),
+            ],
+        ));
+
+        let mut row = vec![
+            InlineKeyboardButton::callback(
+                locale::fill(t.results_problems, &[("n", &n)]),
+                format!("results:problems:{}", submission.id),
+            ),
+            InlineKeyboardButton::callback(
+                locale::fill(t.results_files, &[("n", &n)]),
+                format!("results:files:{}", submission.id),
+            ),
+        ];
+        if submission.solutions_released {
+            row.push(InlineKeyboardButton::callback(
+                locale::fill(t.results_solutions, &[("n", &n)]),
+                format!("results:solutions:{}", submission.id),
+            ));
+        }
+        if submission.has_report {
+            row.push(InlineKeyboardButton::callback(
+                locale::fill(t.results_report, &[("n", &n)]),
+                format!("results:report:{}", submission.id),
+            ));
+        }
+        keyboard.push(row);
+    }
+
+    bot.send_message(
+        dialogue.chat_id(),
+        format!("{}\n\n{}", t.results_header, items.join("\n\n")),
+    )
+    .reply_markup(InlineKeyboardMarkup::new(keyboard))
+    .await?;
+
+    Ok(())
+}
+
+/// Handles the buttons attached by `show_results`.
+pub async fn send_item(
+    bot: Bot,
+    q: CallbackQuery,
+    student_id: Uuid,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    let t = locale.messages();
+    let Some(chat_id) = q.chat_id() else {
+        return Ok(());
+    };
+    let Some((item, submission_id)) = q.data.as_deref().and_then(|data| {
+        let (item, id) = data.strip_prefix("results:")?.split_once(':')?;
+        Some((item.to_string(), Uuid::parse_str(id).ok()?))
+    }) else {
+        return Ok(());
+    };
+
+    let Some(submission) = sqlx::query!(
+        r#"
+        select s.variant_id, s.graded_report, v.variant_no,
+               s.solutions_released_at is not null as "solutions_released!"
+        from submission s
+        inner join variant v on s.variant_id = v.id
+        where s.id = $1 and s.student_id = $2
+        "#,
+        submission_id,
+        student_id,
+    )
+    .fetch_optional(&pool)
+    .await?
+    else {
+        bot.answer_callback_query(q.id.clone())
+            .text(t.results_not_found)
+            .await?;
+        return Ok(());
+    };
+
+    bot.answer_callback_query(q.id.clone()).await?;
+
+    match item.as_str() {
+        "problems" => {
+            assignment::send_problems(bot, chat_id, submission.variant_id, pool).await?;
+        }
+        "files" => {
+            let files = sqlx::query!(
+                r#"
+                select data, extension, file_name
+                from submission_item
+                where submission_id = $1
+                order by created_at
+                "#,
+                submission_id
+            )
+            .fetch_all(&pool)
+            .await?;
+
+            if files.is_empty() {
+                bot.send_message(chat_id, t.results_no_files).await?;
+            }
+            for (i, file) in files.into_iter().enumerate() {
+                let file_name = file
+                    .file_name
+                    .unwrap_or_else(|| format!("solution_{}.{}", i + 1, extension(&file.extension)));
+                bot.send_document(chat_id, InputFile::memory(file.data).file_name(file_name))
+                    .await?;
+            }
+        }
+        "solutions" if submission.solutions_released => {
+            assignment::send_solutions(bot, chat_id, submission.variant_id, pool).await?;
+        }
+        "report" => match submission.graded_report {
+            Some(report) => {
+                bot.send_document(
+                    chat_id,
+                    InputFile::memory(report)
+                        .file_name(format!("report_{}.pdf", submission.variant_no)),
+                )
+                .await?;
+            }
+            None => {
+                bot.send_message(chat_id, t.results_no_report).await?;
+            }
+        },
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// File extension for the MIME type stored in `submission_item.extension`.
+fn extension(mime: &str) -> &str {
+    match mime {
+        "application/pdf" => "pdf",
+        "image/jpeg" => "jpg",
+        "image/png" => "png",
+        "image/heic" => "heic",
+        _ => "bin",
+    }
+}
+
+/// `/upload_report <submission id> [score]` sent as the caption of the reviewed
+/// PDF. The report and the score are shown to the student in `/my_results`.
+pub async fn upload_report(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    let t = locale.messages();
+    let mut args = args.split_whitespace();
+
+    let (Some(submission_id), Some(document)) = (
+        args.next().and_then(|id| Uuid::parse_str(id).ok()),
+        msg.document(),
+    ) else {
+        bot.send_message(msg.chat.id, t.report_usage).await?;
+        return Ok(());
+    };
+
+    let score = match args.next() {
+        None => None,
+        Some(score) => match score.replace(',', ".").parse::<f32>() {
+            Ok(score) => Some(score),
+            Err(_) => {
+                bot.send_message(
+                    msg.chat.id,
+                    locale::fill(t.report_invalid_score, &[("score", &score)]),
+                )
+                .await?;
+                return Ok(());
+            }
+        },
+    };
+
+    let courses = admin::administered_courses(msg.chat.id, &pool).await?;
+    let Some(submission) = sqlx::query!(
+        r#"
+        select st.full_name, st.chat_id, a.title
+        from submission s
+        inner join student st on s.student_id = st.id
+        inner join group_assignment ga on s.group_assignment_id = ga.id
+        inner join assignment a on ga.assignment_id = a.id
+        where s.id = $1 and a.course_id = any($2)
+        "#,
+        submission_id,
+        &courses,
+    )
+    .fetch_optional(&pool)
+    .await?
+    else {
+        bot.send_message(
+            msg.chat.id,
+            locale::fill(t.report_not_found, &[("id", &submission_id)]),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let file = bot.get_file(document.file.id.clone()).await?;
+    let mut data: Vec<u8> = Vec::new();
+    bot.download_file(&file.path, &mut data).await?;
+
+    let score = sqlx::query_scalar!(
+        r#"
+        update submission
+        set graded_report = $1, score = coalesce($2, score)
+        where id = $3
+        returning score
+        "#,
+        data,
+        score,
+        submission_id,
+    )
+    .fetch_one(&pool)
+    .await?;
+    let format_score = |not_graded: &str| score.map_or(not_graded.to_string(), |score| score.to_string());
+
+    bot.send_message(
+        msg.chat.id,
+        locale::fill(
+            t.report_uploaded,
+            &[
+                ("name", &submission.full_name),
+                ("title", &submission.title),
+                ("score", &format_score(t.results_not_graded)),
+            ],
+        ),
+    )
+    .reply_to(msg.id)
+    .await?;
+
+    let student_chat_id = ChatId(submission.chat_id);
+    let student_t = Locale::of_chat(student_chat_id, &pool).await.messages();
+    bot.send_message(
+        student_chat_id,
+        locale::fill(
+            student_t.report_available,
+            &[
+                ("title", &submission.title),
+                ("score", &format_score(student_t.results_not_graded)),
+            ],
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
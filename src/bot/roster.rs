@@ -0,0 +1,242 @@
+use std::collections::HashSet;
+
+use sqlx::PgPool;
+use teloxide::{Bot, net::Download, prelude::Requester, types::Message};
+use uuid::Uuid;
+
+use crate::{
+    bot::{HandlerResult, MyError, admin},
+    db::helpers::current_academic_year,
+    locale::{self, Locale},
+};
+
+/// Roster entries with a similarity below this are not offered during registration.
+const MIN_SIMILARITY: f64 = 0.75;
+const MAX_CANDIDATES: usize = 3;
+
+/// Lowercased, `ё` replaced with `е`, whitespace collapsed.
+pub fn normalize_name(name: &str) -> String {
+    name.to_lowercase()
+        .replace('ё', "е")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Similarity in `[0, 1]`, insensitive to the order of the name parts
+/// ("Иванов Иван" and "Иван Иванов" are the same name).
+pub fn name_similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (normalize_name(a), normalize_name(b));
+    let sorted = |s: &str| {
+        let mut words = s.split(' ').collect::<Vec<_>>();
+        words.sort();
+        words.join(" ")
+    };
+
+    strsim::jaro_winkler(&a, &b).max(strsim::jaro_winkler(&sorted(&a), &sorted(&b)))
+}
+
+pub async fn group_has_roster(group_id: Uuid, pool: &PgPool) -> Result<bool, MyError> {
+    Ok(sqlx::query_scalar!(
+        r#"select exists (select 1 from roster_entry where group_id = $1) as "exists!""#,
+        group_id
+    )
+    .fetch_one(pool)
+    .await?)
+}
+
+pub enum RosterMatch {
+    /// Unclaimed entry with exactly this name (up to normalization).
+    Exact { roster_entry_id: Uuid, full_name: String },
+    /// The name is on the roster, but someone has already claimed it.
+    Claimed,
+    /// Unclaimed entries with similar names, most similar first.
+    Similar(Vec<(Uuid, String)>),
+}
+
+pub async fn find_match(group_id: Uuid, full_name: &str, pool: &PgPool) -> Result<RosterMatch, MyError> {
+    let entries = sqlx::query!(
+        r#"select id, full_name, student_id from roster_entry where group_id = $1"#,
+        group_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let normalized = normalize_name(full_name);
+    if let Some(entry) = entries
+        .iter()
+        .find(|entry| normalize_name(&entry.full_name) == normalized)
+    {
+        return Ok(match entry.student_id {
+            None => RosterMatch::Exact {
+                roster_entry_id: entry.id,
+                full_name: entry.full_name.clone(),
+            },
+            Some(_) => RosterMatch::Claimed,
+        });
+    }
+
+    let mut similar = entries
+        .into_iter()
+        .filter(|entry| entry.student_id.is_none())
+        .map(|entry| (name_similarity(&entry.full_name, full_name), entry.id, entry.full_name))
+        .filter(|(similarity, _, _)| *similarity >= MIN_SIMILARITY)
+        .collect::<Vec<_>>();
+    similar.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    Ok(RosterMatch::Similar(
+        similar
+            .into_iter()
+            .take(MAX_CANDIDATES)
+            .map(|(_, id, name)| (id, name))
+            .collect(),
+    ))
+}
+
+/// Reads names from the first column of a CSV file. A header row named
+/// `full_name` or `ФИО` is skipped, as are empty rows.
+fn parse_roster_csv(data: &[u8]) -> Result<Vec<String>, MyError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(data);
+
+    let mut names = vec![];
+    let mut seen = HashSet::new();
+    for (i, record) in reader.records().enumerate() {
+        let record = record?;
+        let Some(name) = record.get(0).map(|name| name.split_whitespace().collect::<Vec<_>>().join(" ")) else {
+            continue;
+        };
+        if name.is_empty() || (i == 0 && ["full_name", "фио"].contains(&normalize_name(&name).as_str())) {
+            continue;
+        }
+        if seen.insert(normalize_name(&name)) {
+            names.push(name);
+        }
+    }
+    Ok(names)
+}
+
+/// `/import_roster <group>` sent as the caption of a CSV document. The uploaded list
+/// replaces the group's roster, except for the entries already claimed by students.
+/// Registered students whose names are on the list claim their entries right away.
+pub async fn import_roster(
+    bot: Bot,
+    msg: Message,
+    group_name: String,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    let t = locale.messages();
+    let group_name = group_name.trim();
+    let courses = admin::administered_courses(msg.chat.id, &pool).await?;
+    let Some(group_id) = sqlx::query_scalar!(
+        r#"
+            select id from "group"
+            where name = ($1) and academic_year = ($2) and course_id = any($3);
+        "#,
+        group_name,
+        current_academic_year(),
+        &courses,
+    )
+    .fetch_optional(&pool)
+    .await?
+    else {
+        bot.send_message(
+            msg.chat.id,
+            locale::fill(t.group_not_found, &[("group", &group_name)]),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let Some(document) = msg.document() else {
+        bot.send_message(msg.chat.id, t.roster_send_csv).await?;
+        return Ok(());
+    };
+
+    let file = bot.get_file(document.file.id.clone()).await?;
+    let mut data: Vec<u8> = Vec::new();
+    bot.download_file(&file.path, &mut data).await?;
+
+    let names = match parse_roster_csv(&data) {
+        Ok(names) if !names.is_empty() => names,
+        Ok(_) => {
+            bot.send_message(msg.chat.id, t.roster_empty).await?;
+            return Ok(());
+        }
+        Err(err) => {
+            bot.send_message(
+                msg.chat.id,
+                locale::fill(t.roster_csv_error, &[("error", &err)]),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let mut tx = pool.begin().await?;
+
+    let removed = sqlx::query!(
+        r#"
+        delete from roster_entry
+        where group_id = $1 and student_id is null and not (full_name = any($2))
+        "#,
+        group_id,
+        &names,
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    let added = sqlx::query!(
+        r#"
+        insert into roster_entry (group_id, full_name)
+        select $1, unnest($2::text[])
+        on conflict (group_id, full_name) do nothing
+        "#,
+        group_id,
+        &names,
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    let claimed = sqlx::query!(
+        r#"
+        update roster_entry re
+        set student_id = st.id
+        from student st
+        where st.group_id = re.group_id
+            and st.full_name = re.full_name
+            and re.group_id = $1
+            and re.student_id is null
+            and not exists (select 1 from roster_entry r where r.student_id = st.id)
+        "#,
+        group_id,
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    tx.commit().await?;
+
+    bot.send_message(
+        msg.chat.id,
+        locale::fill(
+            t.roster_imported,
+            &[
+                ("group", &group_name),
+                ("count", &names.len()),
+                ("added", &added),
+                ("removed", &removed),
+                ("claimed", &claimed),
+            ],
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
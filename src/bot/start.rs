@@ -1,11 +1,15 @@
 use sqlx::PgPool;
-use teloxide::{Bot, prelude::*, types::Message, utils::command::BotCommands};
+use teloxide::{Bot, prelude::*, types::Message};
 
-use crate::bot::{
-    BotState, HandlerResult, MyDialogue,
-    admin::AdminState,
-    idle::{IdleCommand, IdleState},
-    registration::{self, RegistrationState},
+use crate::{
+    bot::{
+        BotState, HandlerResult, MyDialogue,
+        admin::{self, AdminCommand, AdminState},
+        idle::{IdleCommand, IdleState},
+        language,
+        registration::{self, RegistrationState},
+    },
+    locale::{self, Locale},
 };
 
 pub fn start_handler()
@@ -13,27 +17,39 @@ pub fn start_handler()
     dptree::case![BotState::Start].branch(Update::filter_message().endpoint(start))
 }
 
-pub async fn start(bot: Bot, dialogue: MyDialogue, msg: Message, pool: PgPool) -> HandlerResult {
+pub async fn start(
+    bot: Bot,
+    dialogue: MyDialogue,
+    msg: Message,
+    locale: Locale,
+    pool: PgPool,
+) -> HandlerResult {
+    let t = locale.messages();
     let Some(user) = msg.clone().from else {
-        bot.send_message(
-            dialogue.chat_id(),
-            "Сообщения из каналов не поддерживаются.",
-        )
-        .await?;
+        bot.send_message(dialogue.chat_id(), t.channels_not_supported)
+            .await?;
         return Ok(());
     };
     let telegram_id = user.id.0 as i64;
 
-    let admin_chat_id = ChatId(std::env::var("ADMIN_CHAT_ID")?.parse()?);
-    let is_admin = admin_chat_id == dialogue.chat_id();
+    // the detected locale is remembered for the messages sent outside of updates
+    locale.save(dialogue.chat_id(), false, &pool).await?;
+
+    let is_admin = !admin::administered_courses(dialogue.chat_id(), &pool)
+        .await?
+        .is_empty();
 
     log::debug!("is admin? {:?}", is_admin);
     log::debug!("chat_id = {}", dialogue.chat_id());
     //  5062133349
     // -5062133349
     if is_admin {
-        bot.send_message(dialogue.chat_id(), "Запуск с привелегиями админа")
-            .await?;
+        bot.send_message(dialogue.chat_id(), t.admin_start).await?;
+        bot.send_message(
+            dialogue.chat_id(),
+            language::command_help::<AdminCommand>(t.admin_commands_header, locale),
+        )
+        .await?;
 
         dialogue
             .update(BotState::Admin(AdminState::AwaitingCommand))
@@ -41,40 +57,71 @@ pub async fn start(bot: Bot, dialogue: MyDialogue, msg: Message, pool: PgPool) -
         return Ok(());
     }
 
-    let maybe_student = sqlx::query!(
+    // an account enrolled in several courses continues with the latest one,
+    // the others are available via /courses
+    let students = sqlx::query!(
         r#"
-        select id, group_id, telegram_id, full_name, created_at, updated_at 
+        select id, full_name
         from student
         where telegram_id = $1
+        order by created_at desc
         "#,
         telegram_id
     )
-    .fetch_optional(&pool)
+    .fetch_all(&pool)
     .await?;
+    let maybe_student = students.first();
 
-    match maybe_student {
-        None => {
-            bot.send_message(
-                dialogue.chat_id(),
-                include_str!("long_messages/start_greeting.txt"),
-            )
-            .parse_mode(teloxide::types::ParseMode::Html)
-            .await?;
+    let has_pending_registration = sqlx::query_scalar!(
+        r#"
+        select exists (
+            select 1 from registration_request
+            where telegram_id = $1 and status = 'pending'
+        ) as "exists!"
+        "#,
+        telegram_id
+    )
+    .fetch_one(&pool)
+    .await?;
 
-            registration::request_group(bot.clone(), msg.clone(), pool.clone()).await?;
+    match maybe_student {
+        None if has_pending_registration => {
+            bot.send_message(dialogue.chat_id(), t.registration_pending)
+                .await?;
 
             dialogue
-                .update(BotState::Registration(RegistrationState::AwaitingGroup))
+                .update(BotState::Registration(RegistrationState::AwaitingApproval))
                 .await?;
         }
+        None => {
+            bot.send_message(dialogue.chat_id(), t.start_greeting)
+                .parse_mode(teloxide::types::ParseMode::Html)
+                .await?;
+
+            registration::start_registration(
+                bot.clone(),
+                dialogue.clone(),
+                telegram_id,
+                locale,
+                pool.clone(),
+            )
+            .await?;
+        }
         Some(student) => {
             bot.send_message(
                 dialogue.chat_id(),
-                format!("{}, здравствуйте!", student.full_name),
+                locale::fill(t.hello, &[("name", &student.full_name)]),
             )
             .await?;
-            bot.send_message(dialogue.chat_id(), IdleCommand::descriptions().to_string())
-                .await?;
+            bot.send_message(
+                dialogue.chat_id(),
+                language::command_help::<IdleCommand>(t.student_commands_header, locale),
+            )
+            .await?;
+            if students.len() > 1 {
+                bot.send_message(dialogue.chat_id(), t.several_courses)
+                    .await?;
+            }
             dialogue
                 .update(BotState::Idle(IdleState::AwaitingCommand {
                     student_id: student.id,
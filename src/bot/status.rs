@@ -0,0 +1,159 @@
+use sqlx::PgPool;
+use teloxide::{
+    ApiError, Bot, RequestError,
+    payloads::{EditMessageTextSetters, SendMessageSetters},
+    prelude::Requester,
+    types::{CallbackQuery, ChatId, InlineKeyboardButton, InlineKeyboardMarkup, Message},
+};
+
+use crate::{
+    bot::{HandlerResult, MyError, admin, assignment},
+    locale::{self, Locale},
+};
+
+/// Telegram's limit on the length of a message.
+const MAX_MESSAGE_LENGTH: usize = 4096;
+
+fn refresh_keyboard(locale: Locale) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+        locale.messages().status_refresh,
+        "status:refresh",
+    )]])
+}
+
+/// `/status`: progress of the open group assignments of the courses administered
+/// from the chat. The message is updated in place with the refresh button.
+pub async fn show_status(bot: Bot, msg: Message, locale: Locale, pool: PgPool) -> HandlerResult {
+    let text = status_text(msg.chat.id, locale, &pool).await?;
+    bot.send_message(msg.chat.id, text)
+        .reply_markup(refresh_keyboard(locale))
+        .await?;
+    Ok(())
+}
+
+/// Handles the refresh button attached by `show_status`.
+pub async fn refresh(bot: Bot, q: CallbackQuery, locale: Locale, pool: PgPool) -> HandlerResult {
+    bot.answer_callback_query(q.id.clone()).await?;
+    let Some(message) = q.regular_message() else {
+        return Ok(());
+    };
+
+    let text = status_text(message.chat.id, locale, &pool).await?;
+    match bot
+        .edit_message_text(message.chat.id, message.id, text)
+        .reply_markup(refresh_keyboard(locale))
+        .await
+    {
+        // pressed twice within the same second
+        Err(RequestError::Api(ApiError::MessageNotModified)) => Ok(()),
+        result => result.map(|_| ()).map_err(Into::into),
+    }
+}
+
+async fn status_text(chat_id: ChatId, locale: Locale, pool: &PgPool) -> Result<String, MyError> {
+    let t = locale.messages();
+    let courses = admin::administered_courses(chat_id, pool).await?;
+
+    let group_assignments = sqlx::query!(
+        r#"
+        select ga.id, ga.group_id, a.title, g.name as "group_name"
+        from group_assignment ga
+        inner join assignment a on ga.assignment_id = a.id
+        inner join "group" g on ga.group_id = g.id
+        where a.course_id = any($1)
+            and ga.available_at <= now()
+            and not ga.completed
+            and ga.compiled_at is null
+        order by a.title, g.name
+        "#,
+        &courses,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut sections = vec![locale::fill(
+        t.status_header,
+        &[("time", &chrono::Local::now().format("%H:%M:%S"))],
+    )];
+
+    if group_assignments.is_empty() {
+        sections.push(t.status_no_open.to_string());
+    }
+
+    for group_assignment in group_assignments {
+        // the latest attempt of every student of the group
+        let students = sqlx::query!(
+            r#"
+            select st.full_name, s.id as "submission_id?", s.finished_at,
+                   (select count(*) from submission_item si where si.submission_id = s.id) as "items!"
+            from student st
+            left join lateral (
+                select id, finished_at from submission
+                where student_id = st.id and group_assignment_id = $1
+                order by attempt_no desc
+                limit 1
+            ) s on true
+            where st.group_id = $2
+            order by st.full_name
+            "#,
+            group_assignment.id,
+            group_assignment.group_id,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut not_started = vec![];
+        let mut in_progress = vec![];
+        let mut finished = vec![];
+        for student in students {
+            match (student.submission_id, student.finished_at) {
+                (None, _) => not_started.push(student.full_name),
+                (Some(_), Some(_)) => finished.push(student.full_name),
+                (Some(submission_id), None) => {
+                    let time_left = assignment::time_left(submission_id, pool.clone()).await?;
+                    in_progress.push(locale::fill(
+                        t.status_in_progress_item,
+                        &[
+                            ("name", &student.full_name),
+                            ("time_left", &assignment::minutes_left_text(time_left, locale)),
+                            ("items", &student.items),
+                        ],
+                    ));
+                }
+            }
+        }
+
+        let mut section = vec![
+            locale::fill(
+                t.status_assignment,
+                &[
+                    ("title", &group_assignment.title),
+                    ("group", &group_assignment.group_name),
+                ],
+            ),
+            locale::fill(
+                t.status_not_started,
+                &[("count", &not_started.len()), ("names", &not_started.join(", "))],
+            ),
+            locale::fill(t.status_in_progress, &[("count", &in_progress.len())]),
+        ];
+        section.extend(in_progress);
+        section.push(locale::fill(
+            t.status_finished,
+            &[("count", &finished.len()), ("names", &finished.join(", "))],
+        ));
+
+        sections.push(section.join("\n"));
+    }
+
+    Ok(truncate_message(sections.join("\n\n")))
+}
+
+/// Cuts the text to fit into a single message.
+pub fn truncate_message(text: String) -> String {
+    if text.chars().count() > MAX_MESSAGE_LENGTH {
+        text.chars().take(MAX_MESSAGE_LENGTH - 1).collect::<String>() + "…"
+    } else {
+        text
+    }
+}
@@ -0,0 +1,279 @@
+use super::{Messages, PdfLabels};
+
+pub static EN: Messages = Messages {
+    commands: &[
+        ("help", "show this message."),
+        ("show_assignments", "show available assignments."),
+        ("my_results", "show the results of your assignments."),
+        ("me", "show your profile."),
+        ("change_name", "correct your full name."),
+        ("change_group", "change your group (approved by the instructor)."),
+        ("courses", "switch to another course or enroll in a new one."),
+        ("language", "change language / сменить язык."),
+        ("finish", "finish the assignment."),
+        ("go_back", "leave this assignment without finishing it."),
+        ("get_problems", "show the problems of the assignment."),
+        ("get_rules", "show the rules for sending solutions."),
+        ("get_solutions", "show the solutions sent so far."),
+        ("show_time_left", "how much time is left?"),
+        (
+            "import_roster",
+            "upload a group roster: a CSV file (full names in the first column) with the caption /import_roster <group>.",
+        ),
+        (
+            "upload_report",
+            "upload a reviewed submission: a PDF with the caption /upload_report <submission id> [score].",
+        ),
+        ("status", "show the progress of the open assignments."),
+        ("compile", "compile the solutions sent so far, before the deadline."),
+        ("submissions", "find submissions: /submissions <full name or submission id>."),
+        ("force_finish", "finish a submission: /force_finish <submission id> <reason>."),
+        (
+            "reopen",
+            "reopen a finished submission: /reopen <submission id> <minutes> <reason>.",
+        ),
+        (
+            "reset",
+            "restart a submission with a new variant: /reset <submission id> <reason>.",
+        ),
+        ("delete_item", "delete a file from a submission: /delete_item <file id> <reason>."),
+        (
+            "attach",
+            "attach a file to a submission: a file with the caption /attach <submission id> <reason>.",
+        ),
+        ("export", "download an assignment of a group with all submissions as a zip archive."),
+        (
+            "import_archive",
+            "restore an assignment from an archive: a zip file with the caption /import_archive, or /import_archive <file> for an archive on the server.",
+        ),
+    ],
+    student_commands_header: "Available commands:",
+    admin_commands_header: "Instructor commands:",
+
+    start_greeting: include_str!("en/start_greeting.txt"),
+    submission_rules: include_str!("en/submission_rules.txt"),
+
+    channels_not_supported: "Messages from channels are not supported.",
+    unknown_command: "Error: unknown command.",
+    accept: "Accept",
+    reject: "Reject",
+    accepted: "Accepted",
+    rejected: "Rejected",
+    request_already_resolved: "The request has already been resolved.",
+    send_full_name: "Please send your full name:",
+    invalid_group: "Invalid group selected.",
+    choose_group: "Choose your group:",
+    date_format: "%Y-%m-%d",
+
+    choose_language: "Выберите язык / Choose language:",
+    language_set: "Interface language: English.",
+
+    admin_start: "Started with admin privileges",
+    hello: "Hello, {{name}}!",
+    several_courses: "You are enrolled in several courses, use /courses to switch between them.",
+    registration_pending: "Your registration is awaiting approval by the instructor.",
+
+    no_courses: "There are no courses open for enrollment right now.",
+    choose_course: "Choose a course:",
+    course_info: "Course: <b>{{title}}</b>",
+    course_info_with_instructor: "Course: <b>{{title}}</b> (instructor: {{instructor}})",
+    invalid_course: "Invalid course selected.",
+    chosen_group: "Selected group: {{group}}",
+    enter_full_name: "Enter your full name:",
+    name_claimed: "A student with this name has already registered from another account.",
+    not_in_roster: "My name is not on the list",
+    did_you_mean: "This name is not on the group roster. Did you mean:",
+    roster_entry_taken: "This name has already been taken, please enter your full name again:",
+    registration_failed: "Registration failed: this name has already been taken, or you are already registered in this group.",
+    registration_done: "Registration complete!",
+    admin_registration_request: "Registration request for a name not on the group roster.\nFull name: {{name}}\nGroup: {{group}}",
+    registration_request_sent: "Your name is not on the group roster, so your registration request has been sent to the instructor. You will be notified once it is reviewed.",
+    registration_accepted: "The instructor has approved your registration!",
+    registration_rejected: "Your registration request has been rejected. To try again, send /start.",
+    registration_already_done: "The instructor has approved your registration, but this account is already registered in the group. Send /start to continue.",
+    admin_registration_not_applied: "The registration of {{name}} was not applied: this account is already registered in the group.",
+
+    assignment_until: " until {{deadline}}",
+    assignment_no_deadline: " (no deadline)",
+    assignment_attempt: " [attempt {{attempt}} of {{max_attempts}}]",
+    no_assignments: "There are no available assignments right now.",
+    choose_assignment: "Choose an assignment:",
+    deadline_not_set: "(not set)",
+    duration_until_deadline: "(until the deadline)",
+    duration_format: "%H hours %M minutes",
+    attempts_info: "Attempt {{attempt}} of {{max_attempts}}. Every attempt gets a new variant.\n\n",
+    assignment_info: "{{title}}: \n\n{{description}}\n\nTime limit: {{time}}\n\nDeadline: {{deadline}}.\n\n{{attempts}}{{rules}}",
+    start_assignment: "Start",
+    back: "Back",
+    can_start_now: "You can start right now:",
+    assignment_unavailable: "Error: the selected assignment is not available.",
+    generating_variant: "Generating your variant...",
+    generation_failed: "Failed to generate the problems. Please contact the instructor.",
+    problems: "Problems: ",
+    your_courses: "Your courses:",
+    enroll: "Enroll in another course",
+    current_course: "Current course: {{title}}",
+
+    finish: "Finish",
+    continue_assignment: "No, continue",
+    confirm_finish: "Finish the assignment?",
+    go_back_hint: "To continue the assignment, just open it again.",
+    no_solutions_yet: "No files with solutions have been sent yet.",
+    time_left_unknown: "(time left is unknown)",
+    minutes_left: "{{minutes}} min. left",
+    minutes_overdue: "overdue by {{minutes}} min.",
+    time_left: "Time left: {{time}}",
+    already_finished: "Time is up, the assignment has already been finished.",
+    unsupported_message_kind: "This kind of message is not supported (try regular messages).",
+    unknown_command_try_help: "Unknown command, try /help.",
+    send_photos_as_files: "Please send photos as uncompressed files (see /get_rules)",
+    unsupported_message: "This kind of message is not supported",
+    assignment_finished: "The assignment is finished!",
+    time_is_up: "Time is up, the assignment is finished.",
+
+    results_empty: "You have not done any assignments yet.",
+    results_header: "Your results:",
+    results_item: "{{n}}. {{title}}, variant {{variant}}{{attempt}}\nStarted: {{started_at}}, {{status}}\nScore: {{score}}",
+    results_attempt: ", attempt {{attempt}}",
+    results_counted: " (counted)",
+    results_finished: "finished: {{finished_at}}",
+    results_in_progress: "in progress",
+    results_not_graded: "not graded",
+    results_problems: "{{n}}: problems",
+    results_files: "{{n}}: solutions",
+    results_solutions: "{{n}}: answer key",
+    results_report: "{{n}}: graded work",
+    results_not_found: "Submission not found.",
+    results_no_files: "No solution files were sent.",
+    results_no_report: "The graded work has not been uploaded yet.",
+    report_usage: "Send the PDF of the reviewed submission with the caption /upload_report <submission id> [score]. The submission id is given in the compiled solutions.",
+    report_invalid_score: "Invalid score: {{score}}",
+    report_not_found: "Submission {{id}} not found.",
+    report_uploaded: "Graded work uploaded: {{name}}, «{{title}}». Score: {{score}}.",
+    report_available: "Your work on «{{title}}» has been graded. Score: {{score}}. The graded work can be downloaded in /my_results.",
+
+    profile: "Course: {{course}}\nFull name: {{name}}\nGroup: {{group}}\nRegistered on: {{date}}",
+    profile_pending: "\n\nAwaiting approval by the instructor:",
+    profile_pending_name: "\n- new full name: {{name}}",
+    profile_pending_group: "\n- new group: {{group}}",
+    profile_request_pending: "Your previous profile change request has not been reviewed by the instructor yet.",
+    enter_new_name: "Enter your corrected full name:",
+    name_change_cancelled: "Name change cancelled.",
+    name_change_requested: "Once you have started an assignment, or if the group has a roster, your name can only be changed with the instructor's approval. The request has been sent.",
+    name_changed: "Full name changed: {{name}}",
+    already_in_group: "You are already registered in group {{group}}.",
+    group_change_requested: "A request to move you to group {{group}} has been sent to the instructor.",
+    admin_profile_request: "Profile change request.\nStudent: {{name}} ({{group}})",
+    admin_profile_request_name: "\nNew full name: {{name}}",
+    admin_profile_request_group: "\nNew group: {{group}}",
+    profile_change_accepted: "The instructor has approved your profile change.",
+    profile_change_rejected: "The instructor has rejected your profile change.",
+
+    status_header: "Progress of the assignments (updated at {{time}}):",
+    status_no_open: "There are no open assignments right now.",
+    status_assignment: "{{title}} — {{group}}",
+    status_not_started: "Not started ({{count}}): {{names}}",
+    status_in_progress: "In progress ({{count}}):",
+    status_in_progress_item: "- {{name}}: {{time_left}}, files: {{items}}",
+    status_finished: "Finished ({{count}}): {{names}}",
+    status_refresh: "Refresh",
+
+    submissions_usage: "Usage: /submissions <full name or part of it> — submissions of the students, /submissions <submission id> — files and history of the submission.",
+    force_finish_usage: "Usage: /force_finish <submission id> <reason>",
+    reopen_usage: "Usage: /reopen <submission id> <minutes> <reason>",
+    reset_usage: "Usage: /reset <submission id> <reason>",
+    delete_item_usage: "Usage: /delete_item <file id> <reason>. File ids are shown by /submissions <submission id>.",
+    attach_usage: "Send the file with the caption /attach <submission id> <reason>",
+    submissions_not_found: "No submissions of students named «{{query}}».",
+    submissions_item: "{{name}} ({{group}}), «{{title}}», variant {{variant}}, attempt {{attempt}}: {{status}}\n{{id}}",
+    submission_details: "Submission {{id}}\n{{name}} ({{group}}), «{{title}}»\nVariant {{variant}}, attempt {{attempt}}\nStarted: {{started_at}}, {{status}}{{reopened}}\nScore: {{score}}",
+    submission_reopened_until: "\nReopened until {{time}}",
+    submission_files: "Files ({{count}}):",
+    submission_file: "- {{id}}: {{name}}, pages: {{pages}}{{attached}}",
+    submission_file_attached: " (attached by the instructor)",
+    submission_history: "History:",
+    submission_action: "- {{time}} {{action}} — {{admin}}: {{reason}}",
+    action_force_finish: "Finished",
+    action_reopen: "Reopened",
+    action_reset: "Reset to a new variant",
+    action_delete_item: "File deleted",
+    action_attach: "File attached",
+    override_done: "{{action}}: {{name}}, «{{title}}».",
+    override_already_finished: "The submission is already finished.",
+    override_not_finished: "The submission is not finished yet.",
+    override_other_in_progress: "{{name}} has another assignment in progress, it has to be finished first.",
+    override_invalid_minutes: "Invalid number of minutes: {{minutes}}",
+    override_generation_failed: "Failed to generate a new variant.",
+    override_item_not_found: "File {{id}} not found.",
+    student_force_finished: "The instructor has finished your work on «{{title}}». Reason: {{reason}}",
+    student_reopened: "The instructor has reopened your work on «{{title}}» for {{minutes}} min., you can send solutions again. Reason: {{reason}}",
+    student_reset: "The instructor has restarted your work on «{{title}}» with a new variant, the files sent before have been deleted. Reason: {{reason}}",
+    student_item_deleted: "The instructor has deleted the file «{{file}}» from your work on «{{title}}». Reason: {{reason}}",
+    student_item_attached: "The instructor has attached the file «{{file}}» to your work on «{{title}}». Reason: {{reason}}",
+
+    solutions_released: "Solutions of «{{title}}», variant {{variant}}. They are also available in /my_results.",
+    solutions_caption: "{{title}} — {{group}}",
+    solutions_volume_caption: "{{title}} — {{group}}, volume {{volume}} of {{count}}",
+    compile_choose: "Choose the assignment to compile:",
+    compile_none: "No assignments have started yet.",
+    compile_choose_mode: "{{title}} — {{group}}\nFinished: {{finished}}, in progress: {{in_progress}}.\nWhich submissions should be compiled?",
+    compile_finished_only: "Finished only",
+    compile_include_in_progress: "Including in progress",
+    compile_started: "Compiling {{title}} — {{group}}...",
+    compile_empty: "There are no submissions to compile.",
+    compile_caption: "{{title}} — {{group}} as of {{time}}\nFinished: {{finished}}, in progress: {{in_progress}}",
+    compile_volume_caption: "{{title}} — {{group}} as of {{time}}, volume {{volume}} of {{count}}\nFinished: {{finished}}, in progress: {{in_progress}}",
+
+    export_choose: "Choose the assignment to export:",
+    export_started: "Exporting {{title}} — {{group}}...",
+    export_caption: "{{title}} — {{group}}: variants, submitted files, compiled and reviewed PDFs and manifest.json. Restore it with /import_archive.",
+    export_saved: "The archive of {{title}} — {{group}} exceeds the Telegram limit and is saved on the server: {{path}}. Restore it with /import_archive {{file}}.",
+    import_send_archive: "Send the zip archive made by /export with the caption /import_archive, or name an archive on the server: /import_archive <file>.",
+    import_file_not_found: "There is no archive {{file}} in the archive directory of the server.",
+    import_invalid: "This is not an archive made by /export: {{error}}",
+    import_already_imported: "This assignment of the group is already in the database.",
+    import_not_administered: "The course of this assignment is administered from another chat.",
+    import_done: "Restored {{title}} — {{group}}: {{students}} students, {{submissions}} submissions.",
+
+    similarity_header: "Possible copying between students:",
+    similarity_none: "No identical files or similar pages were uploaded by different students.",
+    similarity_see_reply: "Possible copying between students was found, see the reply.",
+    similarity_file: "{{name}} ({{file}})",
+    similarity_page: "{{file}}, p. {{page}}",
+    similarity_identical: "- identical files: {{a}} — {{b}}",
+    similarity_similar: "- similar pages (differ by {{distance}}/64): {{a}} — {{b}}",
+
+    group_not_found: "Group «{{group}}» not found.",
+    roster_send_csv: "Send a CSV file with the group roster and the caption /import_roster <group>. Full names are taken from the first column.",
+    roster_empty: "No names found in the file.",
+    roster_csv_error: "Failed to read the CSV: {{error}}",
+    roster_imported: "Roster of group {{group}} uploaded: {{count}} names.\nAdded: {{added}}, removed: {{removed}}, matched with registered students: {{claimed}}.",
+
+    pdf: PdfLabels {
+        lang: "en",
+        group: "Group",
+        time_frame: "Time frame:",
+        available_at: "Start",
+        duration: "Time limit",
+        deadline: "Deadline",
+        variant: "Variant",
+        submission_id: "Submission id",
+        started_at: "Started at",
+        finished_at: "Finished at",
+        total: "Total",
+        problems: "Problems",
+        sample_solutions: "Sample solutions",
+        submitted_solutions: "Submitted solutions",
+        nothing_submitted: "(nothing submitted)",
+        attempt: "(attempt {{attempt}})",
+        attempt_counted: "(attempt {{attempt}}, counted)",
+        not_finished: "(not finished)",
+        in_progress: "(in progress)",
+        no_time_limit: "(no time limit)",
+        duration_format: "%H h. %M min.",
+        no_deadline: "(no deadline)",
+        volume: "Volume {{volume}} of {{count}}",
+        contents: "Contents",
+        contents_volume: "vol. {{volume}}",
+    },
+};
@@ -0,0 +1,416 @@
+use sqlx::PgPool;
+use teloxide::types::{BotCommand, ChatId, Update};
+
+mod en;
+mod ru;
+
+pub use en::EN;
+pub use ru::RU;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    Ru,
+    En,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::Ru, Locale::En];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::Ru => "ru",
+            Locale::En => "en",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Locale> {
+        Locale::ALL
+            .into_iter()
+            .find(|locale| locale.code() == code)
+    }
+
+    /// Locale for a Telegram `language_code`: Russian for `ru`, English for any
+    /// other language.
+    pub fn from_language_code(language_code: Option<&str>) -> Locale {
+        match language_code {
+            Some(code) if !code.starts_with("ru") => Locale::En,
+            _ => Locale::Ru,
+        }
+    }
+
+    pub fn messages(self) -> &'static Messages {
+        match self {
+            Locale::Ru => &RU,
+            Locale::En => &EN,
+        }
+    }
+
+    /// Locale chosen with `/language` (or detected on `/start`) in the chat.
+    /// Used for the messages that are not replies to an update.
+    pub async fn of_chat(chat_id: ChatId, pool: &PgPool) -> Locale {
+        sqlx::query_scalar!(
+            r#"select locale from chat_locale where chat_id = $1"#,
+            chat_id.0
+        )
+        .fetch_optional(pool)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|code| Locale::from_code(&code))
+        .unwrap_or_default()
+    }
+
+    /// Locale of the update: the one chosen in the chat, otherwise the one of
+    /// the user's Telegram client.
+    pub async fn detect(update: Update, pool: PgPool) -> Locale {
+        let chosen = match update.chat() {
+            Some(chat) => sqlx::query_scalar!(
+                r#"select locale from chat_locale where chat_id = $1"#,
+                chat.id.0
+            )
+            .fetch_optional(&pool)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|code| Locale::from_code(&code)),
+            None => None,
+        };
+
+        chosen.unwrap_or_else(|| {
+            Locale::from_language_code(
+                update
+                    .from()
+                    .and_then(|user| user.language_code.as_deref()),
+            )
+        })
+    }
+
+    /// Remembers the locale of the chat. Unless `overwrite`, an already chosen
+    /// locale is kept.
+    pub async fn save(
+        self,
+        chat_id: ChatId,
+        overwrite: bool,
+        pool: &PgPool,
+    ) -> Result<(), sqlx::Error> {
+        if overwrite {
+            sqlx::query!(
+                r#"
+                insert into chat_locale (chat_id, locale) values ($1, $2)
+                on conflict (chat_id) do update set locale = excluded.locale
+                "#,
+                chat_id.0,
+                self.code(),
+            )
+            .execute(pool)
+            .await?;
+        } else {
+            sqlx::query!(
+                r#"
+                insert into chat_locale (chat_id, locale) values ($1, $2)
+                on conflict (chat_id) do nothing
+                "#,
+                chat_id.0,
+                self.code(),
+            )
+            .execute(pool)
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Replaces `{{key}}` in `template` with the corresponding values.
+pub fn fill(template: &str, substitutions: &[(&str, &(dyn std::fmt::Display + Sync))]) -> String {
+    substitutions
+        .iter()
+        .fold(template.to_string(), |text, (key, value)| {
+            text.replace(&format!("{{{{{key}}}}}"), &value.to_string())
+        })
+}
+
+/// Help text in the format of `BotCommands::descriptions`, with the descriptions
+/// taken from the catalog. Commands missing from the catalog keep the description
+/// given in the `BotCommands` derive.
+pub fn describe_commands(header: &str, commands: Vec<BotCommand>, locale: Locale) -> String {
+    let lines = localize_commands(commands, locale)
+        .into_iter()
+        .map(|command| format!("/{} — {}", command.command, command.description))
+        .collect::<Vec<_>>();
+
+    format!("{header}\n\n{}", lines.join("\n"))
+}
+
+/// Commands with the leading `/` removed and the descriptions translated,
+/// e.g. for `set_my_commands`.
+pub fn localize_commands(commands: Vec<BotCommand>, locale: Locale) -> Vec<BotCommand> {
+    commands
+        .into_iter()
+        .map(|command| {
+            let name = command.command.trim_start_matches('/').to_string();
+            let description = locale
+                .messages()
+                .commands
+                .iter()
+                .find(|(command_name, _)| *command_name == name)
+                .map_or(command.description, |(_, description)| description.to_string());
+            BotCommand::new(name, description)
+        })
+        .collect()
+}
+
+/// All user-facing texts of the bot. Values may contain `{{key}}` placeholders
+/// to be filled in with [`fill`].
+pub struct Messages {
+    /// Command name (without `/`) to its description.
+    pub commands: &'static [(&'static str, &'static str)],
+    pub student_commands_header: &'static str,
+    pub admin_commands_header: &'static str,
+
+    pub start_greeting: &'static str,
+    pub submission_rules: &'static str,
+
+    pub channels_not_supported: &'static str,
+    pub unknown_command: &'static str,
+    pub accept: &'static str,
+    pub reject: &'static str,
+    pub accepted: &'static str,
+    pub rejected: &'static str,
+    pub request_already_resolved: &'static str,
+    pub send_full_name: &'static str,
+    pub invalid_group: &'static str,
+    pub choose_group: &'static str,
+    pub date_format: &'static str,
+
+    // language
+    pub choose_language: &'static str,
+    pub language_set: &'static str,
+
+    // start
+    pub admin_start: &'static str,
+    pub hello: &'static str,
+    pub several_courses: &'static str,
+    pub registration_pending: &'static str,
+
+    // registration
+    pub no_courses: &'static str,
+    pub choose_course: &'static str,
+    pub course_info: &'static str,
+    pub course_info_with_instructor: &'static str,
+    pub invalid_course: &'static str,
+    pub chosen_group: &'static str,
+    pub enter_full_name: &'static str,
+    pub name_claimed: &'static str,
+    pub not_in_roster: &'static str,
+    pub did_you_mean: &'static str,
+    pub roster_entry_taken: &'static str,
+    pub registration_failed: &'static str,
+    pub registration_done: &'static str,
+    pub admin_registration_request: &'static str,
+    pub registration_request_sent: &'static str,
+    pub registration_accepted: &'static str,
+    pub registration_rejected: &'static str,
+    pub registration_already_done: &'static str,
+    pub admin_registration_not_applied: &'static str,
+
+    // idle
+    pub assignment_until: &'static str,
+    pub assignment_no_deadline: &'static str,
+    pub assignment_attempt: &'static str,
+    pub no_assignments: &'static str,
+    pub choose_assignment: &'static str,
+    pub deadline_not_set: &'static str,
+    pub duration_until_deadline: &'static str,
+    pub duration_format: &'static str,
+    pub attempts_info: &'static str,
+    pub assignment_info: &'static str,
+    pub start_assignment: &'static str,
+    pub back: &'static str,
+    pub can_start_now: &'static str,
+    pub assignment_unavailable: &'static str,
+    pub generating_variant: &'static str,
+    pub generation_failed: &'static str,
+    pub problems: &'static str,
+    pub your_courses: &'static str,
+    pub enroll: &'static str,
+    pub current_course: &'static str,
+
+    // assignment
+    pub finish: &'static str,
+    pub continue_assignment: &'static str,
+    pub confirm_finish: &'static str,
+    pub go_back_hint: &'static str,
+    pub no_solutions_yet: &'static str,
+    pub time_left_unknown: &'static str,
+    pub minutes_left: &'static str,
+    pub minutes_overdue: &'static str,
+    pub time_left: &'static str,
+    pub already_finished: &'static str,
+    pub unsupported_message_kind: &'static str,
+    pub unknown_command_try_help: &'static str,
+    pub send_photos_as_files: &'static str,
+    pub unsupported_message: &'static str,
+    pub assignment_finished: &'static str,
+    pub time_is_up: &'static str,
+
+    // results
+    pub results_empty: &'static str,
+    pub results_header: &'static str,
+    pub results_item: &'static str,
+    pub results_attempt: &'static str,
+    pub results_counted: &'static str,
+    pub results_finished: &'static str,
+    pub results_in_progress: &'static str,
+    pub results_not_graded: &'static str,
+    pub results_problems: &'static str,
+    pub results_files: &'static str,
+    pub results_solutions: &'static str,
+    pub results_report: &'static str,
+    pub results_not_found: &'static str,
+    pub results_no_files: &'static str,
+    pub results_no_report: &'static str,
+    pub report_usage: &'static str,
+    pub report_invalid_score: &'static str,
+    pub report_not_found: &'static str,
+    pub report_uploaded: &'static str,
+    pub report_available: &'static str,
+
+    // profile
+    pub profile: &'static str,
+    pub profile_pending: &'static str,
+    pub profile_pending_name: &'static str,
+    pub profile_pending_group: &'static str,
+    pub profile_request_pending: &'static str,
+    pub enter_new_name: &'static str,
+    pub name_change_cancelled: &'static str,
+    pub name_change_requested: &'static str,
+    pub name_changed: &'static str,
+    pub already_in_group: &'static str,
+    pub group_change_requested: &'static str,
+    pub admin_profile_request: &'static str,
+    pub admin_profile_request_name: &'static str,
+    pub admin_profile_request_group: &'static str,
+    pub profile_change_accepted: &'static str,
+    pub profile_change_rejected: &'static str,
+
+    // status
+    pub status_header: &'static str,
+    pub status_no_open: &'static str,
+    pub status_assignment: &'static str,
+    pub status_not_started: &'static str,
+    pub status_in_progress: &'static str,
+    pub status_in_progress_item: &'static str,
+    pub status_finished: &'static str,
+    pub status_refresh: &'static str,
+
+    // submission overrides
+    pub submissions_usage: &'static str,
+    pub force_finish_usage: &'static str,
+    pub reopen_usage: &'static str,
+    pub reset_usage: &'static str,
+    pub delete_item_usage: &'static str,
+    pub attach_usage: &'static str,
+    pub submissions_not_found: &'static str,
+    pub submissions_item: &'static str,
+    pub submission_details: &'static str,
+    pub submission_reopened_until: &'static str,
+    pub submission_files: &'static str,
+    pub submission_file: &'static str,
+    pub submission_file_attached: &'static str,
+    pub submission_history: &'static str,
+    pub submission_action: &'static str,
+    pub action_force_finish: &'static str,
+    pub action_reopen: &'static str,
+    pub action_reset: &'static str,
+    pub action_delete_item: &'static str,
+    pub action_attach: &'static str,
+    pub override_done: &'static str,
+    pub override_already_finished: &'static str,
+    pub override_not_finished: &'static str,
+    pub override_other_in_progress: &'static str,
+    pub override_invalid_minutes: &'static str,
+    pub override_generation_failed: &'static str,
+    pub override_item_not_found: &'static str,
+    pub student_force_finished: &'static str,
+    pub student_reopened: &'static str,
+    pub student_reset: &'static str,
+    pub student_item_deleted: &'static str,
+    pub student_item_attached: &'static str,
+
+    // compiled solutions
+    pub solutions_released: &'static str,
+    pub solutions_caption: &'static str,
+    pub solutions_volume_caption: &'static str,
+    pub compile_choose: &'static str,
+    pub compile_none: &'static str,
+    pub compile_choose_mode: &'static str,
+    pub compile_finished_only: &'static str,
+    pub compile_include_in_progress: &'static str,
+    pub compile_started: &'static str,
+    pub compile_empty: &'static str,
+    pub compile_caption: &'static str,
+    pub compile_volume_caption: &'static str,
+
+    // archives
+    pub export_choose: &'static str,
+    pub export_started: &'static str,
+    pub export_caption: &'static str,
+    pub export_saved: &'static str,
+    pub import_send_archive: &'static str,
+    pub import_file_not_found: &'static str,
+    pub import_invalid: &'static str,
+    pub import_already_imported: &'static str,
+    pub import_not_administered: &'static str,
+    pub import_done: &'static str,
+
+    // similarity report
+    pub similarity_header: &'static str,
+    pub similarity_none: &'static str,
+    pub similarity_see_reply: &'static str,
+    pub similarity_file: &'static str,
+    pub similarity_page: &'static str,
+    pub similarity_identical: &'static str,
+    pub similarity_similar: &'static str,
+
+    // roster
+    pub group_not_found: &'static str,
+    pub roster_send_csv: &'static str,
+    pub roster_empty: &'static str,
+    pub roster_csv_error: &'static str,
+    pub roster_imported: &'static str,
+
+    // compiled PDF
+    pub pdf: PdfLabels,
+}
+
+/// Labels of the compiled PDF of a group assignment.
+pub struct PdfLabels {
+    /// Value of typst's `text(lang: ..)`.
+    pub lang: &'static str,
+    pub group: &'static str,
+    pub time_frame: &'static str,
+    pub available_at: &'static str,
+    pub duration: &'static str,
+    pub deadline: &'static str,
+    pub variant: &'static str,
+    pub submission_id: &'static str,
+    pub started_at: &'static str,
+    pub finished_at: &'static str,
+    pub total: &'static str,
+    pub problems: &'static str,
+    pub sample_solutions: &'static str,
+    pub submitted_solutions: &'static str,
+    pub nothing_submitted: &'static str,
+    pub attempt: &'static str,
+    pub attempt_counted: &'static str,
+    pub not_finished: &'static str,
+    pub in_progress: &'static str,
+    pub no_time_limit: &'static str,
+    pub duration_format: &'static str,
+    pub no_deadline: &'static str,
+    /// Title of a volume when the solutions are split into several.
+    pub volume: &'static str,
+    pub contents: &'static str,
+    pub contents_volume: &'static str,
+}
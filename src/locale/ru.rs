@@ -0,0 +1,276 @@
+use super::{Messages, PdfLabels};
+
+pub static RU: Messages = Messages {
+    commands: &[
+        ("help", "показать это сообщение."),
+        ("show_assignments", "показать доступные задания."),
+        ("my_results", "показать результаты выполненных заданий."),
+        ("me", "показать данные профиля."),
+        ("change_name", "исправить ФИО."),
+        ("change_group", "сменить группу (с подтверждения преподавателя)."),
+        ("courses", "переключиться на другой курс или записаться на новый."),
+        ("language", "сменить язык / change language."),
+        ("finish", "завершить выполнение задания."),
+        ("go_back", "выйти из этого задания без завершения."),
+        ("get_problems", "посмотреть текущее задание."),
+        ("get_rules", "посмотреть правила отправки решений."),
+        ("get_solutions", "посмотреть оправленные решения."),
+        ("show_time_left", "сколько осталось времени?"),
+        (
+            "import_roster",
+            "загрузить список группы: CSV-файл (ФИО в первом столбце) с подписью /import_roster <группа>.",
+        ),
+        (
+            "upload_report",
+            "загрузить проверенную работу: PDF с подписью /upload_report <id работы> [баллы].",
+        ),
+        ("status", "показать ход выполнения открытых заданий."),
+        ("compile", "собрать присланные решения, не дожидаясь крайнего срока."),
+        ("submissions", "найти работы: /submissions <ФИО или id работы>."),
+        ("force_finish", "завершить работу: /force_finish <id работы> <причина>."),
+        (
+            "reopen",
+            "заново открыть завершённую работу: /reopen <id работы> <минуты> <причина>.",
+        ),
+        ("reset", "перезапустить работу с новым вариантом: /reset <id работы> <причина>."),
+        ("delete_item", "удалить файл из работы: /delete_item <id файла> <причина>."),
+        (
+            "attach",
+            "прикрепить файл к работе: файл с подписью /attach <id работы> <причина>.",
+        ),
+        ("export", "выгрузить задание группы со всеми работами в zip-архив."),
+        (
+            "import_archive",
+            "восстановить задание из архива: zip-файл с подписью /import_archive или /import_archive <файл> для архива на сервере.",
+        ),
+    ],
+    student_commands_header: "Доступные команды:",
+    admin_commands_header: "Команды преподавателя:",
+
+    start_greeting: include_str!("ru/start_greeting.txt"),
+    submission_rules: include_str!("ru/submission_rules.txt"),
+
+    channels_not_supported: "Сообщения из каналов не поддерживаются.",
+    unknown_command: "Ошибка: Неизвестная команда.",
+    accept: "Принять",
+    reject: "Отклонить",
+    accepted: "Принято",
+    rejected: "Отклонено",
+    request_already_resolved: "Запрос уже рассмотрен.",
+    send_full_name: "Пожалуйста, пришлите своё ФИО:",
+    invalid_group: "Выбрана некорректная группа.",
+    choose_group: "Выберите группу:",
+    date_format: "%Y-%m-%d",
+
+    choose_language: "Выберите язык / Choose language:",
+    language_set: "Язык интерфейса: русский.",
+
+    admin_start: "Запуск с привелегиями админа",
+    hello: "{{name}}, здравствуйте!",
+    several_courses: "Вы записаны на несколько курсов, переключиться между ними можно командой /courses.",
+    registration_pending: "Заявка на регистрацию ожидает подтверждения преподавателем.",
+
+    no_courses: "Сейчас нет курсов, доступных для записи.",
+    choose_course: "Выберите курс:",
+    course_info: "Курс: <b>{{title}}</b>",
+    course_info_with_instructor: "Курс: <b>{{title}}</b> (преподаватель: {{instructor}})",
+    invalid_course: "Выбран некорректный курс.",
+    chosen_group: "Выбранная группа: {{group}}",
+    enter_full_name: "Введите ваше ФИО:",
+    name_claimed: "Студент с таким ФИО уже зарегистрирован с другого аккаунта.",
+    not_in_roster: "Моего ФИО нет в списке",
+    did_you_mean: "ФИО не найдено в списке группы. Возможно, вы имели в виду:",
+    roster_entry_taken: "Это ФИО уже занято, введите ваше ФИО ещё раз:",
+    registration_failed: "Не удалось зарегистрироваться: это ФИО уже занято, либо вы уже зарегистрированы в этой группе.",
+    registration_done: "Регистрация пройдена!",
+    admin_registration_request: "Заявка на регистрацию вне списка группы.\nФИО: {{name}}\nГруппа: {{group}}",
+    registration_request_sent: "Вашего ФИО нет в списке группы, заявка на регистрацию отправлена преподавателю. Вы получите сообщение, когда она будет рассмотрена.",
+    registration_accepted: "Преподаватель подтвердил регистрацию!",
+    registration_rejected: "Заявка на регистрацию отклонена. Чтобы попробовать снова, отправьте /start.",
+    registration_already_done: "Преподаватель подтвердил регистрацию, но этот аккаунт уже зарегистрирован в группе. Чтобы продолжить, отправьте /start.",
+    admin_registration_not_applied: "Регистрация {{name}} не применена: этот аккаунт уже зарегистрирован в группе.",
+
+    assignment_until: " до {{deadline}}",
+    assignment_no_deadline: " (крайний срок сдачи не указан)",
+    assignment_attempt: " [попытка {{attempt}} из {{max_attempts}}]",
+    no_assignments: "Сейчас нет доступных заданий.",
+    choose_assignment: "Выберите задание:",
+    deadline_not_set: "(не указано)",
+    duration_until_deadline: "(до крайнего срока)",
+    duration_format: "%H часов %M минут",
+    attempts_info: "Попытка {{attempt}} из {{max_attempts}}. Для каждой попытки генерируется новый вариант.\n\n",
+    assignment_info: "{{title}}: \n\n{{description}}\n\nВремя на выполнение: {{time}}\n\nКрайний срок выполнения: {{deadline}}.\n\n{{attempts}}{{rules}}",
+    start_assignment: "Приступить",
+    back: "Назад",
+    can_start_now: "Можно приступить прямо сейчас:",
+    assignment_unavailable: "Ошибка: выбранное задание недоступно.",
+    generating_variant: "Генерируем вариант...",
+    generation_failed: "Не удалось сгенерировать задачи. Для устранения ошибки, обратитесь к преподавателю.",
+    problems: "Задачи: ",
+    your_courses: "Ваши курсы:",
+    enroll: "Записаться на другой курс",
+    current_course: "Текущий курс: {{title}}",
+
+    finish: "Завершить",
+    continue_assignment: "Нет, продолжить",
+    confirm_finish: "Завершить выполнение задания?",
+    go_back_hint: "Чтобы продолжить выполнение задания, просто заново его откройте.",
+    no_solutions_yet: "Пока не прислано ни одного файла с решениями.",
+    time_left_unknown: "(оставшееся время не известно)",
+    minutes_left: "{{minutes}} мин. до конца",
+    minutes_overdue: "просрочено на {{minutes}} мин.",
+    time_left: "Оставшееся время: {{time}}",
+    already_finished: "Время закончилось, выполнение задания уже завершено.",
+    unsupported_message_kind: "Данный тип сообщений не поддерживается (попробуйте обычные сообщения).",
+    unknown_command_try_help: "Неизвестная команда, попробуйте /help.",
+    send_photos_as_files: "Пожалуйста, пришлите фотографии в виде несжатых файлов (см. /get_rules)",
+    unsupported_message: "Данный тип сообщений не поддерживается",
+    assignment_finished: "Выполнение задания завершено!",
+    time_is_up: "Время закончилось, выполнение задания завершено.",
+
+    results_empty: "Вы ещё не выполняли заданий.",
+    results_header: "Ваши результаты:",
+    results_item: "{{n}}. {{title}}, вариант {{variant}}{{attempt}}\nНачато: {{started_at}}, {{status}}\nБаллы: {{score}}",
+    results_attempt: ", попытка {{attempt}}",
+    results_counted: " (засчитывается)",
+    results_finished: "завершено: {{finished_at}}",
+    results_in_progress: "в процессе",
+    results_not_graded: "не проверено",
+    results_problems: "{{n}}: задачи",
+    results_files: "{{n}}: решения",
+    results_solutions: "{{n}}: ответы",
+    results_report: "{{n}}: проверенная работа",
+    results_not_found: "Работа не найдена.",
+    results_no_files: "Файлы с решениями не были присланы.",
+    results_no_report: "Проверенная работа ещё не загружена.",
+    report_usage: "Пришлите PDF проверенной работы с подписью /upload_report <id работы> [баллы]. Id работы указан в сборнике решений.",
+    report_invalid_score: "Некорректные баллы: {{score}}",
+    report_not_found: "Работа {{id}} не найдена.",
+    report_uploaded: "Проверенная работа загружена: {{name}}, «{{title}}». Баллы: {{score}}.",
+    report_available: "Работа по заданию «{{title}}» проверена. Баллы: {{score}}. Проверенную работу можно скачать в /my_results.",
+
+    profile: "Курс: {{course}}\nФИО: {{name}}\nГруппа: {{group}}\nДата регистрации: {{date}}",
+    profile_pending: "\n\nОжидает подтверждения преподавателем:",
+    profile_pending_name: "\n- новое ФИО: {{name}}",
+    profile_pending_group: "\n- новая группа: {{group}}",
+    profile_request_pending: "Предыдущий запрос на изменение профиля ещё не рассмотрен преподавателем.",
+    enter_new_name: "Введите исправленное ФИО:",
+    name_change_cancelled: "Изменение ФИО отменено.",
+    name_change_requested: "ФИО изменяется только с подтверждения преподавателя, если уже начато первое задание или для группы загружен список студентов. Запрос отправлен.",
+    name_changed: "ФИО изменено: {{name}}",
+    already_in_group: "Вы уже зарегистрированы в группе {{group}}.",
+    group_change_requested: "Запрос на перевод в группу {{group}} отправлен преподавателю.",
+    admin_profile_request: "Запрос на изменение профиля.\nСтудент: {{name}} ({{group}})",
+    admin_profile_request_name: "\nНовое ФИО: {{name}}",
+    admin_profile_request_group: "\nНовая группа: {{group}}",
+    profile_change_accepted: "Преподаватель подтвердил изменение профиля.",
+    profile_change_rejected: "Преподаватель отклонил изменение профиля.",
+
+    status_header: "Ход выполнения заданий (обновлено в {{time}}):",
+    status_no_open: "Сейчас нет открытых заданий.",
+    status_assignment: "{{title}} — {{group}}",
+    status_not_started: "Не начали ({{count}}): {{names}}",
+    status_in_progress: "Выполняют ({{count}}):",
+    status_in_progress_item: "- {{name}}: {{time_left}}, файлов: {{items}}",
+    status_finished: "Завершили ({{count}}): {{names}}",
+    status_refresh: "Обновить",
+
+    submissions_usage: "Использование: /submissions <ФИО или его часть> — работы студентов, /submissions <id работы> — файлы и история работы.",
+    force_finish_usage: "Использование: /force_finish <id работы> <причина>",
+    reopen_usage: "Использование: /reopen <id работы> <минуты> <причина>",
+    reset_usage: "Использование: /reset <id работы> <причина>",
+    delete_item_usage: "Использование: /delete_item <id файла> <причина>. Id файлов показывает /submissions <id работы>.",
+    attach_usage: "Пришлите файл с подписью /attach <id работы> <причина>",
+    submissions_not_found: "Работ студентов с ФИО «{{query}}» не найдено.",
+    submissions_item: "{{name}} ({{group}}), «{{title}}», вариант {{variant}}, попытка {{attempt}}: {{status}}\n{{id}}",
+    submission_details: "Работа {{id}}\n{{name}} ({{group}}), «{{title}}»\nВариант {{variant}}, попытка {{attempt}}\nНачато: {{started_at}}, {{status}}{{reopened}}\nБаллы: {{score}}",
+    submission_reopened_until: "\nОткрыто заново до {{time}}",
+    submission_files: "Файлы ({{count}}):",
+    submission_file: "- {{id}}: {{name}}, страниц: {{pages}}{{attached}}",
+    submission_file_attached: " (прикреплён преподавателем)",
+    submission_history: "История:",
+    submission_action: "- {{time}} {{action}} — {{admin}}: {{reason}}",
+    action_force_finish: "Завершено",
+    action_reopen: "Открыто заново",
+    action_reset: "Заменено новым вариантом",
+    action_delete_item: "Файл удалён",
+    action_attach: "Файл прикреплён",
+    override_done: "{{action}}: {{name}}, «{{title}}».",
+    override_already_finished: "Работа уже завершена.",
+    override_not_finished: "Работа ещё не завершена.",
+    override_other_in_progress: "{{name}} выполняет другое задание, сначала его нужно завершить.",
+    override_invalid_minutes: "Некорректное число минут: {{minutes}}",
+    override_generation_failed: "Не удалось сгенерировать новый вариант.",
+    override_item_not_found: "Файл {{id}} не найден.",
+    student_force_finished: "Преподаватель завершил вашу работу по заданию «{{title}}». Причина: {{reason}}",
+    student_reopened: "Преподаватель заново открыл вашу работу по заданию «{{title}}» на {{minutes}} мин., можно снова присылать решения. Причина: {{reason}}",
+    student_reset: "Преподаватель перезапустил вашу работу по заданию «{{title}}» с новым вариантом, присланные ранее файлы удалены. Причина: {{reason}}",
+    student_item_deleted: "Преподаватель удалил файл «{{file}}» из вашей работы по заданию «{{title}}». Причина: {{reason}}",
+    student_item_attached: "Преподаватель прикрепил файл «{{file}}» к вашей работе по заданию «{{title}}». Причина: {{reason}}",
+
+    solutions_released: "Решения задания «{{title}}», вариант {{variant}}. Их также можно найти в /my_results.",
+    solutions_caption: "{{title}} — {{group}}",
+    solutions_volume_caption: "{{title}} — {{group}}, том {{volume}} из {{count}}",
+    compile_choose: "Выберите задание для сборки:",
+    compile_none: "Пока не началось ни одно задание.",
+    compile_choose_mode: "{{title}} — {{group}}\nЗавершено: {{finished}}, выполняется: {{in_progress}}.\nКакие работы собрать?",
+    compile_finished_only: "Только завершённые",
+    compile_include_in_progress: "Включая выполняемые",
+    compile_started: "Собираем {{title}} — {{group}}...",
+    compile_empty: "Нет работ для сборки.",
+    compile_caption: "{{title}} — {{group}} на {{time}}\nЗавершено: {{finished}}, выполняется: {{in_progress}}",
+    compile_volume_caption: "{{title}} — {{group}} на {{time}}, том {{volume}} из {{count}}\nЗавершено: {{finished}}, выполняется: {{in_progress}}",
+
+    export_choose: "Выберите задание для выгрузки:",
+    export_started: "Выгружаем {{title}} — {{group}}...",
+    export_caption: "{{title}} — {{group}}: варианты, присланные файлы, собранные и проверенные PDF и manifest.json. Восстановить — /import_archive.",
+    export_saved: "Архив {{title}} — {{group}} больше лимита Telegram и сохранён на сервере: {{path}}. Восстановить — /import_archive {{file}}.",
+    import_send_archive: "Пришлите zip-архив, выгруженный командой /export, с подписью /import_archive или укажите архив на сервере: /import_archive <файл>.",
+    import_file_not_found: "Архива {{file}} нет в каталоге архивов сервера.",
+    import_invalid: "Это не архив, выгруженный командой /export: {{error}}",
+    import_already_imported: "Это задание группы уже есть в базе.",
+    import_not_administered: "Курс этого задания ведётся из другого чата.",
+    import_done: "Восстановлено {{title}} — {{group}}: студентов — {{students}}, работ — {{submissions}}.",
+
+    similarity_header: "Возможное списывание между студентами:",
+    similarity_none: "Разные студенты не присылали одинаковых файлов или похожих страниц.",
+    similarity_see_reply: "Найдено возможное списывание между студентами, см. ответ.",
+    similarity_file: "{{name}} ({{file}})",
+    similarity_page: "{{file}}, стр. {{page}}",
+    similarity_identical: "- одинаковые файлы: {{a}} — {{b}}",
+    similarity_similar: "- похожие страницы (отличие {{distance}}/64): {{a}} — {{b}}",
+
+    group_not_found: "Группа «{{group}}» не найдена.",
+    roster_send_csv: "Пришлите CSV-файл со списком группы с подписью /import_roster <группа>. ФИО берутся из первого столбца.",
+    roster_empty: "В файле не найдено ни одного ФИО.",
+    roster_csv_error: "Не удалось прочитать CSV: {{error}}",
+    roster_imported: "Список группы {{group}} загружен: {{count}} ФИО.\nДобавлено: {{added}}, удалено: {{removed}}, сопоставлено с зарегистрированными студентами: {{claimed}}.",
+
+    pdf: PdfLabels {
+        lang: "ru",
+        group: "Группа",
+        time_frame: "Временные рамки:",
+        available_at: "Начало",
+        duration: "Время на выполнение",
+        deadline: "Крайний срок сдачи",
+        variant: "Вариант",
+        submission_id: "Id работы",
+        started_at: "Время начала",
+        finished_at: "Время окончания",
+        total: "Итог",
+        problems: "Задачи",
+        sample_solutions: "Примеры решений",
+        submitted_solutions: "Присланные решения",
+        nothing_submitted: "(ничего не прислано)",
+        attempt: "(попытка {{attempt}})",
+        attempt_counted: "(попытка {{attempt}}, засчитывается)",
+        not_finished: "(не завершено)",
+        in_progress: "(выполняется)",
+        no_time_limit: "(без ограничения по времени)",
+        duration_format: "%H ч. %M мин.",
+        no_deadline: "(без крайнего срока)",
+        volume: "Том {{volume}} из {{count}}",
+        contents: "Содержание",
+        contents_volume: "том {{volume}}",
+    },
+};
@@ -1,9 +1,14 @@
 mod bot;
 mod db;
+mod locale;
 mod submissions;
 
 // mod util;
-use crate::bot::BotState;
+use crate::{
+    bot::{BotState, MyError, admin},
+    locale::Locale,
+    submissions::archive::{self, ImportError},
+};
 use sqlx::PgPool;
 use teloxide::{dispatching::dialogue::InMemStorage, prelude::*};
 
@@ -17,9 +22,28 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
 
     let database_url = std::env::var("DATABASE_URL")?;
     let pool = PgPool::connect(&database_url).await?;
+
+    // `diffurcheck import-archive <path>` restores an archive without the bot, for
+    // the archives too large to go through Telegram
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.as_slice() {
+        [] => {}
+        [command, path] if command == "import-archive" => {
+            return import_archive_file(path, &pool).await;
+        }
+        _ => return Err("usage: diffurcheck [import-archive <path to zip>]".into()),
+    }
+
     let bot = Bot::from_env();
     let storage = InMemStorage::<BotState>::new();
 
+    // clients in languages other than Russian get the English menu
+    bot.set_my_commands(bot::language::student_menu(Locale::En))
+        .await?;
+    bot.set_my_commands(bot::language::student_menu(Locale::Ru))
+        .language_code(Locale::Ru.code())
+        .await?;
+
     tokio::spawn({
         let pool = pool.clone();
         let bot = bot.clone();
@@ -37,6 +61,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
                     Ok(_) => {},
                     Err(err) => log::error!("process_finished_assignments failed with {err}"),
                 }
+                match submissions::release_solutions(bot.clone(), pool.clone()).await {
+                    Ok(_) => {},
+                    Err(err) => log::error!("release_solutions failed with {err}"),
+                }
             }
         }
     });
@@ -50,3 +78,24 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
 
     Ok(())
 }
+
+/// Imports an archive made by `/export` from a file, administering a new course from
+/// `ADMIN_CHAT_ID`.
+async fn import_archive_file(path: &str, pool: &PgPool) -> Result<(), MyError> {
+    let file = std::fs::File::open(path)?;
+    match archive::import_archive(file, admin::admin_chat_id()?, pool).await {
+        Ok(summary) => {
+            println!(
+                "imported {} — {}: {} students, {} submissions",
+                summary.title, summary.group_name, summary.students, summary.submissions
+            );
+            Ok(())
+        }
+        Err(ImportError::InvalidArchive(error)) => Err(format!("invalid archive: {error}").into()),
+        Err(ImportError::AlreadyImported) => Err("the group assignment is already imported".into()),
+        Err(ImportError::NotAdministered) => {
+            Err("the course is administered from another chat".into())
+        }
+        Err(ImportError::Other(err)) => Err(err),
+    }
+}
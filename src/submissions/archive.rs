@@ -0,0 +1,801 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{Read, Seek, Write},
+    path::PathBuf,
+};
+
+use chrono::{DateTime, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, types::Json};
+use teloxide::types::ChatId;
+use uuid::Uuid;
+use zip::{ZipArchive, ZipWriter, write::SimpleFileOptions};
+
+use crate::{
+    bot::{MyError, admin},
+    submissions::{page_map::PageMap, similarity},
+};
+
+/// Version of the archive layout, checked on import.
+const FORMAT_VERSION: u32 = 1;
+const MANIFEST_PATH: &str = "manifest.json";
+const DEFAULT_ARCHIVE_DIR: &str = "archives";
+
+/// `manifest.json` of the archive. Files are referred to by their path in the
+/// archive, ids are the ones of the exporting database and are kept on import.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    pub format_version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub course: CourseRecord,
+    pub group: GroupRecord,
+    pub assignment: AssignmentRecord,
+    pub group_assignment: GroupAssignmentRecord,
+    pub variants: Vec<VariantRecord>,
+    pub students: Vec<StudentRecord>,
+    pub submissions: Vec<SubmissionRecord>,
+    pub compiled_volumes: Vec<VolumeRecord>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CourseRecord {
+    pub id: Uuid,
+    pub title: String,
+    pub instructor: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupRecord {
+    pub id: Uuid,
+    pub name: String,
+    pub academic_year: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AssignmentRecord {
+    pub id: Uuid,
+    pub title: String,
+    pub description: String,
+    pub generator: String,
+    pub duration: Option<NaiveTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupAssignmentRecord {
+    pub id: Uuid,
+    pub available_at: DateTime<Utc>,
+    pub deadline: Option<DateTime<Utc>>,
+    pub max_attempts: i32,
+    pub attempt_policy: String,
+    pub release_solutions: String,
+    /// Missing in the archives exported before the parameters were introduced.
+    #[serde(default = "no_parameters")]
+    pub generator_parameters: serde_json::Value,
+    pub completed: bool,
+    pub compiled_at: Option<DateTime<Utc>>,
+    /// The compiled solutions with the grader's notes, if uploaded.
+    pub graded_solutions: Option<String>,
+}
+
+fn no_parameters() -> serde_json::Value {
+    serde_json::json!({})
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VariantRecord {
+    pub id: Uuid,
+    pub variant_no: i32,
+    /// Typst sources.
+    pub problem_code: String,
+    pub solution_code: String,
+    pub problem_images: Vec<String>,
+    pub solution_images: Vec<String>,
+    /// Missing in the archives exported before the fingerprints were introduced.
+    #[serde(default)]
+    pub fingerprint: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StudentRecord {
+    pub id: Uuid,
+    pub telegram_id: i64,
+    pub chat_id: i64,
+    pub full_name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubmissionRecord {
+    pub id: Uuid,
+    pub student_id: Uuid,
+    pub variant_id: Uuid,
+    pub attempt_no: i32,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub reopened_until: Option<DateTime<Utc>>,
+    pub score: Option<f32>,
+    pub graded_report: Option<String>,
+    pub solutions_released_at: Option<DateTime<Utc>>,
+    pub items: Vec<ItemRecord>,
+    pub actions: Vec<ActionRecord>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ItemRecord {
+    pub id: Uuid,
+    pub path: String,
+    pub file_name: Option<String>,
+    /// MIME type.
+    pub extension: String,
+    pub pages: i32,
+    /// Null for the files attached by the admin.
+    pub message_id: Option<i32>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ActionRecord {
+    pub action: String,
+    pub submission_item_id: Option<Uuid>,
+    pub admin_telegram_id: i64,
+    pub admin_name: String,
+    pub reason: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VolumeRecord {
+    pub volume_no: i32,
+    pub volume_count: i32,
+    pub path: String,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub page_map: Option<PageMap>,
+}
+
+/// File name safe to use as a part of a path in the archive.
+fn sanitize(file_name: &str) -> String {
+    file_name.replace(['/', '\\'], "_")
+}
+
+/// Directory on the server where the archives are written and looked up by name.
+pub fn archive_dir() -> PathBuf {
+    std::env::var("ARCHIVE_DIR")
+        .unwrap_or_else(|_| DEFAULT_ARCHIVE_DIR.to_string())
+        .into()
+}
+
+/// Path of the archive of the group assignment in `archive_dir`.
+pub fn archive_path(group_assignment_id: Uuid) -> PathBuf {
+    archive_dir().join(format!("archive_{group_assignment_id}.zip"))
+}
+
+fn add_file(zip: &mut ZipWriter<impl Write + Seek>, path: &str, data: &[u8]) -> Result<(), MyError> {
+    zip.start_file(path, SimpleFileOptions::default())?;
+    zip.write_all(data)?;
+    Ok(())
+}
+
+/// Writes the zip with the variants, the submitted files, the compiled and graded
+/// PDFs and `manifest.json` of the group assignment to `archive_path`, which is
+/// returned. The archive is written to disk as it may exceed Telegram's limits.
+pub async fn export_group_assignment(
+    group_assignment_id: Uuid,
+    pool: &PgPool,
+) -> Result<PathBuf, MyError> {
+    let path = archive_path(group_assignment_id);
+    std::fs::create_dir_all(archive_dir())?;
+    let mut zip = ZipWriter::new(File::create(&path)?);
+
+    let rec = sqlx::query!(
+        r#"
+        select ga.available_at, ga.deadline, ga.max_attempts, ga.attempt_policy,
+               ga.release_solutions, ga.generator_parameters, ga.completed, ga.compiled_at,
+               ga.graded_solutions,
+               a.id as "assignment_id", a.title as "assignment_title", a.description,
+               a.generator, a.duration,
+               g.id as "group_id", g.name as "group_name", g.academic_year,
+               c.id as "course_id", c.title as "course_title", c.instructor
+        from group_assignment ga
+        inner join assignment a on ga.assignment_id = a.id
+        inner join "group" g on ga.group_id = g.id
+        inner join course c on a.course_id = c.id
+        where ga.id = $1
+        "#,
+        group_assignment_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let variants = sqlx::query!(
+        r#"
+        select id, variant_no, problem_code, solution_code, problem_images, solution_images,
+               fingerprint, created_at
+        from variant
+        where id in (select variant_id from submission where group_assignment_id = $1)
+        order by variant_no
+        "#,
+        group_assignment_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut variant_records = vec![];
+    for variant in variants {
+        let dir = format!("variants/{}", variant.variant_no);
+        add_file(&mut zip, &format!("{dir}/problem.typ"), variant.problem_code.as_bytes())?;
+        add_file(&mut zip, &format!("{dir}/solution.typ"), variant.solution_code.as_bytes())?;
+
+        let mut images = |kind: &str, images: Vec<Vec<u8>>| {
+            images
+                .iter()
+                .enumerate()
+                .map(|(i, image)| {
+                    let path = format!("{dir}/{kind}_{}.png", i + 1);
+                    add_file(&mut zip, &path, image)?;
+                    Ok(path)
+                })
+                .collect::<Result<Vec<_>, MyError>>()
+        };
+        let problem_images = images("problem", variant.problem_images)?;
+        let solution_images = images("solution", variant.solution_images)?;
+
+        variant_records.push(VariantRecord {
+            id: variant.id,
+            variant_no: variant.variant_no,
+            problem_code: format!("{dir}/problem.typ"),
+            solution_code: format!("{dir}/solution.typ"),
+            problem_images,
+            solution_images,
+            fingerprint: variant.fingerprint,
+            created_at: variant.created_at,
+        });
+    }
+
+    // the whole group, including the students who have not started
+    let students = sqlx::query_as!(
+        StudentRecord,
+        r#"
+        select id, telegram_id, chat_id, full_name, created_at
+        from student
+        where group_id = $1
+            or id in (select student_id from submission where group_assignment_id = $2)
+        order by full_name
+        "#,
+        rec.group_id,
+        group_assignment_id,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let submissions = sqlx::query!(
+        r#"
+        select id, student_id, variant_id, attempt_no, started_at, finished_at,
+               reopened_until, score, graded_report, solutions_released_at
+        from submission
+        where group_assignment_id = $1
+        order by started_at
+        "#,
+        group_assignment_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut submission_records = vec![];
+    for submission in submissions {
+        let dir = format!("submissions/{}", submission.id);
+
+        let items = sqlx::query!(
+            r#"
+            select id, data, file_name, extension, pages, message_id, created_at, updated_at
+            from submission_item
+            where submission_id = $1
+            order by created_at
+            "#,
+            submission.id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut item_records = vec![];
+        for (i, item) in items.into_iter().enumerate() {
+            let name = item
+                .file_name
+                .as_deref()
+                .map_or(format!("{}", item.id), sanitize);
+            let path = format!("{dir}/{}_{name}", i + 1);
+            add_file(&mut zip, &path, &item.data)?;
+            item_records.push(ItemRecord {
+                id: item.id,
+                path,
+                file_name: item.file_name,
+                extension: item.extension,
+                pages: item.pages,
+                message_id: item.message_id,
+                created_at: item.created_at,
+                updated_at: item.updated_at,
+            });
+        }
+
+        let graded_report = match submission.graded_report {
+            Some(report) => {
+                let path = format!("{dir}/graded_report.pdf");
+                add_file(&mut zip, &path, &report)?;
+                Some(path)
+            }
+            None => None,
+        };
+
+        let actions = sqlx::query_as!(
+            ActionRecord,
+            r#"
+            select action, submission_item_id, admin_telegram_id, admin_name, reason, created_at
+            from submission_action
+            where submission_id = $1
+            order by created_at
+            "#,
+            submission.id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        submission_records.push(SubmissionRecord {
+            id: submission.id,
+            student_id: submission.student_id,
+            variant_id: submission.variant_id,
+            attempt_no: submission.attempt_no,
+            started_at: submission.started_at,
+            finished_at: submission.finished_at,
+            reopened_until: submission.reopened_until,
+            score: submission.score,
+            graded_report,
+            solutions_released_at: submission.solutions_released_at,
+            items: item_records,
+            actions,
+        });
+    }
+
+    let volumes = sqlx::query!(
+        r#"
+        select volume_no, volume_count, data, delivered_at,
+               page_map as "page_map: Json<PageMap>"
+        from compiled_volume
+        where group_assignment_id = $1
+        order by volume_no
+        "#,
+        group_assignment_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut volume_records = vec![];
+    for volume in volumes {
+        let path = format!("compiled/volume_{}.pdf", volume.volume_no);
+        add_file(&mut zip, &path, &volume.data)?;
+        volume_records.push(VolumeRecord {
+            volume_no: volume.volume_no,
+            volume_count: volume.volume_count,
+            path,
+            delivered_at: volume.delivered_at,
+            page_map: volume.page_map.map(|Json(page_map)| page_map),
+        });
+    }
+
+    let graded_solutions = match rec.graded_solutions {
+        Some(pdf) => {
+            let path = "compiled/graded.pdf".to_string();
+            add_file(&mut zip, &path, &pdf)?;
+            Some(path)
+        }
+        None => None,
+    };
+
+    let manifest = Manifest {
+        format_version: FORMAT_VERSION,
+        exported_at: Utc::now(),
+        course: CourseRecord {
+            id: rec.course_id,
+            title: rec.course_title,
+            instructor: rec.instructor,
+        },
+        group: GroupRecord {
+            id: rec.group_id,
+            name: rec.group_name,
+            academic_year: rec.academic_year,
+        },
+        assignment: AssignmentRecord {
+            id: rec.assignment_id,
+            title: rec.assignment_title,
+            description: rec.description,
+            generator: rec.generator,
+            duration: rec.duration,
+        },
+        group_assignment: GroupAssignmentRecord {
+            id: group_assignment_id,
+            available_at: rec.available_at,
+            deadline: rec.deadline,
+            max_attempts: rec.max_attempts,
+            attempt_policy: rec.attempt_policy,
+            release_solutions: rec.release_solutions,
+            generator_parameters: rec.generator_parameters,
+            completed: rec.completed,
+            compiled_at: rec.compiled_at,
+            graded_solutions,
+        },
+        variants: variant_records,
+        students,
+        submissions: submission_records,
+        compiled_volumes: volume_records,
+    };
+    add_file(&mut zip, MANIFEST_PATH, &serde_json::to_vec_pretty(&manifest)?)?;
+
+    zip.finish()?;
+
+    Ok(path)
+}
+
+pub enum ImportError {
+    /// Not an archive made by `export_group_assignment`.
+    InvalidArchive(String),
+    /// The group assignment is already in the database.
+    AlreadyImported,
+    /// The course exists, but is administered from another chat.
+    NotAdministered,
+    Other(MyError),
+}
+
+impl From<MyError> for ImportError {
+    fn from(err: MyError) -> Self {
+        ImportError::Other(err)
+    }
+}
+
+impl From<sqlx::Error> for ImportError {
+    fn from(err: sqlx::Error) -> Self {
+        ImportError::Other(err.into())
+    }
+}
+
+impl From<std::io::Error> for ImportError {
+    fn from(err: std::io::Error) -> Self {
+        ImportError::Other(err.into())
+    }
+}
+
+pub struct ImportSummary {
+    pub title: String,
+    pub group_name: String,
+    pub students: usize,
+    pub submissions: usize,
+}
+
+/// Restores an archive made by `export_group_assignment`, keeping the ids. The
+/// course (by title), the group and the students that already exist are reused,
+/// a new course is administered from `admin_chat_id`.
+pub async fn import_archive(
+    data: impl Read + Seek,
+    admin_chat_id: ChatId,
+    pool: &PgPool,
+) -> Result<ImportSummary, ImportError> {
+    let mut zip = ZipArchive::new(data)
+        .map_err(|err| ImportError::InvalidArchive(err.to_string()))?;
+    let mut read = |path: &str| -> Result<Vec<u8>, ImportError> {
+        let mut file = zip
+            .by_name(path)
+            .map_err(|err| ImportError::InvalidArchive(format!("{path}: {err}")))?;
+        let mut data = vec![];
+        file.read_to_end(&mut data)?;
+        Ok(data)
+    };
+
+    let manifest: Manifest = serde_json::from_slice(&read(MANIFEST_PATH)?)
+        .map_err(|err| ImportError::InvalidArchive(format!("{MANIFEST_PATH}: {err}")))?;
+    if manifest.format_version != FORMAT_VERSION {
+        return Err(ImportError::InvalidArchive(format!(
+            "format version {}",
+            manifest.format_version
+        )));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    if sqlx::query_scalar!(
+        r#"select exists (select 1 from group_assignment where id = $1) as "exists!""#,
+        manifest.group_assignment.id
+    )
+    .fetch_one(&mut *tx)
+    .await?
+    {
+        return Err(ImportError::AlreadyImported);
+    }
+
+    let course_id = match sqlx::query_scalar!(
+        r#"select id from course where title = $1"#,
+        manifest.course.title
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    {
+        Some(course_id) => {
+            if !admin::administered_courses(admin_chat_id, pool).await?.contains(&course_id) {
+                return Err(ImportError::NotAdministered);
+            }
+            course_id
+        }
+        None => {
+            sqlx::query_scalar!(
+                r#"
+                insert into course (id, title, instructor, admin_chat_id)
+                values ($1, $2, $3, $4)
+                returning id
+                "#,
+                manifest.course.id,
+                manifest.course.title,
+                manifest.course.instructor,
+                admin_chat_id.0,
+            )
+            .fetch_one(&mut *tx)
+            .await?
+        }
+    };
+
+    let group_id = match sqlx::query_scalar!(
+        r#"select id from "group" where course_id = $1 and name = $2 and academic_year = $3"#,
+        course_id,
+        manifest.group.name,
+        manifest.group.academic_year,
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    {
+        Some(group_id) => group_id,
+        None => {
+            sqlx::query_scalar!(
+                r#"
+                insert into "group" (id, course_id, name, academic_year)
+                values ($1, $2, $3, $4)
+                returning id
+                "#,
+                manifest.group.id,
+                course_id,
+                manifest.group.name,
+                manifest.group.academic_year,
+            )
+            .fetch_one(&mut *tx)
+            .await?
+        }
+    };
+
+    let assignment = &manifest.assignment;
+    sqlx::query!(
+        r#"
+        insert into assignment (id, course_id, title, description, generator, duration)
+        values ($1, $2, $3, $4, $5, $6)
+        on conflict (id) do nothing
+        "#,
+        assignment.id,
+        course_id,
+        assignment.title,
+        assignment.description,
+        assignment.generator,
+        assignment.duration,
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    let group_assignment = &manifest.group_assignment;
+    let graded_solutions = group_assignment
+        .graded_solutions
+        .as_deref()
+        .map(&mut read)
+        .transpose()?;
+    sqlx::query!(
+        r#"
+        insert into group_assignment
+            (id, assignment_id, group_id, available_at, deadline, max_attempts,
+             attempt_policy, release_solutions, generator_parameters, completed, compiled_at,
+             graded_solutions)
+        values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        "#,
+        group_assignment.id,
+        assignment.id,
+        group_id,
+        group_assignment.available_at,
+        group_assignment.deadline,
+        group_assignment.max_attempts,
+        group_assignment.attempt_policy,
+        group_assignment.release_solutions,
+        group_assignment.generator_parameters,
+        group_assignment.completed,
+        group_assignment.compiled_at,
+        graded_solutions,
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    for variant in &manifest.variants {
+        let problem_code = String::from_utf8(read(&variant.problem_code)?)
+            .map_err(|err| ImportError::InvalidArchive(format!("{}: {err}", variant.problem_code)))?;
+        let solution_code = String::from_utf8(read(&variant.solution_code)?)
+            .map_err(|err| ImportError::InvalidArchive(format!("{}: {err}", variant.solution_code)))?;
+        let problem_images = variant
+            .problem_images
+            .iter()
+            .map(|path| read(path))
+            .collect::<Result<Vec<_>, _>>()?;
+        let solution_images = variant
+            .solution_images
+            .iter()
+            .map(|path| read(path))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        sqlx::query!(
+            r#"
+            insert into variant
+                (id, variant_no, assignment_id, problem_code, solution_code,
+                 problem_images, solution_images, fingerprint, created_at)
+            values ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            on conflict (id) do nothing
+            "#,
+            variant.id,
+            variant.variant_no,
+            assignment.id,
+            problem_code,
+            solution_code,
+            &problem_images,
+            &solution_images,
+            variant.fingerprint,
+            variant.created_at,
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    // ids of the archive to the ids of the students in the database
+    let mut student_ids = HashMap::new();
+    for student in &manifest.students {
+        let existing = sqlx::query_scalar!(
+            r#"
+            select id from student
+            where id = $1 or (telegram_id = $2 and group_id = $3)
+            limit 1
+            "#,
+            student.id,
+            student.telegram_id,
+            group_id,
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let student_id = match existing {
+            Some(student_id) => student_id,
+            None => {
+                sqlx::query_scalar!(
+                    r#"
+                    insert into student (id, group_id, telegram_id, chat_id, full_name, created_at)
+                    values ($1, $2, $3, $4, $5, $6)
+                    returning id
+                    "#,
+                    student.id,
+                    group_id,
+                    student.telegram_id,
+                    student.chat_id,
+                    student.full_name,
+                    student.created_at,
+                )
+                .fetch_one(&mut *tx)
+                .await?
+            }
+        };
+        student_ids.insert(student.id, student_id);
+    }
+
+    for submission in &manifest.submissions {
+        let Some(student_id) = student_ids.get(&submission.student_id) else {
+            return Err(ImportError::InvalidArchive(format!(
+                "unknown student {}",
+                submission.student_id
+            )));
+        };
+        let graded_report = submission.graded_report.as_deref().map(&mut read).transpose()?;
+
+        sqlx::query!(
+            r#"
+            insert into submission
+                (id, student_id, variant_id, group_assignment_id, attempt_no, started_at,
+                 finished_at, reopened_until, score, graded_report, solutions_released_at)
+            values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            "#,
+            submission.id,
+            student_id,
+            submission.variant_id,
+            group_assignment.id,
+            submission.attempt_no,
+            submission.started_at,
+            submission.finished_at,
+            submission.reopened_until,
+            submission.score,
+            graded_report,
+            submission.solutions_released_at,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for item in &submission.items {
+            let data = read(&item.path)?;
+            let hashes = similarity::hash_item(&data, &item.extension);
+            sqlx::query!(
+                r#"
+                insert into submission_item
+                    (id, submission_id, message_id, data, pages, extension, file_name,
+                     content_hash, page_hashes, created_at, updated_at)
+                values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                "#,
+                item.id,
+                submission.id,
+                item.message_id,
+                data,
+                item.pages,
+                item.extension,
+                item.file_name,
+                hashes.content_hash,
+                &hashes.page_hashes as &[Option<i64>],
+                item.created_at,
+                item.updated_at,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for action in &submission.actions {
+            sqlx::query!(
+                r#"
+                insert into submission_action
+                    (submission_id, action, submission_item_id, admin_telegram_id, admin_name,
+                     reason, created_at)
+                values ($1, $2, $3, $4, $5, $6, $7)
+                "#,
+                submission.id,
+                action.action,
+                action.submission_item_id,
+                action.admin_telegram_id,
+                action.admin_name,
+                action.reason,
+                action.created_at,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+
+    for volume in &manifest.compiled_volumes {
+        let data = read(&volume.path)?;
+        sqlx::query!(
+            r#"
+            insert into compiled_volume
+                (group_assignment_id, volume_no, volume_count, data, delivered_at, page_map)
+            values ($1, $2, $3, $4, $5, $6)
+            "#,
+            group_assignment.id,
+            volume.volume_no,
+            volume.volume_count,
+            data,
+            // already delivered by the exporting server
+            volume.delivered_at.or(Some(Utc::now())),
+            volume.page_map.as_ref().map(Json) as _,
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(ImportSummary {
+        title: assignment.title.clone(),
+        group_name: manifest.group.name.clone(),
+        students: manifest.students.len(),
+        submissions: manifest.submissions.len(),
+    })
+}
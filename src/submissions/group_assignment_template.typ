@@ -5,7 +5,7 @@
 
 
 #set par(justify: true)
-#set text(lang: "ru")
+#set text(lang: inputs.labels.lang)
 #set pagebreak(weak: true)
 
 #[
@@ -15,16 +15,28 @@
   *#inputs.title*
 
   #set text(12pt)
-  Группа #inputs.group_name
+  #inputs.labels.group #inputs.group_name
+
+  #if inputs.volume != "" [
+    #inputs.volume
+  ]
 ]
 
-Временные рамки:
-- Начало: #inputs.available_at
-- Время на выполнение: #inputs.duration
-- Крайний срок сдачи: #inputs.deadline
+#inputs.labels.time_frame
+- #inputs.labels.available_at: #inputs.available_at
+- #inputs.labels.duration: #inputs.duration
+- #inputs.labels.deadline: #inputs.deadline
 
 #inputs.description
 
+#if inputs.contents.len() != 0 [
+  #align(center)[*#inputs.labels.contents*]
+  #table(
+    columns: (1fr, auto), stroke: none,
+    ..inputs.contents.map(entry => (entry.title, entry.volume)).flatten(),
+  )
+]
+
 #outline(depth: 1)
 
 
@@ -36,6 +48,11 @@
     h = h.last()
   }
   align(center, h.body)
+  // the submission of the page, for the tools splitting the volume
+  let s = query(<submission>).filter(m => m.location().page() <= here().page())
+  if s.len() != 0 {
+    align(center, text(7pt, raw(s.last().value.id)))
+  }
   // let s = query(selector(metadata).after(h.location())).filter(it => it.value.children.first().text  == "h:").first()
   // let short = s.value.children.slice(1).first()
   // align(center, {
@@ -49,21 +66,25 @@
 
 #for submission in inputs.submissions [
   #pagebreak()
-  = Вариант #submission.variant.number: #submission.student_name
+  #metadata((id: submission.id)) <submission>
+  = #inputs.labels.variant #submission.variant.number: #submission.student_name #submission.attempt #if submission.in_progress [#inputs.labels.in_progress]
 
-  - Время начала: #submission.started_at
-  - Время окончания: #submission.finished_at
+  - #inputs.labels.submission_id: #raw(submission.id)
+  // outline entry with the id under the student's one
+  #place(hide(heading(level: 2)[#submission.id]))
+  - #inputs.labels.started_at: #submission.started_at
+  - #inputs.labels.finished_at: #submission.finished_at
 
   #let problem_n = submission.variant.problems.len();
   #table(
     align: center + horizon, rows: (10mm, 10mm), columns: range(submission.variant.problems.len() + 1).map(i => 1fr),
     ..range(1, problem_n+1).map(i => [#i]),
-    [Итог],
+    [#inputs.labels.total],
   )
 
   #if submission.variant.problems.len() != 0 [
     #pagebreak()
-    #align(center)[== Задачи]
+    #align(center)[== #inputs.labels.problems]
     #for doc in submission.variant.problems [
       #for i in range(1, doc.pages + 1) [
         #pagebreak()
@@ -74,7 +95,7 @@
   
   #if submission.variant.solutions.len() != 0 [
     #pagebreak()
-    #align(center)[== Примеры решений]
+    #align(center)[== #inputs.labels.sample_solutions]
     #for doc in submission.variant.solutions [
       #for i in range(1, doc.pages + 1) [
         #pagebreak()
@@ -84,7 +105,7 @@
   ]
 
   #pagebreak()
-  #align(center)[== Присланные решения]
+  #align(center)[== #inputs.labels.submitted_solutions]
   #if submission.solutions.len() != 0 [
     #for doc in submission.solutions [
       #for i in range(1, doc.pages + 1) [
@@ -93,6 +114,6 @@
       ]
     ]
   ] else [
-    (ничего не прислано)
+    #inputs.labels.nothing_submitted
   ]
 ]
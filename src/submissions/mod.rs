@@ -1,17 +1,35 @@
-use std::sync::Arc;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
-use sqlx::PgPool;
+use sqlx::{PgPool, types::Json};
 use teloxide::{
-    Bot,
+    ApiError, Bot, RequestError,
     dispatching::dialogue::InMemStorage,
+    payloads::SendDocumentSetters,
     prelude::{Dialogue, Requester},
-    types::{ChatId, InputFile},
+    sugar::request::RequestReplyExt,
+    types::{ChatId, InputFile, MessageId},
 };
 use uuid::Uuid;
 
-use crate::bot::{BotState, HandlerResult, MyError};
+use crate::{
+    bot::{BotState, HandlerResult, MyError, status},
+    locale::{self, Locale},
+    submissions::page_map::PageMap,
+};
 
 // pub mod compile;
+pub mod archive;
+pub mod page_map;
+pub mod similarity;
+
+/// Telegram's limit on the length of a document caption.
+const MAX_CAPTION_LENGTH: usize = 1024;
+
+/// Bots can upload files of up to 50 MB.
+const DEFAULT_MAX_VOLUME_SIZE_MB: usize = 45;
 
 pub async fn close_overdue_submissions(
     bot: Bot,
@@ -32,9 +50,16 @@ pub async fn close_overdue_submissions(
             AND ga.assignment_id = a.id
             AND st.id = s.student_id
             AND (
-                (now() > ga.deadline + ($1 * interval '1 minute'))
+                (
+                    s.reopened_until IS NULL
+                    AND (
+                        (now() > ga.deadline + ($1 * interval '1 minute'))
+                        OR
+                        (now() > s.started_at + a.duration::interval + ($1 * interval '1 minute'))
+                    )
+                )
                 OR
-                (now() > s.started_at + a.duration::interval + ($1 * interval '1 minute'))
+                (now() > s.reopened_until + ($1 * interval '1 minute'))
             )
             AND s.finished_at IS NULL
         RETURNING st.id as "student_id", st.chat_id, s.id as "submission_id", s.variant_id
@@ -46,17 +71,16 @@ pub async fn close_overdue_submissions(
 
     for record in records.into_iter() {
         let dialogue = Dialogue::new(storage.clone(), ChatId(record.chat_id));
+        let locale = Locale::of_chat(ChatId(record.chat_id), &pool).await;
 
-        bot.send_message(
-            ChatId(record.chat_id),
-            "Время закончилось, выполнение задания завершено.",
-        )
-        .await?;
+        bot.send_message(ChatId(record.chat_id), locale.messages().time_is_up)
+            .await?;
 
         crate::bot::assignment::commands::update_and_show_solutions(
             bot.clone(),
             dialogue.clone(),
             (record.submission_id, record.variant_id),
+            locale,
             pool.clone(),
         )
         .await?;
@@ -65,6 +89,7 @@ pub async fn close_overdue_submissions(
             bot.clone(),
             dialogue.clone(),
             record.submission_id,
+            locale,
             pool.clone(),
         )
         .await?;
@@ -73,55 +98,311 @@ pub async fn close_overdue_submissions(
     Ok(())
 }
 
+/// Compiles the solutions of the group assignments past their deadline and sends
+/// them to the admin chat.
 pub async fn process_finished_assignments(
     bot: Bot,
     pool: PgPool,
     admin_chat_id: ChatId,
     acceptable_overdue_minutes: f64,
 ) -> HandlerResult {
-    let not_compiled_group_assignment_ids = sqlx::query_scalar!(
+    let not_compiled_group_assignments = sqlx::query!(
         r#"
-            select id from group_assignment
+            select ga.id, c.admin_chat_id
+            from group_assignment ga
+            inner join assignment a on ga.assignment_id = a.id
+            inner join course c on a.course_id = c.id
             where 
-                now() > deadline + ($1 * interval '1 minute')
+                now() > ga.deadline + ($1 * interval '1 minute')
                 and
-                solutions is null
+                ga.compiled_at is null
         "#,
         acceptable_overdue_minutes
     )
     .fetch_all(&pool)
     .await?;
 
-    for group_assignment_id in not_compiled_group_assignment_ids.into_iter() {
-        let pdf = compile_group_assignment_pdf(group_assignment_id, pool.clone()).await?;
+    for group_assignment in not_compiled_group_assignments.into_iter() {
+        // courses without their own admin chat report to the default one
+        let chat_id = group_assignment
+            .admin_chat_id
+            .map(ChatId)
+            .unwrap_or(admin_chat_id);
+        let locale = Locale::of_chat(chat_id, &pool).await;
+        let volumes = compile_group_assignment_volumes(
+            group_assignment.id,
+            true,
+            locale,
+            max_volume_size(),
+            pool.clone(),
+        )
+        .await?;
 
+        let mut tx = pool.begin().await?;
+        for (i, volume) in volumes.iter().enumerate() {
+            sqlx::query!(
+                r#"
+                insert into compiled_volume
+                    (group_assignment_id, volume_no, volume_count, data, page_map)
+                values ($1, $2, $3, $4, $5)
+                "#,
+                group_assignment.id,
+                i as i32 + 1,
+                volumes.len() as i32,
+                volume.pdf,
+                Json(&volume.page_map) as _,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
         sqlx::query!(
+            r#"update group_assignment set compiled_at = now() where id = $1"#,
+            group_assignment.id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+    }
+
+    deliver_volumes(bot, pool, admin_chat_id).await
+}
+
+/// Sends the students the solutions of their variants, as configured by
+/// `group_assignment.release_solutions`.
+pub async fn release_solutions(bot: Bot, pool: PgPool) -> HandlerResult {
+    let submissions = sqlx::query!(
+        r#"
+        select s.id, s.variant_id, st.chat_id, a.title, v.variant_no
+        from submission s
+        inner join group_assignment ga on s.group_assignment_id = ga.id
+        inner join assignment a on ga.assignment_id = a.id
+        inner join student st on s.student_id = st.id
+        inner join variant v on s.variant_id = v.id
+        where s.solutions_released_at is null
+            and s.finished_at is not null
+            and cardinality(v.solution_images) > 0
+            and (
+                (ga.release_solutions = 'after_deadline' and now() > ga.deadline)
+                or
+                (ga.release_solutions = 'after_grading'
+                    and (s.score is not null or s.graded_report is not null))
+            )
+        order by s.finished_at
+        "#
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    for submission in submissions {
+        let chat_id = ChatId(submission.chat_id);
+        let locale = Locale::of_chat(chat_id, &pool).await;
+
+        let sent = async {
+            bot.send_message(
+                chat_id,
+                locale::fill(
+                    locale.messages().solutions_released,
+                    &[("title", &submission.title), ("variant", &submission.variant_no)],
+                ),
+            )
+            .await?;
+            crate::bot::assignment::send_solutions(
+                bot.clone(),
+                chat_id,
+                submission.variant_id,
+                pool.clone(),
+            )
+            .await
+        };
+        match sent.await {
+            Ok(()) => {}
+            // the student will find the solutions in /my_results
+            Err(err)
+                if err
+                    .downcast_ref::<RequestError>()
+                    .is_some_and(|err| matches!(err, RequestError::Api(ApiError::BotBlocked))) => {}
+            Err(err) => {
+                log::error!("releasing solutions of submission {} failed with {err}", submission.id);
+                continue;
+            }
+        }
+
+        sqlx::query!(
+            r#"update submission set solutions_released_at = now() where id = $1"#,
+            submission.id
+        )
+        .execute(&pool)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Largest size of a compiled volume in bytes, `MAX_VOLUME_SIZE_MB` megabytes.
+pub fn max_volume_size() -> usize {
+    let megabytes = std::env::var("MAX_VOLUME_SIZE_MB")
+        .ok()
+        .and_then(|megabytes| megabytes.parse().ok())
+        .unwrap_or(DEFAULT_MAX_VOLUME_SIZE_MB);
+    megabytes * 1024 * 1024
+}
+
+/// Sends the compiled volumes that have not been delivered yet. A failed volume
+/// is retried on the next call, the following volumes of the same group
+/// assignment wait for it.
+async fn deliver_volumes(bot: Bot, pool: PgPool, admin_chat_id: ChatId) -> HandlerResult {
+    let volumes = sqlx::query!(
+        r#"
+        select cv.id, cv.group_assignment_id, cv.volume_no, cv.volume_count,
+               a.title, g.name as "group_name", c.admin_chat_id
+        from compiled_volume cv
+        inner join group_assignment ga on cv.group_assignment_id = ga.id
+        inner join assignment a on ga.assignment_id = a.id
+        inner join course c on a.course_id = c.id
+        inner join "group" g on ga.group_id = g.id
+        where cv.delivered_at is null
+        order by cv.group_assignment_id, cv.volume_no
+        "#
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    let mut failed = HashSet::new();
+    for volume in volumes {
+        if failed.contains(&volume.group_assignment_id) {
+            continue;
+        }
+
+        let chat_id = volume.admin_chat_id.map(ChatId).unwrap_or(admin_chat_id);
+        let locale = Locale::of_chat(chat_id, &pool).await;
+        let t = locale.messages();
+
+        let mut caption = if volume.volume_count == 1 {
+            locale::fill(
+                t.solutions_caption,
+                &[("title", &volume.title), ("group", &volume.group_name)],
+            )
+        } else {
+            locale::fill(
+                t.solutions_volume_caption,
+                &[
+                    ("title", &volume.title),
+                    ("group", &volume.group_name),
+                    ("volume", &volume.volume_no),
+                    ("count", &volume.volume_count),
+                ],
+            )
+        };
+        // the similarity report accompanies the first volume
+        let mut reply = None;
+        if volume.volume_no == 1 {
+            // without the report the volume is still sent, with the plain caption
+            match similarity::similarity_report(volume.group_assignment_id, locale, &pool).await {
+                Ok(report) => {
+                    let with_report = format!("{caption}\n\n{report}");
+                    if with_report.chars().count() <= MAX_CAPTION_LENGTH {
+                        caption = with_report;
+                    } else {
+                        caption = format!("{caption}\n\n{}", t.similarity_see_reply);
+                        reply = Some(report);
+                    }
+                }
+                Err(err) => log::error!(
+                    "similarity report of group assignment {} failed with {err}",
+                    volume.group_assignment_id
+                ),
+            }
+        }
+
+        let rec = sqlx::query!(
             r#"
-            update group_assignment
-            set solutions = $1
-            where id = $2
+            select data, page_map as "page_map: Json<PageMap>"
+            from compiled_volume
+            where id = $1
             "#,
-            pdf,
-            group_assignment_id
+            volume.id
         )
-        .execute(&pool)
+        .fetch_one(&pool)
         .await?;
+        let file_name = if volume.volume_count == 1 {
+            format!("solutions_{}", volume.group_assignment_id)
+        } else {
+            format!("solutions_{}_{}", volume.group_assignment_id, volume.volume_no)
+        };
 
-        bot.send_document(
-            admin_chat_id,
-            InputFile::memory(pdf.clone())
-                .file_name(format!("solutions_{group_assignment_id}.pdf")),
+        let sent = async {
+            let document = bot
+                .send_document(
+                    chat_id,
+                    InputFile::memory(rec.data).file_name(format!("{file_name}.pdf")),
+                )
+                .caption(caption)
+                .await?;
+            if let Some(Json(page_map)) = rec.page_map {
+                send_page_map(&bot, chat_id, &page_map, &file_name, document.id).await?;
+            }
+            Ok::<_, MyError>(document)
+        };
+        let document = match sent.await {
+            Ok(document) => document,
+            Err(err) => {
+                log::error!(
+                    "sending volume {} of group assignment {} failed with {err}",
+                    volume.volume_no,
+                    volume.group_assignment_id
+                );
+                failed.insert(volume.group_assignment_id);
+                continue;
+            }
+        };
+
+        sqlx::query!(
+            r#"update compiled_volume set delivered_at = now() where id = $1"#,
+            volume.id
         )
+        .execute(&pool)
         .await?;
+
+        if let Some(report) = reply {
+            bot.send_message(chat_id, status::truncate_message(report))
+                .reply_to(document.id)
+                .await?;
+        }
     }
 
     Ok(())
 }
 
-pub async fn compile_group_assignment_pdf(
+/// The page map of a volume as a JSON file, in reply to the volume.
+pub async fn send_page_map(
+    bot: &Bot,
+    chat_id: ChatId,
+    page_map: &PageMap,
+    file_name: &str,
+    volume_message_id: MessageId,
+) -> HandlerResult {
+    bot.send_document(
+        chat_id,
+        InputFile::memory(serde_json::to_vec_pretty(page_map)?)
+            .file_name(format!("{file_name}.json")),
+    )
+    .reply_to(volume_message_id)
+    .await?;
+    Ok(())
+}
+
+/// Compiles the solutions of the group assignment into volumes of at most
+/// `max_size` bytes. Every volume starts with the same cover and contents; a
+/// single submission larger than `max_size` still gets a volume of its own.
+/// Unfinished submissions are left out unless `include_in_progress`.
+pub async fn compile_group_assignment_volumes(
     group_assignment_id: Uuid,
+    include_in_progress: bool,
+    locale: Locale,
+    max_size: usize,
     pool: PgPool,
-) -> Result<Vec<u8>, MyError> {
+) -> Result<Vec<CompiledVolume>, MyError> {
+    let labels = &locale.messages().pdf;
     let (title, description, duration, available_at, deadline, group_name) = {
         let rec = sqlx::query!(
             r#"
@@ -148,9 +429,14 @@ pub async fn compile_group_assignment_pdf(
 
     let submission_ids = sqlx::query_scalar!(
         r#"
-            select id from submission where group_assignment_id = $1
+            select s.id
+            from submission s
+                join student st on s.student_id = st.id
+            where s.group_assignment_id = $1 and ($2 or s.finished_at is not null)
+            order by st.full_name, s.attempt_no
         "#,
-        group_assignment_id
+        group_assignment_id,
+        include_in_progress,
     )
     .fetch_all(&pool)
     .await?;
@@ -164,10 +450,16 @@ pub async fn compile_group_assignment_pdf(
                         v.problem_images,
                         v.solution_images,
                         s.started_at, 
-                        s.finished_at
+                        s.finished_at,
+                        s.attempt_no,
+                        ga.max_attempts,
+                        exists (
+                            select 1 from counted_submission cs where cs.submission_id = s.id
+                        ) as "counted!"
                 from submission s 
                     join variant v on s.variant_id = v.id
                     join student st on s.student_id = st.id
+                    join group_assignment ga on s.group_assignment_id = ga.id
                 where s.id = $1
                 "#,
             submission_id
@@ -204,8 +496,17 @@ pub async fn compile_group_assignment_pdf(
         .fetch_all(&pool)
         .await?;
 
+        let attempt = match (rec.max_attempts, rec.counted) {
+            (1, _) => "".to_string(),
+            (_, true) => locale::fill(labels.attempt_counted, &[("attempt", &rec.attempt_no)]),
+            (_, false) => locale::fill(labels.attempt, &[("attempt", &rec.attempt_no)]),
+        };
+
         submissions.push(Submission {
+            id: submission_id.to_string(),
             student_name: rec.student_name,
+            attempt,
+            in_progress: rec.finished_at.is_none(),
             variant,
             solutions: solutions
                 .into_iter()
@@ -220,7 +521,7 @@ pub async fn compile_group_assignment_pdf(
                 .format("%Y-%m-%d %H:%M")
                 .to_string(),
             finished_at: rec.finished_at.map_or(
-                "(не завершено)".to_string(),
+                labels.not_finished.to_string(),
                 |finished_at| {
                     finished_at
                         .with_timezone(&chrono::Local)
@@ -231,6 +532,153 @@ pub async fn compile_group_assignment_pdf(
         });
     }
 
+    let cover = GroupAssignmnet {
+        title,
+        description,
+        group_name,
+        available_at: available_at
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string(),
+        duration: duration.map_or(
+            labels.no_time_limit.to_string(),
+            |duration| duration.format(labels.duration_format).to_string(),
+        ),
+        deadline: deadline.map_or(
+            labels.no_deadline.to_string(),
+            |deadline| {
+                deadline
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            },
+        ),
+        submissions: vec![],
+        volume: "".to_string(),
+        contents: vec![],
+        labels: Labels {
+            lang: labels.lang.to_string(),
+            group: labels.group.to_string(),
+            time_frame: labels.time_frame.to_string(),
+            available_at: labels.available_at.to_string(),
+            duration: labels.duration.to_string(),
+            deadline: labels.deadline.to_string(),
+            variant: labels.variant.to_string(),
+            submission_id: labels.submission_id.to_string(),
+            started_at: labels.started_at.to_string(),
+            finished_at: labels.finished_at.to_string(),
+            total: labels.total.to_string(),
+            problems: labels.problems.to_string(),
+            sample_solutions: labels.sample_solutions.to_string(),
+            submitted_solutions: labels.submitted_solutions.to_string(),
+            nothing_submitted: labels.nothing_submitted.to_string(),
+            in_progress: labels.in_progress.to_string(),
+            contents: labels.contents.to_string(),
+        },
+    };
+
+    let mut chunks = pack_submissions(submissions, max_size);
+    loop {
+        let volume_count = chunks.len();
+        let contents = chunks
+            .iter()
+            .enumerate()
+            .flat_map(|(i, chunk)| {
+                chunk.iter().map(move |submission| ContentsEntry {
+                    title: format!(
+                        "{} {}: {} {} {}",
+                        labels.variant,
+                        submission.variant.number,
+                        submission.student_name,
+                        submission.attempt,
+                        if submission.in_progress { labels.in_progress } else { "" },
+                    ),
+                    volume: locale::fill(labels.contents_volume, &[("volume", &(i + 1))]),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let volumes = chunks
+            .iter()
+            .enumerate()
+            .map(|(i, chunk)| {
+                let input = GroupAssignmnet {
+                    submissions: chunk.clone(),
+                    volume: if volume_count == 1 {
+                        "".to_string()
+                    } else {
+                        locale::fill(
+                            labels.volume,
+                            &[("volume", &(i + 1)), ("count", &volume_count)],
+                        )
+                    },
+                    contents: if volume_count == 1 { vec![] } else { contents.clone() },
+                    ..cover.clone()
+                };
+                compile_volume(input, group_assignment_id, i + 1, volume_count)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // the sizes of the uploads are only an estimate of the size of the volume
+        match volumes
+            .iter()
+            .zip(&chunks)
+            .position(|(volume, chunk)| volume.pdf.len() > max_size && chunk.len() > 1)
+        {
+            Some(i) => {
+                let mut chunk = chunks.remove(i);
+                let second_half = chunk.split_off(chunk.len() / 2);
+                chunks.insert(i, second_half);
+                chunks.insert(i, chunk);
+            }
+            None => {
+                for (volume, chunk) in volumes.iter().zip(&chunks) {
+                    if volume.pdf.len() > max_size {
+                        log::warn!(
+                            "submission {} alone takes {} bytes, more than the volume size",
+                            chunk[0].id,
+                            volume.pdf.len()
+                        );
+                    }
+                }
+                return Ok(volumes);
+            }
+        }
+    }
+}
+
+/// Consecutive submissions whose uploads add up to at most `max_size` bytes.
+fn pack_submissions(submissions: Vec<Submission>, max_size: usize) -> Vec<Vec<Submission>> {
+    let mut chunks: Vec<Vec<Submission>> = vec![vec![]];
+    let mut chunk_size = 0;
+    for submission in submissions {
+        let size = submission.size();
+        if chunk_size + size > max_size && !chunks.last().unwrap().is_empty() {
+            chunks.push(vec![]);
+            chunk_size = 0;
+        }
+        chunk_size += size;
+        chunks.last_mut().unwrap().push(submission);
+    }
+    chunks
+}
+
+pub struct CompiledVolume {
+    pub pdf: Vec<u8>,
+    pub page_map: PageMap,
+}
+
+fn compile_volume(
+    input: GroupAssignmnet,
+    group_assignment_id: Uuid,
+    volume: usize,
+    volume_count: usize,
+) -> Result<CompiledVolume, MyError> {
+    let student_names = input
+        .submissions
+        .iter()
+        .map(|submission| (submission.id.clone(), submission.student_name.clone()))
+        .collect::<HashMap<_, _>>();
     let template_text = include_str!("group_assignment_template.typ");
 
     let engine = typst_as_lib::TypstEngine::builder()
@@ -243,44 +691,37 @@ pub async fn compile_group_assignment_pdf(
         )
         .build();
 
-    let doc = engine
-        .compile_with_input(GroupAssignmnet {
-            title,
-            description,
-            group_name,
-            available_at: available_at
-                .with_timezone(&chrono::Local)
-                .format("%Y-%m-%d %H:%M")
-                .to_string(),
-            duration: duration.map_or(
-                "(без ограничения по времени)".to_string(),
-                |duration| duration.format("%H ч. %M мин.").to_string(),
-            ),
-            deadline: deadline.map_or(
-                "(без крайнего срока)".to_string(),
-                |deadline| {
-                    deadline
-                        .with_timezone(&chrono::Local)
-                        .format("%Y-%m-%d %H:%M")
-                        .to_string()
-                },
-            ),
-            submissions,
-        })
-        .output?;
+    let doc: PagedDocument = engine.compile_with_input(input).output?;
 
-    let options = Default::default();
-    let pdf = typst_pdf::pdf(&doc, &options);
+    let page_map = PageMap {
+        group_assignment_id: group_assignment_id.to_string(),
+        volume,
+        volume_count,
+        page_count: doc.pages.len(),
+        submissions: page_map::page_ranges(
+            page_map::submission_first_pages(&doc),
+            |id| student_names.get(id).cloned().unwrap_or_default(),
+            doc.pages.len(),
+        ),
+    };
 
-    match pdf {
-        Ok(pdf) => return Ok(pdf),
+    let options = Default::default();
+    let pdf = match typst_pdf::pdf(&doc, &options) {
+        Ok(pdf) => pdf,
         Err(_) => return Err("Could not produce pdf.".into()),
-    }
-    // std::fs::write("./output.pdf", pdf).expect("Could not write pdf.");
+    };
+
+    Ok(CompiledVolume {
+        pdf: page_map::embed(pdf, &page_map)?,
+        page_map,
+    })
 }
 
 use derive_typst_intoval::{IntoDict, IntoValue};
-use typst::foundations::{Bytes, Dict, IntoValue};
+use typst::{
+    foundations::{Bytes, Dict, IntoValue},
+    layout::PagedDocument,
+};
 use typst_as_lib::typst_kit_options::TypstKitFontOptions;
 
 #[derive(Debug, Clone, IntoValue, IntoDict)]
@@ -292,20 +733,65 @@ struct GroupAssignmnet {
     duration: String,
     deadline: String,
     submissions: Vec<Submission>,
+    /// "Volume 2 of 3", empty if there is a single volume.
+    volume: String,
+    /// Submissions of all the volumes, empty if there is a single volume.
+    contents: Vec<ContentsEntry>,
+    labels: Labels,
 }
 impl From<GroupAssignmnet> for Dict {
     fn from(value: GroupAssignmnet) -> Self {
         value.into_dict()
     }
 }
+/// Texts of the template, see `locale::PdfLabels`.
+#[derive(Debug, Clone, IntoValue, IntoDict)]
+struct Labels {
+    lang: String,
+    group: String,
+    time_frame: String,
+    available_at: String,
+    duration: String,
+    deadline: String,
+    variant: String,
+    submission_id: String,
+    started_at: String,
+    finished_at: String,
+    total: String,
+    problems: String,
+    sample_solutions: String,
+    submitted_solutions: String,
+    nothing_submitted: String,
+    in_progress: String,
+    contents: String,
+}
+#[derive(Debug, Clone, IntoValue, IntoDict)]
+struct ContentsEntry {
+    title: String,
+    volume: String,
+}
 #[derive(Debug, Clone, IntoValue, IntoDict)]
 struct Submission {
+    id: String,
     student_name: String,
+    attempt: String,
+    in_progress: bool,
     variant: Variant,
     solutions: Vec<Doc>,
     started_at: String,
     finished_at: String,
 }
+impl Submission {
+    /// Size of the embedded files, which make up most of the compiled PDF.
+    fn size(&self) -> usize {
+        self.solutions
+            .iter()
+            .chain(&self.variant.problems)
+            .chain(&self.variant.solutions)
+            .map(|doc| doc.data.len())
+            .sum()
+    }
+}
 #[derive(Debug, Clone, IntoValue, IntoDict)]
 struct Variant {
     number: i32,
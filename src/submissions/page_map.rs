@@ -0,0 +1,118 @@
+use lopdf::{Object, dictionary};
+use serde::{Deserialize, Serialize};
+use typst::{
+    foundations::{Element, Selector, Value},
+    introspection::MetadataElem,
+    layout::PagedDocument,
+};
+
+use crate::bot::MyError;
+
+/// Key of the page map in the document information dictionary.
+const INFO_KEY: &str = "DiffurcheckPageMap";
+
+/// Pages of every submission in a compiled volume. Embedded into the PDF and sent
+/// alongside it as JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageMap {
+    pub group_assignment_id: String,
+    pub volume: usize,
+    pub volume_count: usize,
+    pub page_count: usize,
+    pub submissions: Vec<SubmissionPages>,
+}
+
+/// Page numbers start from 1, `last_page` is inclusive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmissionPages {
+    pub submission_id: String,
+    pub student_name: String,
+    pub first_page: usize,
+    pub last_page: usize,
+}
+
+/// First pages of the submissions, found by the `#metadata((id: ..)) <submission>`
+/// the template puts at the start of every submission.
+pub fn submission_first_pages(doc: &PagedDocument) -> Vec<(String, usize)> {
+    doc.introspector
+        .query(&Selector::Elem(Element::of::<MetadataElem>(), None))
+        .iter()
+        .filter_map(|content| {
+            let metadata = content.to_packed::<MetadataElem>()?;
+            let Value::Dict(dict) = &metadata.value else {
+                return None;
+            };
+            let Ok(Value::Str(id)) = dict.get("id") else {
+                return None;
+            };
+            let page = doc.introspector.page(content.location()?).get();
+            Some((id.to_string(), page))
+        })
+        .collect()
+}
+
+/// Every submission lasts until the next one starts.
+pub fn page_ranges(
+    first_pages: Vec<(String, usize)>,
+    student_names: impl Fn(&str) -> String,
+    page_count: usize,
+) -> Vec<SubmissionPages> {
+    let next_first_pages = first_pages
+        .iter()
+        .skip(1)
+        .map(|(_, page)| *page)
+        .chain([page_count + 1])
+        .collect::<Vec<_>>();
+
+    first_pages
+        .into_iter()
+        .zip(next_first_pages)
+        .map(|((submission_id, first_page), next_first_page)| SubmissionPages {
+            student_name: student_names(&submission_id),
+            submission_id,
+            first_page,
+            last_page: next_first_page - 1,
+        })
+        .collect()
+}
+
+/// Adds page labels (`<submission id>/<page>`, roman numerals for the cover) and
+/// the JSON page map in the document information dictionary. The outline entries
+/// come from the headings of the template.
+pub fn embed(pdf: Vec<u8>, page_map: &PageMap) -> Result<Vec<u8>, MyError> {
+    let mut doc = lopdf::Document::load_mem(&pdf)?;
+
+    let mut nums = vec![];
+    if page_map.submissions.first().is_none_or(|submission| submission.first_page > 1) {
+        nums.push(Object::Integer(0));
+        nums.push(dictionary! { "S" => Object::Name(b"r".to_vec()) }.into());
+    }
+    for submission in &page_map.submissions {
+        nums.push(Object::Integer(submission.first_page as i64 - 1));
+        nums.push(
+            dictionary! {
+                "S" => Object::Name(b"D".to_vec()),
+                "P" => Object::string_literal(format!("{}/", submission.submission_id)),
+            }
+            .into(),
+        );
+    }
+    doc.catalog_mut()?
+        .set("PageLabels", dictionary! { "Nums" => nums });
+
+    let info_id = match doc.trailer.get(b"Info").and_then(Object::as_reference) {
+        Ok(info_id) => info_id,
+        Err(_) => {
+            let info_id = doc.add_object(dictionary! {});
+            doc.trailer.set("Info", info_id);
+            info_id
+        }
+    };
+    doc.get_object_mut(info_id)?
+        .as_dict_mut()?
+        .set(INFO_KEY, Object::string_literal(serde_json::to_string(page_map)?));
+
+    let mut out = Vec::new();
+    doc.save_to(&mut out)?;
+    Ok(out)
+}
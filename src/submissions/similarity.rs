@@ -0,0 +1,202 @@
+use image::{DynamicImage, GrayImage, ImageFormat, imageops::FilterType};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    bot::MyError,
+    locale::{self, Locale},
+};
+
+/// Pages whose hashes differ in at most this many bits are reported as similar.
+const MAX_DISTANCE: u32 = 5;
+
+/// Hashes with fewer set (or unset) bits come from nearly blank pages, which all
+/// look alike.
+const MIN_HASH_DETAIL: u32 = 8;
+
+pub struct ItemHashes {
+    pub content_hash: Vec<u8>,
+    pub page_hashes: Vec<Option<i64>>,
+}
+
+/// Hashes of an uploaded file stored in `submission_item`.
+pub fn hash_item(data: &[u8], mime: &str) -> ItemHashes {
+    let page_hashes = match mime {
+        "application/pdf" => pdf_page_images(data)
+            .into_iter()
+            .map(|page| page.map(|page| dhash(&page)))
+            .collect(),
+        _ => vec![image::load_from_memory(data).ok().map(|image| dhash(&image))],
+    };
+
+    ItemHashes {
+        content_hash: Sha256::digest(data).to_vec(),
+        page_hashes,
+    }
+}
+
+/// 64-bit difference hash: the image is shrunk to 9×8 grayscale pixels and every
+/// bit tells whether a pixel is darker than its right neighbour.
+fn dhash(image: &DynamicImage) -> i64 {
+    let small: GrayImage = image::imageops::resize(&image.to_luma8(), 9, 8, FilterType::Triangle);
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if small.get_pixel(x, y).0[0] < small.get_pixel(x + 1, y).0[0] {
+                hash |= 1;
+            }
+        }
+    }
+    hash as i64
+}
+
+/// The largest image of every page. Scans and photos converted to PDF have one
+/// image per page; pages without a decodable image are `None`.
+fn pdf_page_images(data: &[u8]) -> Vec<Option<DynamicImage>> {
+    let Ok(doc) = lopdf::Document::load_mem(data) else {
+        return vec![];
+    };
+
+    doc.get_pages()
+        .into_values()
+        .map(|page_id| {
+            let images = doc.get_page_images(page_id).ok()?;
+            let largest = images.into_iter().max_by_key(|image| image.width * image.height)?;
+            let filters = largest.filters.clone().unwrap_or_default();
+
+            if filters.iter().any(|filter| filter == "DCTDecode") {
+                return image::load_from_memory_with_format(largest.content, ImageFormat::Jpeg).ok();
+            }
+
+            // raw 8-bit pixels, usually deflated
+            if largest.bits_per_component != Some(8) {
+                return None;
+            }
+            let pixels = doc.get_object(largest.id).ok()?.as_stream().ok()?.decompressed_content().ok()?;
+            let (width, height) = (largest.width as u32, largest.height as u32);
+            match largest.color_space.as_deref() {
+                Some("DeviceRGB") => image::RgbImage::from_raw(width, height, pixels).map(DynamicImage::from),
+                Some("DeviceGray") => GrayImage::from_raw(width, height, pixels).map(DynamicImage::from),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+fn is_informative(hash: i64) -> bool {
+    let ones = hash.count_ones();
+    (MIN_HASH_DETAIL..=64 - MIN_HASH_DETAIL).contains(&ones)
+}
+
+/// Computes the hashes of the items uploaded before hashing was introduced.
+async fn hash_missing(group_assignment_id: Uuid, pool: &PgPool) -> Result<(), MyError> {
+    let items = sqlx::query!(
+        r#"
+        select si.id, si.data, si.extension
+        from submission_item si
+        inner join submission s on si.submission_id = s.id
+        where s.group_assignment_id = $1 and si.content_hash is null
+        "#,
+        group_assignment_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for item in items {
+        let hashes = hash_item(&item.data, &item.extension);
+        sqlx::query!(
+            r#"update submission_item set content_hash = $1, page_hashes = $2 where id = $3"#,
+            hashes.content_hash,
+            &hashes.page_hashes as &[Option<i64>],
+            item.id,
+        )
+        .execute(pool)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Identical files and near-identical pages uploaded by different students for
+/// the group assignment.
+pub async fn similarity_report(
+    group_assignment_id: Uuid,
+    locale: Locale,
+    pool: &PgPool,
+) -> Result<String, MyError> {
+    let t = locale.messages();
+    hash_missing(group_assignment_id, pool).await?;
+
+    let items = sqlx::query!(
+        r#"
+        select si.id, si.file_name, si.content_hash as "content_hash!",
+               si.page_hashes as "page_hashes!: Vec<Option<i64>>",
+               s.student_id, st.full_name
+        from submission_item si
+        inner join submission s on si.submission_id = s.id
+        inner join student st on s.student_id = st.id
+        where s.group_assignment_id = $1
+        order by st.full_name, si.created_at
+        "#,
+        group_assignment_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let file = |i: usize| {
+        locale::fill(
+            t.similarity_file,
+            &[
+                ("name", &items[i].full_name),
+                ("file", &items[i].file_name.clone().unwrap_or_else(|| items[i].id.to_string())),
+            ],
+        )
+    };
+    let page = |i: usize, page: usize| {
+        locale::fill(t.similarity_page, &[("file", &file(i)), ("page", &(page + 1))])
+    };
+
+    let mut lines = vec![];
+    for a in 0..items.len() {
+        for b in a + 1..items.len() {
+            if items[a].student_id == items[b].student_id {
+                continue;
+            }
+
+            if items[a].content_hash == items[b].content_hash {
+                lines.push(locale::fill(
+                    t.similarity_identical,
+                    &[("a", &file(a)), ("b", &file(b))],
+                ));
+                continue;
+            }
+
+            for (page_a, hash_a) in items[a].page_hashes.iter().enumerate() {
+                for (page_b, hash_b) in items[b].page_hashes.iter().enumerate() {
+                    let (Some(hash_a), Some(hash_b)) = (*hash_a, *hash_b) else {
+                        continue;
+                    };
+                    let distance = (hash_a ^ hash_b).count_ones();
+                    if distance <= MAX_DISTANCE && is_informative(hash_a) && is_informative(hash_b) {
+                        lines.push(locale::fill(
+                            t.similarity_similar,
+                            &[
+                                ("a", &page(a, page_a)),
+                                ("b", &page(b, page_b)),
+                                ("distance", &distance),
+                            ],
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(if lines.is_empty() {
+        t.similarity_none.to_string()
+    } else {
+        format!("{}\n{}", t.similarity_header, lines.join("\n"))
+    })
+}
@@ -0,0 +1,399 @@
+//! Formulas of the generated tasks. The same expression is printed in Typst and
+//! evaluated by the checks of the generators, so a printed formula cannot differ
+//! from the checked one.
+//!
+//! The constructors keep the expressions in a normal form: numbers are folded into
+//! the coefficient of a product, equal factors into powers, exponents into one
+//! `e^(…)`, and like summands are collected.
+
+use std::{
+    fmt,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+use num::{One, Signed, Zero, rational::Rational64};
+
+pub type Q = Rational64;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Func {
+    Exp,
+    Ln,
+    /// `ln abs(…)`.
+    LnAbs,
+    Sin,
+    Cos,
+    Tg,
+    Ctg,
+    Arctan,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Expr {
+    Number(Q),
+    Var(&'static str),
+    Sum(Vec<Expr>),
+    /// The coefficient times the factors raised to non-zero powers, the negative
+    /// ones are printed in the denominator.
+    Product(Q, Vec<(Expr, i32)>),
+    Apply(Func, Box<Expr>),
+}
+
+pub fn num(n: i64) -> Expr {
+    Expr::Number(Q::from_integer(n))
+}
+
+pub fn rational(q: Q) -> Expr {
+    Expr::Number(q)
+}
+
+pub fn var(name: &'static str) -> Expr {
+    Expr::Var(name)
+}
+
+fn apply(func: Func, arg: Expr) -> Expr {
+    Expr::Apply(func, Box::new(arg))
+}
+
+/// `e^arg`, `1` for `arg = 0`.
+pub fn exp(arg: Expr) -> Expr {
+    if arg.is_zero() { num(1) } else { apply(Func::Exp, arg) }
+}
+
+pub fn ln(arg: Expr) -> Expr {
+    apply(Func::Ln, arg)
+}
+
+pub fn ln_abs(arg: Expr) -> Expr {
+    apply(Func::LnAbs, arg)
+}
+
+pub fn sin(arg: Expr) -> Expr {
+    apply(Func::Sin, arg)
+}
+
+pub fn cos(arg: Expr) -> Expr {
+    apply(Func::Cos, arg)
+}
+
+pub fn tg(arg: Expr) -> Expr {
+    apply(Func::Tg, arg)
+}
+
+pub fn ctg(arg: Expr) -> Expr {
+    apply(Func::Ctg, arg)
+}
+
+pub fn arctan(arg: Expr) -> Expr {
+    apply(Func::Arctan, arg)
+}
+
+/// Sum of the summands, `0` if there are none.
+pub fn sum(summands: impl IntoIterator<Item = Expr>) -> Expr {
+    let mut collected: Vec<(Q, Expr)> = vec![];
+    for summand in summands.into_iter().flat_map(Expr::summands) {
+        let (coeff, rest) = summand.split_coefficient();
+        match collected.iter_mut().find(|(_, other)| *other == rest) {
+            Some((total, _)) => *total += coeff,
+            None => collected.push((coeff, rest)),
+        }
+    }
+
+    let mut summands = collected
+        .into_iter()
+        .filter(|(coeff, _)| !coeff.is_zero())
+        .map(|(coeff, rest)| rational(coeff) * rest)
+        .collect::<Vec<_>>();
+    match summands.len() {
+        0 => num(0),
+        1 => summands.pop().unwrap(),
+        _ => Expr::Sum(summands),
+    }
+}
+
+/// Product of the factors, the numbers go to the coefficient, a number times a sum
+/// is expanded.
+pub fn product(factors: impl IntoIterator<Item = (Expr, i32)>) -> Expr {
+    let mut coeff = Q::one();
+    let mut collected: Vec<(Expr, i32)> = vec![];
+    let mut exponent = vec![];
+    fn push(base: Expr, power: i32, collected: &mut Vec<(Expr, i32)>) {
+        match collected.iter_mut().find(|(other, _)| *other == base) {
+            Some((_, total)) => *total += power,
+            None => collected.push((base, power)),
+        }
+    }
+    for (factor, power) in factors {
+        match factor {
+            Expr::Number(q) => coeff *= q.pow(power),
+            Expr::Product(c, inner) => {
+                coeff *= c.pow(power);
+                for (base, p) in inner {
+                    match base {
+                        Expr::Apply(Func::Exp, arg) => exponent.push(*arg * num((p * power) as i64)),
+                        base => push(base, p * power, &mut collected),
+                    }
+                }
+            }
+            Expr::Apply(Func::Exp, arg) => exponent.push(*arg * num(power as i64)),
+            base => push(base, power, &mut collected),
+        }
+    }
+    if coeff.is_zero() {
+        return num(0);
+    }
+
+    let exponent = sum(exponent);
+    if !exponent.is_zero() {
+        collected.push((apply(Func::Exp, exponent), 1));
+    }
+    let mut factors = collected
+        .into_iter()
+        .filter(|(_, power)| *power != 0)
+        .collect::<Vec<_>>();
+    factors.sort_by_cached_key(|(base, power)| (base.rank(), base.to_string(), *power));
+
+    match factors.as_slice() {
+        [] => rational(coeff),
+        [(_, 1)] if coeff.is_one() => factors.pop().unwrap().0,
+        [(Expr::Sum(summands), 1)] => sum(summands.iter().map(|s| rational(coeff) * s.clone())),
+        _ => Expr::Product(coeff, factors),
+    }
+}
+
+impl Expr {
+    pub fn is_zero(&self) -> bool {
+        matches!(self, Expr::Number(q) if q.is_zero())
+    }
+
+    /// `self^n`, `1/self^(-n)` for negative `n`.
+    pub fn pow(self, n: i32) -> Expr {
+        product([(self, n)])
+    }
+
+    /// The summands of a sum, the expression itself otherwise.
+    pub fn summands(self) -> Vec<Expr> {
+        match self {
+            Expr::Sum(summands) => summands,
+            Expr::Number(q) if q.is_zero() => vec![],
+            other => vec![other],
+        }
+    }
+
+    /// `(c, e)` with `self = c e`.
+    fn split_coefficient(self) -> (Q, Expr) {
+        match self {
+            Expr::Number(q) => (q, num(1)),
+            Expr::Product(coeff, factors) => (coeff, product(factors)),
+            other => (Q::one(), other),
+        }
+    }
+
+    /// Order of the factors in a product: `x^2 e^x sin(x) ln(x)`.
+    fn rank(&self) -> u8 {
+        match self {
+            Expr::Number(_) => 0,
+            Expr::Var(_) => 1,
+            Expr::Apply(Func::Exp, _) => 2,
+            Expr::Apply(Func::Sin | Func::Cos | Func::Tg | Func::Ctg, _) => 3,
+            Expr::Apply(_, _) => 4,
+            Expr::Sum(_) | Expr::Product(_, _) => 5,
+        }
+    }
+
+    /// The value for the given values of the variables, `NaN` if a variable is
+    /// missing.
+    pub fn value(&self, vars: &[(&str, f64)]) -> f64 {
+        match self {
+            Expr::Number(q) => *q.numer() as f64 / *q.denom() as f64,
+            Expr::Var(name) => vars
+                .iter()
+                .find(|(var, _)| var == name)
+                .map_or(f64::NAN, |(_, value)| *value),
+            Expr::Sum(summands) => summands.iter().map(|s| s.value(vars)).sum(),
+            Expr::Product(coeff, factors) => factors.iter().fold(
+                *coeff.numer() as f64 / *coeff.denom() as f64,
+                |acc, (base, power)| acc * base.value(vars).powi(*power),
+            ),
+            Expr::Apply(func, arg) => {
+                let t = arg.value(vars);
+                match func {
+                    Func::Exp => t.exp(),
+                    Func::Ln => t.ln(),
+                    Func::LnAbs => t.abs().ln(),
+                    Func::Sin => t.sin(),
+                    Func::Cos => t.cos(),
+                    Func::Tg => t.tan(),
+                    Func::Ctg => 1. / t.tan(),
+                    Func::Arctan => t.atan(),
+                }
+            }
+        }
+    }
+
+    /// `-self` if it is printed with a leading minus.
+    fn negated(&self) -> Option<Expr> {
+        match self {
+            Expr::Number(q) if q.is_negative() => Some(Expr::Number(-q)),
+            Expr::Product(coeff, factors) if coeff.is_negative() => {
+                Some(Expr::Product(-coeff, factors.clone()))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn fmt_number(q: &Q, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if q.is_integer() {
+        write!(f, "{}", q.numer())
+    } else {
+        write!(f, "{}/{}", q.numer(), q.denom())
+    }
+}
+
+/// `base^power` for a positive power as a factor of a product.
+fn fmt_factor(base: &Expr, power: i32, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match (base, power) {
+        (Expr::Sum(_), 1) => write!(f, "({base})"),
+        (_, 1) => write!(f, "{base}"),
+        (Expr::Var(name), n) => write!(f, "{name}^{n}"),
+        (Expr::Apply(func @ (Func::Sin | Func::Cos | Func::Tg | Func::Ctg), arg), n) => {
+            write!(f, "{}^{n}({arg})", func.name())
+        }
+        (base, n) => write!(f, "({base})^{n}"),
+    }
+}
+
+/// A side of a fraction in parentheses, unless it is a number, a variable or a sum
+/// in its own parentheses.
+fn fmt_side(coeff: i64, factors: &[(&Expr, i32)], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if let (_, []) | (1, [(Expr::Var(_) | Expr::Sum(_), 1)]) = (coeff, factors) {
+        return fmt_factors(coeff, factors, f);
+    }
+    write!(f, "(")?;
+    fmt_factors(coeff, factors, f)?;
+    write!(f, ")")
+}
+
+/// The factors separated by spaces, after the coefficient unless it is `1`.
+fn fmt_factors(coeff: i64, factors: &[(&Expr, i32)], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if coeff != 1 || factors.is_empty() {
+        write!(f, "{coeff}")?;
+        if !factors.is_empty() {
+            write!(f, " ")?;
+        }
+    }
+    for (i, (base, power)) in factors.iter().enumerate() {
+        if i > 0 {
+            write!(f, " ")?;
+        }
+        fmt_factor(base, *power, f)?;
+    }
+    Ok(())
+}
+
+impl Func {
+    fn name(self) -> &'static str {
+        match self {
+            Func::Exp => "exp",
+            Func::Ln => "ln",
+            Func::LnAbs => "ln abs",
+            Func::Sin => "sin",
+            Func::Cos => "cos",
+            Func::Tg => "tg",
+            Func::Ctg => "ctg",
+            Func::Arctan => "arctan",
+        }
+    }
+}
+
+/// Typst markup.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(positive) = self.negated() {
+            return write!(f, "-{positive}");
+        }
+        match self {
+            Expr::Number(q) => fmt_number(q, f),
+            Expr::Var(name) => write!(f, "{name}"),
+            Expr::Sum(summands) => {
+                for (i, summand) in summands.iter().enumerate() {
+                    match (i, summand.negated()) {
+                        (0, _) => write!(f, "{summand}")?,
+                        (_, Some(positive)) => write!(f, "-{positive}")?,
+                        (_, None) => write!(f, "+{summand}")?,
+                    }
+                }
+                Ok(())
+            }
+            Expr::Product(coeff, factors) => {
+                let numerator = factors
+                    .iter()
+                    .filter(|(_, power)| *power > 0)
+                    .map(|(base, power)| (base, *power))
+                    .collect::<Vec<_>>();
+                let denominator = factors
+                    .iter()
+                    .filter(|(_, power)| *power < 0)
+                    .map(|(base, power)| (base, -power))
+                    .collect::<Vec<_>>();
+                if denominator.is_empty() {
+                    if !coeff.is_integer() {
+                        fmt_number(coeff, f)?;
+                        write!(f, " ")?;
+                        return fmt_factors(1, &numerator, f);
+                    }
+                    return fmt_factors(*coeff.numer(), &numerator, f);
+                }
+                fmt_side(*coeff.numer(), &numerator, f)?;
+                write!(f, "/")?;
+                fmt_side(*coeff.denom(), &denominator, f)
+            }
+            Expr::Apply(Func::Exp, arg) => match **arg {
+                Expr::Var(name) => write!(f, "e^{name}"),
+                _ => write!(f, "e^({arg})"),
+            },
+            Expr::Apply(func, arg) => write!(f, "{}({arg})", func.name()),
+        }
+    }
+}
+
+impl Add for Expr {
+    type Output = Expr;
+
+    fn add(self, other: Expr) -> Expr {
+        sum([self, other])
+    }
+}
+
+impl Sub for Expr {
+    type Output = Expr;
+
+    fn sub(self, other: Expr) -> Expr {
+        sum([self, -other])
+    }
+}
+
+impl Mul for Expr {
+    type Output = Expr;
+
+    fn mul(self, other: Expr) -> Expr {
+        product([(self, 1), (other, 1)])
+    }
+}
+
+impl Div for Expr {
+    type Output = Expr;
+
+    fn div(self, other: Expr) -> Expr {
+        product([(self, 1), (other, -1)])
+    }
+}
+
+impl Neg for Expr {
+    type Output = Expr;
+
+    fn neg(self) -> Expr {
+        num(-1) * self
+    }
+}
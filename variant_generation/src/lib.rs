@@ -1,3 +1,5 @@
+pub mod expr;
+
 use std::{
     io::{self, Read, Write},
     process::Command,
@@ -10,6 +12,14 @@ use serde::{Deserialize, Serialize};
 pub struct VariantGeneratorInput {
     pub variant_number: i32,
     pub generator: String,
+    /// Generator-specific settings of the group assignment, e.g. the difficulty
+    /// (`null` when missing).
+    #[serde(default)]
+    pub parameters: serde_json::Value,
+    /// Fingerprints of the variants already issued for the group assignment, see
+    /// `least_overlapping`.
+    #[serde(default)]
+    pub issued_fingerprints: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -20,6 +30,10 @@ pub struct VariantGeneratorOutput {
     pub problem_images: Vec<String>, // Base64-encoded PNGs
     pub solution_code: String,
     pub solution_images: Vec<String>, // Base64-encoded PNGs
+    /// Canonical fingerprint of the task choices, see `fingerprint`. `None` from
+    /// generators that do not track their choices.
+    #[serde(default)]
+    pub fingerprint: Option<String>,
 }
 
 impl VariantGeneratorInput {
@@ -50,6 +64,34 @@ impl VariantGeneratorOutput {
     }
 }
 
+/// Canonical fingerprint of a variant: the choice made for every task, in order,
+/// e.g. the index of the chosen alternative.
+pub fn fingerprint<S: ToString>(choices: &[S]) -> String {
+    let choices = choices.iter().map(ToString::to_string).collect::<Vec<_>>();
+    serde_json::to_string(&choices).expect("strings serialize")
+}
+
+/// Number of tasks with the same choice in both variants. Fingerprints that fail
+/// to parse share nothing.
+pub fn overlap(a: &str, b: &str) -> usize {
+    let parse = |fingerprint| serde_json::from_str::<Vec<String>>(fingerprint).unwrap_or_default();
+    parse(a)
+        .iter()
+        .zip(parse(b).iter())
+        .filter(|(a, b)| a == b)
+        .count()
+}
+
+/// The candidate, given with its fingerprint, sharing the fewest tasks with the issued
+/// variants: the least overlap with the closest of them, then the least total overlap.
+/// Ties go to the earlier candidate, so random candidates keep the choice random.
+pub fn least_overlapping<T>(candidates: Vec<(String, T)>, issued: &[String]) -> Option<(String, T)> {
+    candidates.into_iter().min_by_key(|(fingerprint, _)| {
+        let overlaps = issued.iter().map(|other| overlap(fingerprint, other));
+        (overlaps.clone().max().unwrap_or(0), overlaps.sum::<usize>())
+    })
+}
+
 fn double_braced(key: &str) -> String {
     format!("{{{{{}}}}}", key)
 }